@@ -24,6 +24,10 @@ pub enum DbType {
     Foxlake,
     #[strum(serialize = "tidb")]
     Tidb,
+    #[strum(serialize = "sqs")]
+    Sqs,
+    #[strum(serialize = "rabbitmq")]
+    RabbitMq,
 }
 
 #[derive(Display, EnumString, IntoStaticStr, Debug, Clone)]
@@ -38,6 +42,8 @@ pub enum ExtractType {
     Struct,
     #[strum(serialize = "snapshot_file")]
     SnapshotFile,
+    #[strum(serialize = "aof_file")]
+    AofFile,
     #[strum(serialize = "scan")]
     Scan,
     #[strum(serialize = "reshard")]
@@ -64,6 +70,10 @@ pub enum SinkType {
     Push,
     #[strum(serialize = "merge")]
     Merge,
+    #[strum(serialize = "snapshot_file")]
+    SnapshotFile,
+    #[strum(serialize = "stream")]
+    Stream,
 }
 
 #[derive(EnumString, IntoStaticStr, Clone, Display)]
@@ -103,6 +113,62 @@ pub enum ConflictPolicyEnum {
     #[default]
     #[strum(serialize = "interrupt")]
     Interrupt,
+    // only supported by the redis sinker
+    #[strum(serialize = "replace")]
+    Replace,
+}
+
+#[derive(Clone, Debug, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum KafkaAssignment {
+    // partitions are assigned by the task
+    #[default]
+    #[strum(serialize = "manual")]
+    Manual,
+    // partitions are assigned by the consumer group
+    #[strum(serialize = "group")]
+    Group,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum MessageFormat {
+    // the avro record of ape_dts
+    #[default]
+    #[strum(serialize = "avro")]
+    Avro,
+    #[strum(serialize = "debezium_json")]
+    DebeziumJson,
+    #[strum(serialize = "canal_json")]
+    CanalJson,
+    #[strum(serialize = "maxwell_json")]
+    MaxwellJson,
+    // typed avro records of tables, registered in schema registry, in confluent wire format
+    #[strum(serialize = "confluent_avro")]
+    ConfluentAvro,
+    // json of redis cmds, only for redis sources
+    #[strum(serialize = "json")]
+    Json,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum KafkaPartitionBy {
+    // murmur2 of the message key, same as the default partitioner of the Java client
+    #[default]
+    #[strum(serialize = "key")]
+    Key,
+    #[strum(serialize = "table")]
+    Table,
+    #[strum(serialize = "column")]
+    Column,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum SqsMessageGroupBy {
+    // messages of the same primary / unique key are in the same message group
+    #[default]
+    #[strum(serialize = "key")]
+    Key,
+    #[strum(serialize = "table")]
+    Table,
 }
 
 #[derive(Display, EnumString, IntoStaticStr, PartialEq)]
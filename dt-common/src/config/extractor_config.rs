@@ -1,5 +1,7 @@
+use std::collections::HashMap;
+
 use super::{
-    config_enums::{DbType, ExtractType},
+    config_enums::{DbType, ExtractType, KafkaAssignment, MessageFormat},
     s3_config::S3Config,
 };
 
@@ -85,6 +87,10 @@ pub enum ExtractorConfig {
         start_timestamp: u32,
         // op_log, change_stream
         source: String,
+        // for change_stream: off, update_lookup, when_available, required
+        full_document: String,
+        // for change_stream: off, when_available, required
+        full_document_before_change: String,
         heartbeat_interval_secs: u64,
         heartbeat_tb: String,
     },
@@ -99,6 +105,7 @@ pub enum ExtractorConfig {
     RedisSnapshot {
         url: String,
         repl_port: u64,
+        is_cluster: bool,
     },
 
     RedisCdc {
@@ -110,12 +117,24 @@ pub enum ExtractorConfig {
         heartbeat_interval_secs: u64,
         heartbeat_key: String,
         now_db_id: i64,
+        is_cluster: bool,
+    },
+
+    RedisCheck {
+        url: String,
+        check_log_dir: String,
+        batch_size: usize,
+        is_cluster: bool,
     },
 
     RedisSnapshotFile {
         file_path: String,
     },
 
+    RedisAofFile {
+        aof_dir: String,
+    },
+
     RedisScan {
         url: String,
         scan_count: u64,
@@ -124,15 +143,25 @@ pub enum ExtractorConfig {
 
     RedisReshard {
         url: String,
+        node_weights: String,
+        target_slot_map: String,
+        plan_file: String,
+        dry_run: bool,
     },
 
     Kafka {
         url: String,
         group: String,
+        // topics separated by ',', or a regex starting with '^'
         topic: String,
+        // -1 for all partitions
         partition: i32,
         offset: i64,
+        assignment: KafkaAssignment,
         ack_interval_secs: u64,
+        message_format: MessageFormat,
+        // client properties of librdkafka, from configs like: kafka.security.protocol=SASL_SSL
+        properties: HashMap<String, String>,
     },
 
     FoxlakeS3 {
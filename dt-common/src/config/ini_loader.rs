@@ -1,4 +1,4 @@
-use std::{any::type_name, fmt::Debug, fs::File, io::Read, str::FromStr};
+use std::{any::type_name, collections::HashMap, fmt::Debug, fs::File, io::Read, str::FromStr};
 
 use anyhow::bail;
 use configparser::ini::Ini;
@@ -58,6 +58,22 @@ impl IniLoader {
         default
     }
 
+    /// Get all non-empty configs in the section whose keys start with prefix, with prefix trimmed
+    /// E.g. kafka.security.protocol=SASL_SSL -> security.protocol: SASL_SSL
+    pub fn get_with_prefix(&self, section: &str, prefix: &str) -> HashMap<String, String> {
+        let mut results = HashMap::new();
+        if let Some(configs) = self.ini.get_map_ref().get(section) {
+            for (key, value) in configs.iter() {
+                if let (Some(key), Some(value)) = (key.strip_prefix(prefix), value) {
+                    if !value.is_empty() {
+                        results.insert(key.to_string(), value.to_string());
+                    }
+                }
+            }
+        }
+        results
+    }
+
     fn parse_value<T>(section: &str, key: &str, value: &str) -> anyhow::Result<T>
     where
         T: FromStr,
@@ -1,4 +1,4 @@
-use super::config_enums::PipelineType;
+use super::config_enums::{MessageFormat, PipelineType};
 
 #[derive(Clone)]
 pub struct PipelineConfig {
@@ -14,4 +14,5 @@ pub struct PipelineConfig {
     pub http_host: String,
     pub http_port: u64,
     pub with_field_defs: bool,
+    pub message_format: MessageFormat,
 }
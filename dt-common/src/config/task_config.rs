@@ -9,8 +9,8 @@ use crate::error::Error;
 
 use super::{
     config_enums::{
-        ConflictPolicyEnum, DbType, ExtractType, MetaCenterType, ParallelType, PipelineType,
-        SinkType,
+        ConflictPolicyEnum, DbType, ExtractType, MessageFormat, MetaCenterType, ParallelType,
+        PipelineType, SinkType,
     },
     data_marker_config::DataMarkerConfig,
     extractor_config::{BasicExtractorConfig, ExtractorConfig},
@@ -70,6 +70,7 @@ const REVERSE: &str = "reverse";
 const REPL_PORT: &str = "repl_port";
 const PARALLEL_SIZE: &str = "parallel_size";
 const DDL_CONFLICT_POLICY: &str = "ddl_conflict_policy";
+const KAFKA_PROPERTY_PREFIX: &str = "kafka.";
 // default values
 const APE_DTS: &str = "APE_DTS";
 const ASTRISK: &str = "*";
@@ -83,6 +84,7 @@ impl TaskConfig {
         let resumer = Self::load_resumer_config(&loader, &runtime)?;
         let (extractor_basic, extractor) = Self::load_extractor_config(&loader, &pipeline)?;
         let (sinker_basic, sinker) = Self::load_sinker_config(&loader)?;
+        Self::validate_message_format(&extractor_basic, &sinker, &pipeline)?;
         Ok(Self {
             extractor_basic,
             extractor,
@@ -235,6 +237,16 @@ impl TaskConfig {
                         resume_token: loader.get_optional(EXTRACTOR, "resume_token"),
                         start_timestamp: loader.get_optional(EXTRACTOR, "start_timestamp"),
                         source: loader.get_optional(EXTRACTOR, "source"),
+                        full_document: loader.get_with_default(
+                            EXTRACTOR,
+                            "full_document",
+                            "update_lookup".to_string(),
+                        ),
+                        full_document_before_change: loader.get_with_default(
+                            EXTRACTOR,
+                            "full_document_before_change",
+                            "when_available".to_string(),
+                        ),
                         heartbeat_interval_secs,
                         heartbeat_tb,
                     },
@@ -253,13 +265,21 @@ impl TaskConfig {
             DbType::Redis => match extract_type {
                 ExtractType::Snapshot => {
                     let repl_port = loader.get_with_default(EXTRACTOR, REPL_PORT, 10008);
-                    ExtractorConfig::RedisSnapshot { url, repl_port }
+                    ExtractorConfig::RedisSnapshot {
+                        url,
+                        repl_port,
+                        is_cluster: loader.get_optional(EXTRACTOR, "is_cluster"),
+                    }
                 }
 
                 ExtractType::SnapshotFile => ExtractorConfig::RedisSnapshotFile {
                     file_path: loader.get_required(EXTRACTOR, "file_path"),
                 },
 
+                ExtractType::AofFile => ExtractorConfig::RedisAofFile {
+                    aof_dir: loader.get_required(EXTRACTOR, "aof_dir"),
+                },
+
                 ExtractType::Scan => ExtractorConfig::RedisScan {
                     url,
                     statistic_type: loader.get_required(EXTRACTOR, "statistic_type"),
@@ -277,22 +297,49 @@ impl TaskConfig {
                         heartbeat_interval_secs,
                         heartbeat_key: loader.get_optional(EXTRACTOR, "heartbeat_key"),
                         now_db_id: loader.get_optional(EXTRACTOR, "now_db_id"),
+                        is_cluster: loader.get_optional(EXTRACTOR, "is_cluster"),
                     }
                 }
 
-                ExtractType::Reshard => ExtractorConfig::RedisReshard { url },
+                ExtractType::Reshard => ExtractorConfig::RedisReshard {
+                    url,
+                    node_weights: loader.get_optional(EXTRACTOR, "node_weights"),
+                    target_slot_map: loader.get_optional(EXTRACTOR, "target_slot_map"),
+                    plan_file: loader.get_optional(EXTRACTOR, "plan_file"),
+                    dry_run: loader.get_optional(EXTRACTOR, "dry_run"),
+                },
+
+                ExtractType::CheckLog => ExtractorConfig::RedisCheck {
+                    url,
+                    check_log_dir: loader.get_required(EXTRACTOR, CHECK_LOG_DIR),
+                    batch_size: loader.get_with_default(EXTRACTOR, BATCH_SIZE, 200),
+                    is_cluster: loader.get_optional(EXTRACTOR, "is_cluster"),
+                },
 
                 _ => bail! { not_supported_err },
             },
 
-            DbType::Kafka => ExtractorConfig::Kafka {
-                url,
-                group: loader.get_required(EXTRACTOR, "group"),
-                topic: loader.get_required(EXTRACTOR, "topic"),
-                partition: loader.get_optional(EXTRACTOR, "partition"),
-                offset: loader.get_optional(EXTRACTOR, "offset"),
-                ack_interval_secs: loader.get_optional(EXTRACTOR, "ack_interval_secs"),
-            },
+            DbType::Kafka => {
+                let message_format: MessageFormat =
+                    loader.get_optional(EXTRACTOR, "message_format");
+                if message_format == MessageFormat::ConfluentAvro {
+                    bail! {Error::ConfigError(
+                        "message_format: confluent_avro is not supported by kafka extractor".into()
+                    )}
+                }
+
+                ExtractorConfig::Kafka {
+                    url,
+                    group: loader.get_required(EXTRACTOR, "group"),
+                    topic: loader.get_required(EXTRACTOR, "topic"),
+                    partition: loader.get_optional(EXTRACTOR, "partition"),
+                    offset: loader.get_optional(EXTRACTOR, "offset"),
+                    assignment: loader.get_optional(EXTRACTOR, "assignment"),
+                    ack_interval_secs: loader.get_optional(EXTRACTOR, "ack_interval_secs"),
+                    message_format,
+                    properties: loader.get_with_prefix(EXTRACTOR, KAFKA_PROPERTY_PREFIX),
+                }
+            }
 
             db_type => {
                 bail! {Error::ConfigError(format!(
@@ -322,6 +369,11 @@ impl TaskConfig {
 
         let conflict_policy: ConflictPolicyEnum =
             loader.get_with_default(SINKER, "conflict_policy", ConflictPolicyEnum::Interrupt);
+        if conflict_policy == ConflictPolicyEnum::Replace && db_type != DbType::Redis {
+            bail! {Error::ConfigError(
+                "conflict_policy=replace is only supported by the redis sinker".into()
+            )}
+        }
 
         let not_supported_err =
             Error::ConfigError(format!("sinker db type: {} not supported", db_type));
@@ -377,6 +429,7 @@ impl TaskConfig {
                         url,
                         app_name,
                         batch_size,
+                        nest_map: loader.get_optional(SINKER, "nest_map"),
                     },
 
                     SinkType::Check => SinkerConfig::MongoCheck {
@@ -384,19 +437,96 @@ impl TaskConfig {
                         app_name,
                         batch_size,
                         check_log_dir: loader.get_optional(SINKER, CHECK_LOG_DIR),
+                        check_extra: loader.get_optional(SINKER, "check_extra"),
                     },
 
                     _ => bail! { not_supported_err },
                 }
             }
 
-            DbType::Kafka => SinkerConfig::Kafka {
-                url,
-                batch_size,
-                ack_timeout_secs: loader.get_with_default(SINKER, "ack_timeout_secs", 5),
-                required_acks: loader.get_with_default(SINKER, "required_acks", "one".to_string()),
-                with_field_defs: loader.get_with_default(SINKER, "with_field_defs", true),
-            },
+            DbType::Kafka => {
+                let message_format: MessageFormat = loader.get_optional(SINKER, "message_format");
+                let schema_registry_url: String =
+                    loader.get_optional(SINKER, "schema_registry_url");
+                if message_format == MessageFormat::ConfluentAvro && schema_registry_url.is_empty()
+                {
+                    bail! {Error::ConfigError(
+                        "schema_registry_url is required for message_format: confluent_avro".into()
+                    )}
+                }
+
+                SinkerConfig::Kafka {
+                    url,
+                    batch_size,
+                    ack_timeout_secs: loader.get_with_default(SINKER, "ack_timeout_secs", 5),
+                    required_acks: loader.get_with_default(
+                        SINKER,
+                        "required_acks",
+                        "one".to_string(),
+                    ),
+                    with_field_defs: loader.get_with_default(SINKER, "with_field_defs", true),
+                    message_format,
+                    tombstones_on_delete: loader.get_with_default(
+                        SINKER,
+                        "tombstones_on_delete",
+                        true,
+                    ),
+                    partition_by: loader.get_optional(SINKER, "partition_by"),
+                    partition_cols: loader.get_optional(SINKER, "partition_cols"),
+                    properties: loader.get_with_prefix(SINKER, KAFKA_PROPERTY_PREFIX),
+                    schema_registry_url,
+                    transactional_id: loader.get_optional(SINKER, "transactional_id"),
+                    offsets_topic: loader.get_with_default(
+                        SINKER,
+                        "offsets_topic",
+                        "ape_dts_offsets".to_string(),
+                    ),
+                }
+            }
+
+            DbType::Sqs => {
+                let message_format: MessageFormat = loader.get_optional(SINKER, "message_format");
+                if message_format == MessageFormat::ConfluentAvro {
+                    bail! {Error::ConfigError(
+                        "message_format: confluent_avro is not supported by sqs sinker".into()
+                    )}
+                }
+
+                SinkerConfig::Sqs {
+                    url,
+                    batch_size,
+                    with_field_defs: loader.get_with_default(SINKER, "with_field_defs", true),
+                    message_format,
+                    message_group_by: loader.get_optional(SINKER, "message_group_by"),
+                    max_retries: loader.get_with_default(SINKER, "max_retries", 3),
+                    region: loader.get_optional(SINKER, "sqs_region"),
+                    endpoint: loader.get_optional(SINKER, "sqs_endpoint"),
+                    access_key: loader.get_optional(SINKER, "sqs_access_key"),
+                    secret_key: loader.get_optional(SINKER, "sqs_secret_key"),
+                }
+            }
+
+            DbType::RabbitMq => {
+                let message_format: MessageFormat = loader.get_optional(SINKER, "message_format");
+                if message_format == MessageFormat::ConfluentAvro {
+                    bail! {Error::ConfigError(
+                        "message_format: confluent_avro is not supported by rabbitmq sinker".into()
+                    )}
+                }
+
+                SinkerConfig::RabbitMq {
+                    url,
+                    batch_size,
+                    with_field_defs: loader.get_with_default(SINKER, "with_field_defs", true),
+                    message_format,
+                    exchange: loader.get_optional(SINKER, "exchange"),
+                    routing_key: loader.get_with_default(
+                        SINKER,
+                        "routing_key",
+                        "{schema}.{tb}.{operation}".to_string(),
+                    ),
+                }
+            }
 
             DbType::Redis => match sink_type {
                 SinkType::Write => SinkerConfig::Redis {
@@ -404,6 +534,25 @@ impl TaskConfig {
                     batch_size,
                     method: loader.get_optional(SINKER, "method"),
                     is_cluster: loader.get_optional(SINKER, "is_cluster"),
+                    rewrite_chunk_size: loader.get_with_default(SINKER, "rewrite_chunk_size", 1000),
+                    max_restore_bytes: loader.get_with_default(
+                        SINKER,
+                        "max_restore_bytes",
+                        100 * 1024 * 1024,
+                    ),
+                    // keys in target are overwritten by default, same as RESTORE with REPLACE
+                    conflict_policy: loader.get_with_default(
+                        SINKER,
+                        "conflict_policy",
+                        ConflictPolicyEnum::Replace,
+                    ),
+                },
+
+                SinkType::Check => SinkerConfig::RedisCheck {
+                    url,
+                    batch_size,
+                    check_log_dir: loader.get_optional(SINKER, CHECK_LOG_DIR),
+                    is_cluster: loader.get_optional(SINKER, "is_cluster"),
                 },
 
                 SinkType::Statistic => SinkerConfig::RedisStatistic {
@@ -411,6 +560,42 @@ impl TaskConfig {
                     data_size_threshold: loader.get_optional(SINKER, "data_size_threshold"),
                     freq_threshold: loader.get_optional(SINKER, "freq_threshold"),
                     statistic_log_dir: loader.get_optional(SINKER, "statistic_log_dir"),
+                    top_n: loader.get_with_default(SINKER, "top_n", 100),
+                    prefix_delimiter: loader.get_with_default(
+                        SINKER,
+                        "prefix_delimiter",
+                        ":".to_string(),
+                    ),
+                    report_interval_secs: loader.get_with_default(
+                        SINKER,
+                        "report_interval_secs",
+                        60,
+                    ),
+                },
+
+                SinkType::Stream => {
+                    let message_format: MessageFormat =
+                        loader.get_optional(SINKER, "message_format");
+                    if message_format == MessageFormat::ConfluentAvro {
+                        bail! {Error::ConfigError(
+                            "message_format: confluent_avro is not supported by redis stream sinker"
+                                .into()
+                        )}
+                    }
+
+                    SinkerConfig::RedisStream {
+                        url,
+                        batch_size,
+                        with_field_defs: loader.get_with_default(SINKER, "with_field_defs", true),
+                        message_format,
+                        max_len: loader.get_optional(SINKER, "max_len"),
+                        position_key: loader.get_optional(SINKER, "position_key"),
+                    }
+                }
+
+                SinkType::SnapshotFile => SinkerConfig::RedisSnapshotFile {
+                    file_path: loader.get_required(SINKER, "file_path"),
+                    rdb_version: loader.get_with_default(SINKER, "rdb_version", 9),
                 },
 
                 _ => bail! { not_supported_err },
@@ -494,6 +679,42 @@ impl TaskConfig {
         Ok((basic, sinker))
     }
 
+    /// Redis entries are sent as cmds in avro or json, and json is only for redis entries
+    fn validate_message_format(
+        extractor_basic: &BasicExtractorConfig,
+        sinker: &SinkerConfig,
+        pipeline: &PipelineConfig,
+    ) -> anyhow::Result<()> {
+        let mut message_formats = Vec::new();
+        match sinker {
+            SinkerConfig::Kafka { message_format, .. }
+            | SinkerConfig::Sqs { message_format, .. }
+            | SinkerConfig::RabbitMq { message_format, .. }
+            | SinkerConfig::RedisStream { message_format, .. } => {
+                message_formats.push(message_format)
+            }
+            _ => {}
+        }
+        if let PipelineType::HttpServer = pipeline.pipeline_type {
+            message_formats.push(&pipeline.message_format);
+        }
+
+        for message_format in message_formats {
+            let supported = if extractor_basic.db_type == DbType::Redis {
+                matches!(message_format, MessageFormat::Avro | MessageFormat::Json)
+            } else {
+                *message_format != MessageFormat::Json
+            };
+            if !supported {
+                bail! {Error::ConfigError(format!(
+                    "message_format: {} is not supported for extractor db_type: {}",
+                    message_format, extractor_basic.db_type
+                ))}
+            }
+        }
+        Ok(())
+    }
+
     fn load_parallelizer_config(loader: &IniLoader) -> anyhow::Result<ParallelizerConfig> {
         Ok(ParallelizerConfig {
             parallel_size: loader.get_with_default(PARALLELIZER, PARALLEL_SIZE, 1),
@@ -522,6 +743,7 @@ impl TaskConfig {
             http_host: loader.get_with_default(PIPELINE, "http_host", "0.0.0.0".to_string()),
             http_port: loader.get_with_default(PIPELINE, "http_port", 10231),
             with_field_defs: loader.get_with_default(PIPELINE, "with_field_defs", true),
+            message_format: loader.get_optional(PIPELINE, "message_format"),
         };
 
         if config.counter_time_window_secs == 0 {
@@ -553,6 +775,9 @@ impl TaskConfig {
             do_ddls: loader.get_optional(FILTER, "do_ddls"),
             do_structures: loader.get_with_default(FILTER, "do_structures", ASTRISK.to_string()),
             ignore_cmds: loader.get_optional(FILTER, "ignore_cmds"),
+            do_keys: loader.get_optional(FILTER, "do_keys"),
+            ignore_keys: loader.get_optional(FILTER, "ignore_keys"),
+            ignore_key_types: loader.get_optional(FILTER, "ignore_key_types"),
         })
     }
 
@@ -562,6 +787,7 @@ impl TaskConfig {
             tb_map: loader.get_optional(ROUTER, "tb_map"),
             col_map: loader.get_optional(ROUTER, "col_map"),
             topic_map: loader.get_optional(ROUTER, "topic_map"),
+            key_map: loader.get_optional(ROUTER, "key_map"),
         })
     }
 
@@ -1,3 +1,4 @@
+pub mod mongo_col_value_convertor;
 pub mod mysql_col_value_convertor;
 pub mod pg_col_value_convertor;
 pub mod sqlx_ext;
@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use mongodb::bson::{spec::BinarySubtype, Binary, Bson, DateTime, Decimal128};
+
+use crate::{meta::col_value::ColValue, utils::time_util::TimeUtil};
+
+pub struct MongoColValueConvertor {}
+
+impl MongoColValueConvertor {
+    pub fn to_bson(col_value: &ColValue) -> Bson {
+        match col_value {
+            ColValue::None => Bson::Null,
+            ColValue::Bool(v) => Bson::Boolean(*v),
+            ColValue::Tiny(v) => Bson::Int32(*v as i32),
+            ColValue::UnsignedTiny(v) => Bson::Int32(*v as i32),
+            ColValue::Short(v) => Bson::Int32(*v as i32),
+            ColValue::UnsignedShort(v) => Bson::Int32(*v as i32),
+            ColValue::Long(v) => Bson::Int32(*v),
+            ColValue::UnsignedLong(v) => Bson::Int64(*v as i64),
+            ColValue::LongLong(v) => Bson::Int64(*v),
+            ColValue::UnsignedLongLong(v) => {
+                // values beyond i64::MAX would overflow Int64, keep them exact in Decimal128
+                if *v <= i64::MAX as u64 {
+                    Bson::Int64(*v as i64)
+                } else {
+                    Self::decimal_to_bson(&v.to_string())
+                }
+            }
+            // f32 -> f64 conversion introduces noise digits (1234.12 -> 1234.1199951171875),
+            // convert from the shortest string representation instead
+            ColValue::Float(v) => Bson::Double(v.to_string().parse().unwrap_or(*v as f64)),
+            ColValue::Double(v) => Bson::Double(*v),
+            ColValue::Decimal(v) => Self::decimal_to_bson(v),
+            ColValue::Date(v) => match TimeUtil::date_from_str(v) {
+                Ok(date) => {
+                    let millis = date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis();
+                    Bson::DateTime(DateTime::from_millis(millis))
+                }
+                // invalid dates like 0000-00-00 in mysql
+                Err(_) => Bson::String(v.clone()),
+            },
+            ColValue::DateTime(v) | ColValue::Timestamp(v) => {
+                match TimeUtil::datetime_from_utc_str(v) {
+                    Ok(dt) => Bson::DateTime(DateTime::from_millis(dt.timestamp_millis())),
+                    Err(_) => Bson::String(v.clone()),
+                }
+            }
+            ColValue::Time(v) => Bson::String(v.clone()),
+            ColValue::Year(v) => Bson::Int32(*v as i32),
+            ColValue::String(v) | ColValue::Set2(v) | ColValue::Enum2(v) => Bson::String(v.clone()),
+            ColValue::RawString(v) => match String::from_utf8(v.clone()) {
+                Ok(str) => Bson::String(str),
+                Err(_) => Self::binary_to_bson(v),
+            },
+            ColValue::Blob(v) => Self::binary_to_bson(v),
+            ColValue::Bit(v) => Bson::Int64(*v as i64),
+            ColValue::Set(v) => Bson::Int64(*v as i64),
+            ColValue::Enum(v) => Bson::Int64(*v as i64),
+            ColValue::Json(v) => match serde_json::from_slice::<serde_json::Value>(v) {
+                Ok(json) => Self::json_to_bson(json),
+                Err(_) => Self::binary_to_bson(v),
+            },
+            ColValue::Json2(v) => match serde_json::from_str::<serde_json::Value>(v) {
+                Ok(json) => Self::json_to_bson(json),
+                Err(_) => Bson::String(v.clone()),
+            },
+            ColValue::Json3(v) => Self::json_to_bson(v.clone()),
+            ColValue::MongoDoc(v) => Bson::Document(v.clone()),
+        }
+    }
+
+    fn decimal_to_bson(v: &str) -> Bson {
+        match Decimal128::from_str(v) {
+            Ok(decimal) => Bson::Decimal128(decimal),
+            // NaN / Infinity in pg numeric are kept as strings
+            Err(_) => Bson::String(v.to_string()),
+        }
+    }
+
+    fn binary_to_bson(v: &[u8]) -> Bson {
+        Bson::Binary(Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: v.to_vec(),
+        })
+    }
+
+    fn json_to_bson(json: serde_json::Value) -> Bson {
+        let str = json.to_string();
+        // json values are embedded as documents / arrays instead of strings,
+        // fall back to string if the json can not be represented in bson (e.g. invalid extended json keys)
+        Bson::try_from(json).unwrap_or(Bson::String(str))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, Bson};
+
+    use super::*;
+
+    #[test]
+    fn test_to_bson() {
+        assert_eq!(
+            MongoColValueConvertor::to_bson(&ColValue::Long(10)),
+            Bson::Int32(10)
+        );
+        assert_eq!(
+            MongoColValueConvertor::to_bson(&ColValue::UnsignedLongLong(u64::MAX)),
+            Bson::Decimal128(Decimal128::from_str("18446744073709551615").unwrap())
+        );
+        assert_eq!(
+            MongoColValueConvertor::to_bson(&ColValue::Float(1234.12)),
+            Bson::Double(1234.12)
+        );
+        assert_eq!(
+            MongoColValueConvertor::to_bson(&ColValue::Decimal("123456.1234".into())),
+            Bson::Decimal128(Decimal128::from_str("123456.1234").unwrap())
+        );
+        assert_eq!(
+            MongoColValueConvertor::to_bson(&ColValue::DateTime(
+                "2022-01-02 03:04:05.123456".into()
+            )),
+            Bson::DateTime(DateTime::from_millis(1641092645123))
+        );
+        assert_eq!(
+            MongoColValueConvertor::to_bson(&ColValue::Date("2022-01-02".into())),
+            Bson::DateTime(DateTime::from_millis(1641081600000))
+        );
+        assert_eq!(
+            MongoColValueConvertor::to_bson(&ColValue::Date("0000-00-00".into())),
+            Bson::String("0000-00-00".into())
+        );
+        assert_eq!(
+            MongoColValueConvertor::to_bson(&ColValue::Blob(vec![1, 2, 3])),
+            Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: vec![1, 2, 3]
+            })
+        );
+        assert_eq!(
+            MongoColValueConvertor::to_bson(&ColValue::Json2(r#"{"k": [1, "v"]}"#.into())),
+            Bson::Document(doc! {"k": [1, "v"]})
+        );
+    }
+}
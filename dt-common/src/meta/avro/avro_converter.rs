@@ -1,22 +1,27 @@
 use std::{collections::HashMap, str::FromStr};
 
-use anyhow::Ok;
+use anyhow::{bail, Ok};
 use apache_avro::{from_avro_datum, to_avro_datum, types::Value, Schema};
 
 use crate::{
     config::config_enums::DbType,
+    error::Error,
     meta::{
         col_value::ColValue,
         ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
         dt_data::DtData,
         rdb_meta_manager::RdbMetaManager,
         rdb_tb_meta::RdbTbMeta,
+        redis::{redis_entry::RedisEntry, redis_object::RedisCmd},
         row_data::RowData,
         row_type::RowType,
     },
 };
 
-use super::avro_converter_schema::{AvroConverterSchema, AvroFieldDef};
+use super::{
+    avro_converter_schema::{AvroConverterSchema, AvroFieldDef},
+    avro_table_schema::AvroTableSchema,
+};
 
 #[derive(Clone)]
 pub struct AvroConverter {
@@ -36,6 +41,12 @@ const QUERY: &str = "query";
 const SCHEMA: &str = "schema";
 const TB: &str = "tb";
 const FIELDS: &str = "fields";
+const REDIS: &str = "redis";
+const CMD: &str = "cmd";
+const EXPIRE_MS: &str = "expire_ms";
+const POSITION: &str = "position";
+const KEY_PREFIX: &str = "key_";
+const ARG_PREFIX: &str = "arg_";
 
 impl AvroConverter {
     pub fn new(meta_manager: Option<RdbMetaManager>, with_field_defs: bool) -> Self {
@@ -54,25 +65,63 @@ impl AvroConverter {
         }
     }
 
+    /// The key is the value of the primary / unique key, e.g. 1, or a json array of values
+    /// for composite keys, e.g. ["1","a"]. It is empty for tables without keys
     pub async fn row_data_to_avro_key(&mut self, row_data: &RowData) -> anyhow::Result<String> {
+        let tb_meta = match self.get_tb_meta(row_data).await? {
+            Some(tb_meta) if !tb_meta.key_map.is_empty() => tb_meta,
+            _ => return Ok(String::new()),
+        };
+
+        let col_values = match row_data.row_type {
+            RowType::Insert => row_data.after.as_ref().unwrap(),
+            RowType::Update | RowType::Delete => row_data.before.as_ref().unwrap(),
+        };
+        let key_values: Vec<Option<String>> = tb_meta
+            .id_cols
+            .iter()
+            .map(|col| col_values.get(col).and_then(|v| v.to_option_string()))
+            .collect();
+
+        if key_values.len() == 1 {
+            return Ok(key_values[0].clone().unwrap_or_default());
+        }
+        Ok(serde_json::to_string(&key_values)?)
+    }
+
+    /// Whether any col of the message key is changed by the update
+    pub async fn is_key_changed(&mut self, row_data: &RowData) -> anyhow::Result<bool> {
+        if row_data.row_type != RowType::Update {
+            return Ok(false);
+        }
+
         if let Some(tb_meta) = self.get_tb_meta(row_data).await? {
-            let convert = |col_values: &HashMap<String, ColValue>| {
-                if let Some(col) = &tb_meta.order_col {
-                    if let Some(value) = col_values.get(col) {
-                        return value.to_option_string();
-                    }
+            if tb_meta.key_map.is_empty() {
+                return Ok(false);
+            }
+            let before = row_data.before.as_ref().unwrap();
+            let after = row_data.after.as_ref().unwrap();
+            for col in tb_meta.id_cols.iter() {
+                if before.get(col) != after.get(col) {
+                    return Ok(true);
                 }
-                None
-            };
-
-            if let Some(key) = match row_data.row_type {
-                RowType::Insert => convert(row_data.after.as_ref().unwrap()),
-                RowType::Update | RowType::Delete => convert(row_data.before.as_ref().unwrap()),
-            } {
-                return Ok(key);
             }
         }
-        Ok(String::new())
+        Ok(false)
+    }
+
+    /// The typed avro schema of the table, only available with the meta data of RDB extractor
+    pub async fn get_avro_table_schema(
+        &mut self,
+        row_data: &RowData,
+    ) -> anyhow::Result<AvroTableSchema> {
+        match self.get_tb_meta(row_data).await? {
+            Some(tb_meta) => AvroTableSchema::from_tb_meta(tb_meta),
+            None => bail! {Error::MetadataError(format!(
+                "no meta data to generate avro schema for: `{}`.`{}`",
+                row_data.schema, row_data.tb
+            ))},
+        }
     }
 
     pub async fn row_data_to_avro_value(&mut self, row_data: RowData) -> anyhow::Result<Vec<u8>> {
@@ -181,6 +230,44 @@ impl AvroConverter {
         Ok(to_avro_datum(&self.schema, value)?)
     }
 
+    /// A redis cmd is saved in extra, schema is the db id, e.g.
+    /// {"db_type": "redis", "cmd": "SET", "key_0": "k1", "arg_0": b"SET", "arg_1": b"k1", "arg_2": b"v1",
+    ///  "expire_ms": 0, "position": "..."}
+    pub fn redis_entry_to_avro_value(
+        &self,
+        entry: &RedisEntry,
+        position: &str,
+    ) -> anyhow::Result<Vec<u8>> {
+        let mut col_values: HashMap<String, ColValue> = HashMap::new();
+        col_values.insert(DB_TYPE.into(), ColValue::String(DbType::Redis.to_string()));
+        col_values.insert(CMD.into(), ColValue::String(entry.cmd.get_name()));
+        col_values.insert(EXPIRE_MS.into(), ColValue::LongLong(entry.expire_ms));
+        col_values.insert(POSITION.into(), ColValue::String(position.into()));
+        for (i, key) in entry.cmd.keys.iter().enumerate() {
+            col_values.insert(
+                format!("{}{}", KEY_PREFIX, i),
+                ColValue::String(key.clone()),
+            );
+        }
+        for (i, arg) in entry.cmd.args.iter().enumerate() {
+            col_values.insert(format!("{}{}", ARG_PREFIX, i), ColValue::Blob(arg.clone()));
+        }
+
+        let (avro_values, _) = Self::col_values_to_avro(&Some(col_values));
+        let extra = Value::Union(1, Box::new(avro_values));
+
+        let value = Value::Record(vec![
+            (SCHEMA.into(), Value::String(entry.db_id.to_string())),
+            (TB.into(), Value::String(String::new())),
+            (OPERATION.into(), Value::String(REDIS.into())),
+            (FIELDS.into(), Value::Union(0, Box::new(Value::Null))),
+            (BEFORE.into(), Value::Union(0, Box::new(Value::Null))),
+            (AFTER.into(), Value::Union(0, Box::new(Value::Null))),
+            (EXTRA.into(), extra),
+        ]);
+        Ok(to_avro_datum(&self.schema, value)?)
+    }
+
     pub fn avro_value_to_dt_data(&self, payload: Vec<u8>) -> anyhow::Result<DtData> {
         let mut reader = payload.as_slice();
         let value = from_avro_datum(&self.schema, &mut reader, None)?;
@@ -197,7 +284,14 @@ impl AvroConverter {
         let tb = avro_to_string(avro_map.remove(TB));
         let operation = avro_to_string(avro_map.remove(OPERATION));
 
-        if operation == *DDL {
+        if operation == *REDIS {
+            let extra = self
+                .avro_to_col_values(avro_map.remove(EXTRA))
+                .unwrap_or_default();
+            Ok(DtData::Redis {
+                entry: Self::col_values_to_redis_entry(&schema, extra)?,
+            })
+        } else if operation == *DDL {
             let get_extra_string = |extra: &Option<HashMap<String, ColValue>>, key: &str| {
                 if let Some(extra) = extra {
                     if let Some(v) = extra.get(key) {
@@ -229,6 +323,31 @@ impl AvroConverter {
         }
     }
 
+    fn col_values_to_redis_entry(
+        db_id: &str,
+        mut extra: HashMap<String, ColValue>,
+    ) -> anyhow::Result<RedisEntry> {
+        let mut args = Vec::new();
+        while let Some(ColValue::Blob(arg)) = extra.remove(&format!("{}{}", ARG_PREFIX, args.len()))
+        {
+            args.push(arg);
+        }
+        let mut keys = Vec::new();
+        while let Some(key) = extra.remove(&format!("{}{}", KEY_PREFIX, keys.len())) {
+            keys.push(key.to_string());
+        }
+
+        let mut entry = RedisEntry::new();
+        entry.db_id = db_id.parse()?;
+        entry.cmd = RedisCmd::from_args(args);
+        entry.cmd.keys = keys;
+        if let Some(ColValue::LongLong(expire_ms)) = extra.remove(EXPIRE_MS) {
+            entry.expire_ms = expire_ms;
+        }
+        entry.data_size = entry.cmd.get_malloc_size();
+        Ok(entry)
+    }
+
     fn avro_to_fields(&self, value: Option<Value>) -> Vec<AvroFieldDef> {
         if let Some(v) = value {
             return apache_avro::from_value(&v).unwrap();
@@ -435,6 +554,40 @@ mod tests {
         validate_ddl_data(&mut avro_converter, &ddl_data).await;
     }
 
+    #[test]
+    fn test_redis_entry_to_avro() {
+        let avro_converter = AvroConverter::new(None, false);
+
+        let mut entry = RedisEntry::new();
+        entry.db_id = 2;
+        entry.expire_ms = 1000;
+        entry.cmd = RedisCmd::from_args(vec![
+            b"MSET".to_vec(),
+            b"k1".to_vec(),
+            vec![0, 255, 1],
+            b"k2".to_vec(),
+            b"v2".to_vec(),
+        ]);
+        entry.cmd.keys = vec!["k1".into(), "k2".into()];
+
+        let payload = avro_converter
+            .redis_entry_to_avro_value(&entry, "position")
+            .unwrap();
+        let dt_data = avro_converter.avro_value_to_dt_data(payload).unwrap();
+        if let DtData::Redis {
+            entry: decoded_entry,
+        } = dt_data
+        {
+            assert_eq!(decoded_entry.db_id, entry.db_id);
+            assert_eq!(decoded_entry.expire_ms, entry.expire_ms);
+            assert_eq!(decoded_entry.cmd.args, entry.cmd.args);
+            assert_eq!(decoded_entry.cmd.keys, entry.cmd.keys);
+            assert!(!decoded_entry.is_base);
+        } else {
+            panic!()
+        }
+    }
+
     async fn validate_row_data(avro_converter: &mut AvroConverter, row_data: &RowData) {
         let payload = avro_converter
             .row_data_to_avro_value(row_data.clone())
@@ -0,0 +1,483 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use apache_avro::{to_avro_datum, types::Value, Decimal, Schema};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde_json::json;
+
+use crate::{
+    error::Error,
+    meta::{col_value::ColValue, rdb_tb_meta::RdbTbMeta, row_data::RowData},
+};
+
+// the max precision of decimals which can be held by i128
+const MAX_DECIMAL_PRECISION: u32 = 38;
+
+#[derive(Debug, Clone, PartialEq)]
+enum AvroColType {
+    Int,
+    Long,
+    Float,
+    Double,
+    Boolean,
+    Bytes,
+    String,
+    Decimal { precision: u32, scale: u32 },
+    Date,
+    TimeMicros,
+    TimestampMicros,
+}
+
+/// A typed avro record schema of a table, generated from RdbTbMeta, e.g.
+/// {"type": "record", "name": "Envelope", "namespace": "test_db_1.tb_1", "fields": [
+///   {"name": "schema", "type": "string"}, {"name": "tb", "type": "string"},
+///   {"name": "operation", "type": "string"},
+///   {"name": "before", "type": ["null", {"type": "record", "name": "Value", "fields": [
+///     {"name": "id", "type": ["null", "int"], "default": null}, ...]}], "default": null},
+///   {"name": "after", "type": ["null", "Value"], "default": null}]}
+///
+/// All cols are nullable with a default null, so adding / dropping cols by DDL
+/// produces a new schema compatible with the old one
+#[derive(Debug, Clone)]
+pub struct AvroTableSchema {
+    pub schema: Schema,
+    pub schema_str: String,
+    pub full_name: String,
+    // (col, avro field name, avro type)
+    cols: Vec<(String, String, AvroColType)>,
+}
+
+impl AvroTableSchema {
+    pub fn from_tb_meta(tb_meta: &RdbTbMeta) -> anyhow::Result<Self> {
+        let mut cols = Vec::new();
+        let mut field_defs = Vec::new();
+        // avro field name -> col
+        let mut field_cols: HashMap<String, &String> = HashMap::new();
+        for col in tb_meta.cols.iter() {
+            let col_type = match tb_meta.col_origin_type_map.get(col) {
+                Some(origin_type) => Self::get_avro_col_type(origin_type),
+                None => AvroColType::String,
+            };
+            let field = Self::to_avro_name(col);
+            // e.g. cols a-b and a_b are both named a_b in avro
+            if let Some(other_col) = field_cols.insert(field.clone(), col) {
+                bail! {Error::SinkerError(format!(
+                    "cols: {} and {} of table: {}.{} have the same avro field name: {}",
+                    other_col, col, tb_meta.schema, tb_meta.tb, field
+                ))}
+            }
+            field_defs.push(json!({
+                "name": field,
+                "type": ["null", Self::get_type_def(&col_type)],
+                "default": null
+            }));
+            cols.push((col.clone(), field, col_type));
+        }
+
+        let namespace = format!(
+            "{}.{}",
+            Self::to_avro_name(&tb_meta.schema),
+            Self::to_avro_name(&tb_meta.tb)
+        );
+        let schema_def = json!({
+            "type": "record",
+            "name": "Envelope",
+            "namespace": namespace,
+            "fields": [
+                {"name": "schema", "type": "string"},
+                {"name": "tb", "type": "string"},
+                {"name": "operation", "type": "string"},
+                {
+                    "name": "before",
+                    "type": ["null", {"type": "record", "name": "Value", "fields": field_defs}],
+                    "default": null
+                },
+                {"name": "after", "type": ["null", "Value"], "default": null}
+            ]
+        });
+
+        let schema_str = schema_def.to_string();
+        let schema = Schema::parse_str(&schema_str)?;
+        Ok(Self {
+            schema,
+            schema_str,
+            full_name: format!("{}.Envelope", namespace),
+            cols,
+        })
+    }
+
+    pub fn row_data_to_avro_datum(&self, row_data: &RowData) -> anyhow::Result<Vec<u8>> {
+        let value = Value::Record(vec![
+            ("schema".into(), Value::String(row_data.schema.clone())),
+            ("tb".into(), Value::String(row_data.tb.clone())),
+            (
+                "operation".into(),
+                Value::String(row_data.row_type.to_string()),
+            ),
+            ("before".into(), self.col_values_to_avro(&row_data.before)?),
+            ("after".into(), self.col_values_to_avro(&row_data.after)?),
+        ]);
+        Ok(to_avro_datum(&self.schema, value)?)
+    }
+
+    fn col_values_to_avro(
+        &self,
+        col_values: &Option<HashMap<String, ColValue>>,
+    ) -> anyhow::Result<Value> {
+        let col_values = match col_values {
+            Some(col_values) => col_values,
+            None => return Ok(Value::Union(0, Box::new(Value::Null))),
+        };
+
+        let mut fields = Vec::with_capacity(self.cols.len());
+        for (col, field, col_type) in self.cols.iter() {
+            let value = match col_values.get(col) {
+                Some(col_value) => Self::col_value_to_avro(col_value, col_type)?,
+                None => Value::Null,
+            };
+            let value = match value {
+                Value::Null => Value::Union(0, Box::new(Value::Null)),
+                value => Value::Union(1, Box::new(value)),
+            };
+            fields.push((field.clone(), value));
+        }
+        Ok(Value::Union(1, Box::new(Value::Record(fields))))
+    }
+
+    fn col_value_to_avro(value: &ColValue, col_type: &AvroColType) -> anyhow::Result<Value> {
+        if let ColValue::None = value {
+            return Ok(Value::Null);
+        }
+
+        let str_value = || match value {
+            ColValue::Blob(v) | ColValue::Json(v) | ColValue::RawString(v) => {
+                String::from_utf8_lossy(v).to_string()
+            }
+            _ => value.to_option_string().unwrap_or_default(),
+        };
+        let parse_error = || {
+            Error::SinkerError(format!(
+                "failed to convert value: {:?} to avro type: {:?}",
+                value, col_type
+            ))
+        };
+
+        let avro_value = match col_type {
+            AvroColType::Int => Value::Int(str_value().parse().map_err(|_| parse_error())?),
+            AvroColType::Long => Value::Long(str_value().parse().map_err(|_| parse_error())?),
+            AvroColType::Float => Value::Float(str_value().parse().map_err(|_| parse_error())?),
+            AvroColType::Double => Value::Double(str_value().parse().map_err(|_| parse_error())?),
+
+            AvroColType::Boolean => match value {
+                ColValue::Bool(v) => Value::Boolean(*v),
+                _ => Value::Boolean(matches!(
+                    str_value().to_lowercase().as_str(),
+                    "1" | "t" | "true"
+                )),
+            },
+
+            AvroColType::Bytes => match value {
+                ColValue::Blob(v) | ColValue::Json(v) | ColValue::RawString(v) => {
+                    Value::Bytes(v.clone())
+                }
+                _ => Value::Bytes(str_value().into_bytes()),
+            },
+
+            AvroColType::String => Value::String(str_value()),
+
+            AvroColType::Decimal { scale, .. } => {
+                let unscaled =
+                    Self::parse_unscaled_decimal(&str_value(), *scale).ok_or_else(parse_error)?;
+                Value::Decimal(Decimal::from(Self::to_twos_complement(unscaled)))
+            }
+
+            // invalid dates such as 0000-00-00 of mysql are sent as null
+            AvroColType::Date => match Self::parse_date(&str_value()) {
+                Some(days) => Value::Date(days),
+                None => Value::Null,
+            },
+
+            AvroColType::TimeMicros => {
+                Value::TimeMicros(Self::parse_time_micros(&str_value()).ok_or_else(parse_error)?)
+            }
+
+            AvroColType::TimestampMicros => match Self::parse_timestamp_micros(&str_value()) {
+                Some(micros) => Value::TimestampMicros(micros),
+                None => Value::Null,
+            },
+        };
+        Ok(avro_value)
+    }
+
+    /// Get the avro type by the column type of source db, e.g. decimal(10,2), int unsigned, int4
+    fn get_avro_col_type(origin_type: &str) -> AvroColType {
+        let origin_type = origin_type.to_lowercase();
+        let base_type = origin_type
+            .split(|c: char| c == '(' || c == ' ')
+            .next()
+            .unwrap_or_default();
+        let unsigned = origin_type.contains("unsigned");
+
+        match base_type {
+            "tinyint" | "smallint" | "mediumint" | "year" | "int2" | "smallserial" => {
+                AvroColType::Int
+            }
+            "int" | "integer" if unsigned => AvroColType::Long,
+            "int" | "integer" | "int4" | "serial" => AvroColType::Int,
+            "bigint" if unsigned => AvroColType::Decimal {
+                precision: 20,
+                scale: 0,
+            },
+            "bigint" | "int8" | "bigserial" | "bit" => AvroColType::Long,
+            "float" | "float4" | "real" => AvroColType::Float,
+            "double" | "float8" => AvroColType::Double,
+            "bool" | "boolean" => AvroColType::Boolean,
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bytea" => {
+                AvroColType::Bytes
+            }
+            "decimal" | "numeric" => match Self::parse_precision_scale(&origin_type) {
+                Some((precision, scale)) if precision <= MAX_DECIMAL_PRECISION => {
+                    AvroColType::Decimal { precision, scale }
+                }
+                // numeric without precision of pg, or too large to be held by i128
+                _ => AvroColType::String,
+            },
+            "date" => AvroColType::Date,
+            "time" => AvroColType::TimeMicros,
+            "datetime" | "timestamp" | "timestamptz" => AvroColType::TimestampMicros,
+            // varchar, text, json, enum, set, timetz, uuid and others
+            _ => AvroColType::String,
+        }
+    }
+
+    fn get_type_def(col_type: &AvroColType) -> serde_json::Value {
+        match col_type {
+            AvroColType::Int => json!("int"),
+            AvroColType::Long => json!("long"),
+            AvroColType::Float => json!("float"),
+            AvroColType::Double => json!("double"),
+            AvroColType::Boolean => json!("boolean"),
+            AvroColType::Bytes => json!("bytes"),
+            AvroColType::String => json!("string"),
+            AvroColType::Decimal { precision, scale } => json!({
+                "type": "bytes",
+                "logicalType": "decimal",
+                "precision": precision,
+                "scale": scale
+            }),
+            AvroColType::Date => json!({"type": "int", "logicalType": "date"}),
+            AvroColType::TimeMicros => json!({"type": "long", "logicalType": "time-micros"}),
+            AvroColType::TimestampMicros => {
+                json!({"type": "long", "logicalType": "timestamp-micros"})
+            }
+        }
+    }
+
+    /// decimal(10,2) -> (10, 2), decimal(10) -> (10, 0)
+    fn parse_precision_scale(origin_type: &str) -> Option<(u32, u32)> {
+        let start = origin_type.find('(')?;
+        let end = origin_type.find(')')?;
+        let mut items = origin_type[start + 1..end].split(',');
+        let precision = items.next()?.trim().parse().ok()?;
+        let scale = match items.next() {
+            Some(scale) => scale.trim().parse().ok()?,
+            None => 0,
+        };
+        Some((precision, scale))
+    }
+
+    /// "-123.4" with scale 2 -> -12340
+    fn parse_unscaled_decimal(value: &str, scale: u32) -> Option<i128> {
+        let value = value.trim();
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(v) => (true, v),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let (int_part, frac_part) = value.split_once('.').unwrap_or((value, ""));
+
+        let scale = scale as usize;
+        let mut digits = int_part.to_string();
+        if frac_part.len() >= scale {
+            digits.push_str(&frac_part[..scale]);
+        } else {
+            digits.push_str(frac_part);
+            digits.push_str(&"0".repeat(scale - frac_part.len()));
+        }
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let unscaled: i128 = digits.parse().ok()?;
+        Some(if negative { -unscaled } else { unscaled })
+    }
+
+    /// The minimal big-endian two's complement bytes, as required by avro decimals
+    fn to_twos_complement(value: i128) -> Vec<u8> {
+        let bytes = value.to_be_bytes();
+        let mut start = 0;
+        while start < bytes.len() - 1 {
+            let redundant = (bytes[start] == 0x00 && bytes[start + 1] & 0x80 == 0)
+                || (bytes[start] == 0xff && bytes[start + 1] & 0x80 != 0);
+            if !redundant {
+                break;
+            }
+            start += 1;
+        }
+        bytes[start..].to_vec()
+    }
+
+    /// Days since 1970-01-01
+    fn parse_date(value: &str) -> Option<i32> {
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+        Some((date - epoch).num_days() as i32)
+    }
+
+    /// Time of mysql may be negative or larger than 24 hours, e.g. -838:59:59.000000
+    fn parse_time_micros(value: &str) -> Option<i64> {
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(v) => (true, v),
+            None => (false, value),
+        };
+        let mut items = value.split(':');
+        let hours: i64 = items.next()?.parse().ok()?;
+        let minutes: i64 = items.next()?.parse().ok()?;
+        let secs = items.next()?;
+        let (secs, frac) = secs.split_once('.').unwrap_or((secs, ""));
+        let secs: i64 = secs.parse().ok()?;
+        let micros: i64 = if frac.is_empty() {
+            0
+        } else {
+            format!("{:0<6}", &frac[..frac.len().min(6)]).parse().ok()?
+        };
+
+        let total = ((hours * 60 + minutes) * 60 + secs) * 1_000_000 + micros;
+        Some(if negative { -total } else { total })
+    }
+
+    /// Datetimes without timezone are regarded as UTC, e.g.
+    /// 2024-01-01 12:00:00.123456, 2024-01-01 12:00:00+08, 2024-01-01T12:00:00Z
+    fn parse_timestamp_micros(value: &str) -> Option<i64> {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+            return Some(dt.timestamp_micros());
+        }
+        if let Ok(dt) = DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%#z") {
+            return Some(dt.timestamp_micros());
+        }
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+            .ok()
+            .map(|dt| dt.and_utc().timestamp_micros())
+    }
+
+    /// Avro names must match [A-Za-z_][A-Za-z0-9_]*
+    fn to_avro_name(name: &str) -> String {
+        let mut avro_name: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if !avro_name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            avro_name.insert(0, '_');
+        }
+        avro_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::from_avro_datum;
+
+    use crate::meta::row_type::RowType;
+
+    use super::*;
+
+    #[test]
+    fn test_row_data_to_avro_datum() {
+        let cols = ["id", "price", "created_at", "birthday", "name", "f-1"];
+        let origin_types = [
+            "int",
+            "decimal(10,2)",
+            "datetime(6)",
+            "date",
+            "varchar(255)",
+            "bigint unsigned",
+        ];
+        let tb_meta = RdbTbMeta {
+            schema: "test_db".into(),
+            tb: "tb_1".into(),
+            cols: cols.iter().map(|i| i.to_string()).collect(),
+            col_origin_type_map: cols
+                .iter()
+                .zip(origin_types.iter())
+                .map(|(col, origin_type)| (col.to_string(), origin_type.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        let table_schema = AvroTableSchema::from_tb_meta(&tb_meta).unwrap();
+        assert_eq!(table_schema.full_name, "test_db.tb_1.Envelope");
+
+        let mut after = HashMap::new();
+        after.insert("id".into(), ColValue::Long(1));
+        after.insert("price".into(), ColValue::Decimal("-12.3".into()));
+        after.insert(
+            "created_at".into(),
+            ColValue::DateTime("1970-01-01 00:00:01.000002".into()),
+        );
+        after.insert("birthday".into(), ColValue::Date("1970-01-11".into()));
+        after.insert("name".into(), ColValue::None);
+        after.insert("f-1".into(), ColValue::UnsignedLongLong(u64::MAX));
+        let row_data = RowData::new(
+            "test_db".into(),
+            "tb_1".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+
+        let payload = table_schema.row_data_to_avro_datum(&row_data).unwrap();
+        let value = from_avro_datum(&table_schema.schema, &mut payload.as_slice(), None).unwrap();
+        let Value::Record(fields) = value else {
+            panic!("not a record")
+        };
+        assert_eq!(fields[3].1, Value::Union(0, Box::new(Value::Null)));
+
+        let Value::Union(1, after) = &fields[4].1 else {
+            panic!("after is null")
+        };
+        let Value::Record(after) = after.as_ref() else {
+            panic!("after is not a record")
+        };
+        let expected = vec![
+            ("id", Value::Int(1)),
+            ("price", Value::Decimal(Decimal::from(vec![0xfb, 0x32]))),
+            ("created_at", Value::TimestampMicros(1_000_002)),
+            ("birthday", Value::Date(10)),
+            ("name", Value::Null),
+            (
+                "f_1",
+                Value::Decimal(Decimal::from(vec![
+                    0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                ])),
+            ),
+        ];
+        for ((field, value), (expected_field, expected_value)) in after.iter().zip(expected) {
+            assert_eq!(field, expected_field);
+            let expected_value = match expected_value {
+                Value::Null => Value::Union(0, Box::new(Value::Null)),
+                v => Value::Union(1, Box::new(v)),
+            };
+            assert_eq!(value, &expected_value);
+        }
+    }
+
+    #[test]
+    fn test_duplicate_avro_field_names() {
+        let tb_meta = RdbTbMeta {
+            schema: "test_db".into(),
+            tb: "tb_1".into(),
+            cols: vec!["id".into(), "a-b".into(), "a_b".into()],
+            ..Default::default()
+        };
+        let err = AvroTableSchema::from_tb_meta(&tb_meta).unwrap_err();
+        assert!(err.to_string().contains("a-b and a_b"));
+    }
+}
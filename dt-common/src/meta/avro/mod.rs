@@ -1,2 +1,3 @@
 pub mod avro_converter;
 pub mod avro_converter_schema;
+pub mod avro_table_schema;
@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    error::Error,
+    meta::{
+        col_value::ColValue,
+        ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
+        dt_data::DtData,
+        position::Position,
+        rdb_meta_manager::RdbMetaManager,
+        rdb_tb_meta::RdbTbMeta,
+        row_data::RowData,
+        row_type::RowType,
+    },
+};
+
+use anyhow::bail;
+
+const ID: &str = "id";
+const DATABASE: &str = "database";
+const TABLE: &str = "table";
+const PK_NAMES: &str = "pkNames";
+const IS_DDL: &str = "isDdl";
+const TYPE: &str = "type";
+const ES: &str = "es";
+const TS: &str = "ts";
+const SQL: &str = "sql";
+const SQL_TYPE: &str = "sqlType";
+const MYSQL_TYPE: &str = "mysqlType";
+const DATA: &str = "data";
+const OLD: &str = "old";
+const INSERT: &str = "INSERT";
+const UPDATE: &str = "UPDATE";
+const DELETE: &str = "DELETE";
+
+// java.sql.Types
+const SQL_TYPE_BIT: i32 = -7;
+const SQL_TYPE_TINYINT: i32 = -6;
+const SQL_TYPE_SMALLINT: i32 = 5;
+const SQL_TYPE_INTEGER: i32 = 4;
+const SQL_TYPE_BIGINT: i32 = -5;
+const SQL_TYPE_FLOAT: i32 = 7;
+const SQL_TYPE_DOUBLE: i32 = 8;
+const SQL_TYPE_DECIMAL: i32 = 3;
+const SQL_TYPE_CHAR: i32 = 1;
+const SQL_TYPE_VARCHAR: i32 = 12;
+const SQL_TYPE_BINARY: i32 = -2;
+const SQL_TYPE_VARBINARY: i32 = -3;
+const SQL_TYPE_DATE: i32 = 91;
+const SQL_TYPE_TIME: i32 = 92;
+const SQL_TYPE_TIMESTAMP: i32 = 93;
+const SQL_TYPE_BOOLEAN: i32 = 16;
+const SQL_TYPE_BLOB: i32 = 2004;
+const SQL_TYPE_CLOB: i32 = 2005;
+
+/// Converts RowData to / from the flat messages of Canal, e.g.
+/// {"id": 1, "database": "db1", "table": "tb1", "pkNames": ["id"], "isDdl": false, "type": "UPDATE",
+///  "es": 1700000000000, "ts": 1700000000456, "sql": "", "sqlType": {"id": 4, "name": 12},
+///  "mysqlType": {"id": "int(11)", "name": "varchar(255)"},
+///  "data": [{"id": "1", "name": "b"}], "old": [{"name": "a"}]}
+/// all values are strings, binary values are ISO-8859-1 strings, same as Canal
+#[derive(Clone)]
+pub struct CanalConverter {
+    pub meta_manager: Option<RdbMetaManager>,
+    id: u64,
+}
+
+impl CanalConverter {
+    pub fn new(meta_manager: Option<RdbMetaManager>) -> Self {
+        Self {
+            meta_manager,
+            id: 0,
+        }
+    }
+
+    pub fn refresh_meta(&mut self, data: &[DdlData]) {
+        if let Some(meta_manager) = &mut self.meta_manager {
+            for ddl_data in data.iter() {
+                meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+            }
+        }
+    }
+
+    pub async fn row_data_to_canal_value(
+        &mut self,
+        row_data: &RowData,
+        position: &Position,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.id += 1;
+        let id = self.id;
+        let tb_meta = self.get_tb_meta(row_data).await?;
+
+        let (row_type, image) = match row_data.row_type {
+            RowType::Insert => (INSERT, row_data.after.as_ref()),
+            RowType::Update => (UPDATE, row_data.after.as_ref()),
+            RowType::Delete => (DELETE, row_data.before.as_ref()),
+        };
+        let empty = HashMap::new();
+        let image = image.unwrap_or(&empty);
+        let cols = Self::get_cols(image, tb_meta);
+
+        let mut data = Map::new();
+        let mut sql_types = Map::new();
+        let mut mysql_types = Map::new();
+        for col in cols.iter() {
+            let col_value = image.get(col).unwrap_or(&ColValue::None);
+            data.insert(col.clone(), Self::col_value_to_canal(col_value));
+
+            let col_type = match tb_meta.and_then(|i| i.col_origin_type_map.get(col)) {
+                Some(origin_type) => origin_type.clone(),
+                None => Self::get_col_type_name(col_value).to_string(),
+            };
+            sql_types.insert(col.clone(), json!(Self::get_sql_type(&col_type)));
+            mysql_types.insert(col.clone(), json!(col_type));
+        }
+
+        // old contains the changed cols only
+        let old = match (&row_data.row_type, &row_data.before) {
+            (RowType::Update, Some(before)) => {
+                let mut old = Map::new();
+                for col in cols.iter() {
+                    let before_value = before.get(col).unwrap_or(&ColValue::None);
+                    if image.get(col) != Some(before_value) {
+                        old.insert(col.clone(), Self::col_value_to_canal(before_value));
+                    }
+                }
+                json!([old])
+            }
+            _ => Value::Null,
+        };
+
+        let pk_names = match tb_meta.and_then(|i| i.key_map.get("primary")) {
+            Some(pk_cols) => json!(pk_cols),
+            None => Value::Null,
+        };
+        let value = json!({
+            ID: id,
+            DATABASE: row_data.schema,
+            TABLE: row_data.tb,
+            PK_NAMES: pk_names,
+            IS_DDL: false,
+            TYPE: row_type,
+            ES: Self::get_es(position),
+            TS: Utc::now().timestamp_millis(),
+            SQL: "",
+            SQL_TYPE: sql_types,
+            MYSQL_TYPE: mysql_types,
+            DATA: [data],
+            OLD: old,
+        });
+        Ok(value.to_string().into_bytes())
+    }
+
+    /// Ddl is sent as a message with isDdl: true, type is the event type of Canal, e.g. ALTER
+    pub fn ddl_data_to_canal_value(&mut self, ddl_data: &DdlData, position: &Position) -> Vec<u8> {
+        self.id += 1;
+        let (schema, tb) = ddl_data.get_schema_tb();
+        let ddl_type = match ddl_data.ddl_type {
+            DdlType::CreateTable => "CREATE",
+            DdlType::AlterTable => "ALTER",
+            DdlType::DropTable => "ERASE",
+            DdlType::RenameTable => "RENAME",
+            DdlType::TruncateTable => "TRUNCATE",
+            DdlType::CreateIndex => "CINDEX",
+            DdlType::DropIndex => "DINDEX",
+            _ => "QUERY",
+        };
+
+        let value = json!({
+            ID: self.id,
+            DATABASE: schema,
+            TABLE: tb,
+            PK_NAMES: Value::Null,
+            IS_DDL: true,
+            TYPE: ddl_type,
+            ES: Self::get_es(position),
+            TS: Utc::now().timestamp_millis(),
+            SQL: ddl_data.query,
+            SQL_TYPE: Value::Null,
+            MYSQL_TYPE: Value::Null,
+            DATA: Value::Null,
+            OLD: Value::Null,
+        });
+        value.to_string().into_bytes()
+    }
+
+    /// A Canal message may contain multiple rows
+    pub fn canal_value_to_dt_data(&self, payload: &[u8]) -> anyhow::Result<Vec<DtData>> {
+        let mut value: Value = serde_json::from_slice(payload)?;
+        if !value.is_object() {
+            bail! {Error::Unexpected(format!(
+                "invalid canal message: {}",
+                String::from_utf8_lossy(payload)
+            ))}
+        }
+        let schema = value[DATABASE].as_str().unwrap_or_default().to_string();
+        let tb = value[TABLE].as_str().unwrap_or_default().to_string();
+        if value[IS_DDL].as_bool().unwrap_or_default() {
+            // ddl is not supported
+            return Ok(Vec::new());
+        }
+
+        let row_type = match value[TYPE].as_str() {
+            Some(INSERT) => RowType::Insert,
+            Some(UPDATE) => RowType::Update,
+            Some(DELETE) => RowType::Delete,
+            row_type => {
+                bail! {Error::Unexpected(format!(
+                    "unsupported canal type: {:?}, schema: {}, tb: {}",
+                    row_type, schema, tb
+                ))}
+            }
+        };
+
+        let mut sql_types = HashMap::new();
+        if let Value::Object(map) = value[SQL_TYPE].take() {
+            for (col, sql_type) in map {
+                sql_types.insert(col, sql_type.as_i64().unwrap_or_default() as i32);
+            }
+        }
+
+        let rows = match value[DATA].take() {
+            Value::Array(rows) => rows,
+            _ => Vec::new(),
+        };
+        let mut olds = match value[OLD].take() {
+            Value::Array(olds) => olds,
+            _ => Vec::new(),
+        };
+
+        let mut dt_datas = Vec::new();
+        for (i, row) in rows.into_iter().enumerate() {
+            let image = Self::canal_to_col_values(row, &sql_types);
+            let (before, after) = match row_type {
+                RowType::Insert => (None, Some(image)),
+                RowType::Delete => (Some(image), None),
+                RowType::Update => {
+                    // before = data overwritten by old
+                    let mut before = image.clone();
+                    if let Some(old) = olds.get_mut(i) {
+                        before.extend(Self::canal_to_col_values(old.take(), &sql_types));
+                    }
+                    (Some(before), Some(image))
+                }
+            };
+            let row_data =
+                RowData::new(schema.clone(), tb.clone(), row_type.clone(), before, after);
+            dt_datas.push(DtData::Dml { row_data });
+        }
+        Ok(dt_datas)
+    }
+
+    fn get_es(position: &Position) -> i64 {
+        match position {
+            Position::MysqlCdc { timestamp, .. } | Position::PgCdc { timestamp, .. } => {
+                Position::parse_timestamp_millis(timestamp)
+            }
+            _ => 0,
+        }
+    }
+
+    fn get_cols(image: &HashMap<String, ColValue>, tb_meta: Option<&RdbTbMeta>) -> Vec<String> {
+        if let Some(tb_meta) = tb_meta {
+            return tb_meta.cols.clone();
+        }
+        let mut cols: Vec<String> = image.keys().cloned().collect();
+        cols.sort();
+        cols
+    }
+
+    fn canal_to_col_values(
+        value: Value,
+        sql_types: &HashMap<String, i32>,
+    ) -> HashMap<String, ColValue> {
+        let mut col_values = HashMap::new();
+        if let Value::Object(map) = value {
+            for (col, value) in map {
+                let sql_type = sql_types.get(&col).cloned().unwrap_or(SQL_TYPE_VARCHAR);
+                col_values.insert(col, Self::canal_to_col_value(value, sql_type));
+            }
+        }
+        col_values
+    }
+
+    fn col_value_to_canal(value: &ColValue) -> Value {
+        match value {
+            // ISO-8859-1, same as Canal
+            ColValue::Blob(v) | ColValue::RawString(v) => {
+                Value::String(v.iter().map(|i| *i as char).collect())
+            }
+            ColValue::Json(v) => Value::String(String::from_utf8_lossy(v).to_string()),
+            _ => match value.to_option_string() {
+                Some(v) => Value::String(v),
+                None => Value::Null,
+            },
+        }
+    }
+
+    fn canal_to_col_value(value: Value, sql_type: i32) -> ColValue {
+        let v = match value {
+            Value::Null => return ColValue::None,
+            Value::String(v) => v,
+            value => value.to_string(),
+        };
+
+        match sql_type {
+            SQL_TYPE_BIT | SQL_TYPE_TINYINT | SQL_TYPE_SMALLINT | SQL_TYPE_INTEGER
+            | SQL_TYPE_BIGINT => {
+                if let Ok(i) = v.parse::<i64>() {
+                    ColValue::LongLong(i)
+                } else if let Ok(u) = v.parse::<u64>() {
+                    ColValue::UnsignedLongLong(u)
+                } else {
+                    // enum / set values may be strings
+                    ColValue::String(v)
+                }
+            }
+            SQL_TYPE_FLOAT | SQL_TYPE_DOUBLE => match v.parse::<f64>() {
+                Ok(f) => ColValue::Double(f),
+                Err(_) => ColValue::String(v),
+            },
+            SQL_TYPE_BOOLEAN => ColValue::Bool(v == "true" || v == "1"),
+            SQL_TYPE_BINARY | SQL_TYPE_VARBINARY | SQL_TYPE_BLOB => {
+                ColValue::Blob(v.chars().map(|i| i as u8).collect())
+            }
+            _ => ColValue::String(v),
+        }
+    }
+
+    /// Get java.sql.Types by the column type of source db, e.g. varchar(255) -> 12
+    pub fn get_sql_type(col_type: &str) -> i32 {
+        let col_type = col_type.to_lowercase();
+        let base_type = col_type
+            .split(|c: char| c == '(' || c == ' ')
+            .next()
+            .unwrap_or_default();
+        match base_type {
+            "bit" | "set" => SQL_TYPE_BIT,
+            "tinyint" => SQL_TYPE_TINYINT,
+            "smallint" => SQL_TYPE_SMALLINT,
+            "mediumint" | "int" | "integer" | "enum" => SQL_TYPE_INTEGER,
+            "bigint" => SQL_TYPE_BIGINT,
+            "float" => SQL_TYPE_FLOAT,
+            "double" => SQL_TYPE_DOUBLE,
+            "decimal" | "numeric" => SQL_TYPE_DECIMAL,
+            "char" => SQL_TYPE_CHAR,
+            "binary" => SQL_TYPE_BINARY,
+            "varbinary" => SQL_TYPE_VARBINARY,
+            "date" => SQL_TYPE_DATE,
+            "time" => SQL_TYPE_TIME,
+            "datetime" | "timestamp" => SQL_TYPE_TIMESTAMP,
+            "boolean" | "bool" => SQL_TYPE_BOOLEAN,
+            "tinyblob" | "blob" | "mediumblob" | "longblob" | "bytea" => SQL_TYPE_BLOB,
+            "tinytext" | "text" | "mediumtext" | "longtext" => SQL_TYPE_CLOB,
+            // varchar, year, json and others
+            _ => SQL_TYPE_VARCHAR,
+        }
+    }
+
+    /// Used if no meta data is available
+    fn get_col_type_name(value: &ColValue) -> &'static str {
+        match value {
+            ColValue::Bool(_) => "boolean",
+            ColValue::Tiny(_) | ColValue::UnsignedTiny(_) => "tinyint",
+            ColValue::Short(_) | ColValue::UnsignedShort(_) => "smallint",
+            ColValue::Long(_) | ColValue::UnsignedLong(_) => "int",
+            ColValue::LongLong(_) | ColValue::UnsignedLongLong(_) => "bigint",
+            ColValue::Float(_) => "float",
+            ColValue::Double(_) => "double",
+            ColValue::Decimal(_) => "decimal",
+            ColValue::Time(_) => "time",
+            ColValue::Date(_) => "date",
+            ColValue::DateTime(_) => "datetime",
+            ColValue::Timestamp(_) => "timestamp",
+            ColValue::Year(_) => "year",
+            ColValue::Blob(_) | ColValue::RawString(_) => "blob",
+            ColValue::Bit(_) => "bit",
+            ColValue::Set(_) | ColValue::Set2(_) => "set",
+            ColValue::Enum(_) | ColValue::Enum2(_) => "enum",
+            ColValue::Json(_) | ColValue::Json2(_) | ColValue::Json3(_) => "json",
+            _ => "varchar",
+        }
+    }
+
+    async fn get_tb_meta<'a>(
+        &'a mut self,
+        row_data: &RowData,
+    ) -> anyhow::Result<Option<&'a RdbTbMeta>> {
+        if let Some(meta_manager) = self.meta_manager.as_mut() {
+            let tb_meta = meta_manager
+                .get_tb_meta(&row_data.schema, &row_data.tb)
+                .await?;
+            return Ok(Some(tb_meta));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_row_data_to_canal() {
+        let mut before = HashMap::new();
+        before.insert("id".to_string(), ColValue::LongLong(1));
+        before.insert("name".to_string(), ColValue::String("a".into()));
+        before.insert("data".to_string(), ColValue::Blob(vec![1, 2, 255]));
+        before.insert("score".to_string(), ColValue::Double(1.5));
+        before.insert("null_col".to_string(), ColValue::None);
+        let mut after = before.clone();
+        after.insert("name".to_string(), ColValue::String("b".into()));
+
+        let mut converter = CanalConverter::new(None);
+        let row_datas = [
+            RowData::new(
+                "db1".into(),
+                "tb1".into(),
+                RowType::Insert,
+                None,
+                Some(after.clone()),
+            ),
+            RowData::new(
+                "db1".into(),
+                "tb1".into(),
+                RowType::Update,
+                Some(before.clone()),
+                Some(after.clone()),
+            ),
+            RowData::new(
+                "db1".into(),
+                "tb1".into(),
+                RowType::Delete,
+                Some(before.clone()),
+                None,
+            ),
+        ];
+
+        for row_data in row_datas {
+            let payload = converter
+                .row_data_to_canal_value(&row_data, &Position::None)
+                .await
+                .unwrap();
+
+            let value: Value = serde_json::from_slice(&payload).unwrap();
+            assert_eq!(value[SQL_TYPE]["data"], SQL_TYPE_BLOB);
+            if row_data.row_type == RowType::Update {
+                // only the changed cols
+                assert_eq!(value[OLD], json!([{"name": "a"}]));
+            }
+
+            let dt_datas = converter.canal_value_to_dt_data(&payload).unwrap();
+            assert_eq!(dt_datas.len(), 1);
+            if let DtData::Dml {
+                row_data: decoded_row_data,
+            } = &dt_datas[0]
+            {
+                assert_eq!(decoded_row_data, &row_data);
+            } else {
+                panic!()
+            }
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod canal_converter;
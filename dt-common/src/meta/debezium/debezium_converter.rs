@@ -0,0 +1,607 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    config::config_enums::DbType,
+    error::Error,
+    meta::{
+        col_value::ColValue, ddl_meta::ddl_data::DdlData, dt_data::DtData, position::Position,
+        rdb_meta_manager::RdbMetaManager, rdb_tb_meta::RdbTbMeta, row_data::RowData,
+        row_type::RowType,
+    },
+};
+
+use anyhow::bail;
+
+const SCHEMA: &str = "schema";
+const PAYLOAD: &str = "payload";
+const BEFORE: &str = "before";
+const AFTER: &str = "after";
+const SOURCE: &str = "source";
+const OP: &str = "op";
+const TS_MS: &str = "ts_ms";
+const FIELDS: &str = "fields";
+const FIELD: &str = "field";
+const TYPE: &str = "type";
+const DB: &str = "db";
+const TABLE: &str = "table";
+const DATABASE_NAME: &str = "databaseName";
+const DDL: &str = "ddl";
+const CONNECTOR_NAME: &str = "ape_dts";
+const SOURCE_COLUMN_TYPE: &str = "__debezium.source.column.type";
+
+/// Converts RowData to / from Debezium change events, e.g.
+/// {"before": null, "after": {"id": 1, "name": "a"}, "op": "c", "ts_ms": 1700000000000,
+///  "source": {"connector": "mysql", "name": "ape_dts", "db": "db1", "table": "tb1",
+///             "file": "mysql-bin.000001", "pos": 1234, "ts_ms": 1700000000000, "snapshot": "false"}}
+/// with_schema: wrap the event as {"schema": {...}, "payload": {...}} like the JsonConverter of Kafka Connect
+#[derive(Clone)]
+pub struct DebeziumConverter {
+    pub db_type: DbType,
+    pub with_schema: bool,
+    pub meta_manager: Option<RdbMetaManager>,
+}
+
+impl DebeziumConverter {
+    pub fn new(meta_manager: Option<RdbMetaManager>, db_type: DbType, with_schema: bool) -> Self {
+        Self {
+            db_type,
+            with_schema,
+            meta_manager,
+        }
+    }
+
+    pub fn refresh_meta(&mut self, data: &[DdlData]) {
+        if let Some(meta_manager) = &mut self.meta_manager {
+            for ddl_data in data.iter() {
+                meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+            }
+        }
+    }
+
+    /// The key contains the primary / unique key cols, e.g. {"id": 1},
+    /// it is empty if the table has no keys or no meta data is available
+    pub async fn row_data_to_debezium_key(&mut self, row_data: &RowData) -> anyhow::Result<String> {
+        let with_schema = self.with_schema;
+        let tb_meta = match self.get_tb_meta(row_data).await? {
+            Some(tb_meta) if !tb_meta.key_map.is_empty() => tb_meta,
+            _ => return Ok(String::new()),
+        };
+
+        let col_values = match row_data.row_type {
+            RowType::Insert => row_data.after.as_ref(),
+            RowType::Update | RowType::Delete => row_data.before.as_ref(),
+        };
+        let mut key = HashMap::new();
+        for col in tb_meta.id_cols.iter() {
+            let col_value = col_values
+                .and_then(|i| i.get(col))
+                .cloned()
+                .unwrap_or(ColValue::None);
+            key.insert(col.clone(), col_value);
+        }
+
+        let payload = Self::col_values_to_json(&Some(key.clone()), &tb_meta.id_cols);
+        if !with_schema {
+            return Ok(payload.to_string());
+        }
+        let name = format!("{}.{}.Key", row_data.schema, row_data.tb);
+        let schema =
+            Self::build_struct_schema(&name, "", false, &tb_meta.id_cols, Some(tb_meta), &[&key]);
+        Ok(json!({ SCHEMA: schema, PAYLOAD: payload }).to_string())
+    }
+
+    pub async fn row_data_to_debezium_value(
+        &mut self,
+        row_data: &RowData,
+        position: &Position,
+    ) -> anyhow::Result<Vec<u8>> {
+        let (db_type, with_schema) = (self.db_type.clone(), self.with_schema);
+        let tb_meta = self.get_tb_meta(row_data).await?;
+        let cols = Self::get_cols(row_data, tb_meta);
+
+        let op = match (&row_data.row_type, position) {
+            (RowType::Insert, Position::RdbSnapshot { .. }) => "r",
+            (RowType::Insert, _) => "c",
+            (RowType::Update, _) => "u",
+            (RowType::Delete, _) => "d",
+        };
+        let source = Self::build_source(&db_type, &row_data.schema, &row_data.tb, position);
+        let payload = json!({
+            BEFORE: Self::col_values_to_json(&row_data.before, &cols),
+            AFTER: Self::col_values_to_json(&row_data.after, &cols),
+            SOURCE: source,
+            OP: op,
+            TS_MS: Utc::now().timestamp_millis(),
+        });
+
+        if !with_schema {
+            return Ok(payload.to_string().into_bytes());
+        }
+
+        let mut images = Vec::new();
+        for col_values in [&row_data.before, &row_data.after].into_iter().flatten() {
+            images.push(col_values);
+        }
+        let value_name = format!("{}.{}.Value", row_data.schema, row_data.tb);
+        let before_schema =
+            Self::build_struct_schema(&value_name, BEFORE, true, &cols, tb_meta, &images);
+        let after_schema =
+            Self::build_struct_schema(&value_name, AFTER, true, &cols, tb_meta, &images);
+        let source_schema = Self::build_source_schema(&db_type, &payload[SOURCE]);
+        let schema = json!({
+            TYPE: "struct",
+            "name": format!("{}.{}.Envelope", row_data.schema, row_data.tb),
+            "optional": false,
+            FIELDS: [
+                before_schema,
+                after_schema,
+                source_schema,
+                { TYPE: "string", "optional": false, FIELD: OP },
+                { TYPE: "int64", "optional": true, FIELD: TS_MS },
+            ],
+        });
+        Ok(json!({ SCHEMA: schema, PAYLOAD: payload })
+            .to_string()
+            .into_bytes())
+    }
+
+    /// Ddl is sent as a schema change event of Debezium, e.g.
+    /// {"source": {...}, "databaseName": "db1", "ddl": "ALTER TABLE ...", "ts_ms": 1700000000000}
+    pub fn ddl_data_to_debezium_value(&self, ddl_data: &DdlData, position: &Position) -> Vec<u8> {
+        let (schema, tb) = ddl_data.get_schema_tb();
+        let source = Self::build_source(&self.db_type, &schema, &tb, position);
+        let payload = json!({
+            SOURCE: source,
+            DATABASE_NAME: schema,
+            DDL: ddl_data.query,
+            TS_MS: Utc::now().timestamp_millis(),
+        });
+
+        if !self.with_schema {
+            return payload.to_string().into_bytes();
+        }
+
+        let schema = json!({
+            TYPE: "struct",
+            "name": format!(
+                "io.debezium.connector.{}.SchemaChangeValue",
+                Self::get_connector(&self.db_type)
+            ),
+            "optional": false,
+            FIELDS: [
+                Self::build_source_schema(&self.db_type, &payload[SOURCE]),
+                { TYPE: "string", "optional": false, FIELD: DATABASE_NAME },
+                { TYPE: "string", "optional": false, FIELD: DDL },
+                { TYPE: "int64", "optional": true, FIELD: TS_MS },
+            ],
+        });
+        json!({ SCHEMA: schema, PAYLOAD: payload })
+            .to_string()
+            .into_bytes()
+    }
+
+    /// Schema change events are skipped
+    pub fn debezium_value_to_dt_data(&self, payload_bytes: &[u8]) -> anyhow::Result<Vec<DtData>> {
+        let mut value: Value = serde_json::from_slice(payload_bytes)?;
+        // {"schema": {...}, "payload": {...}} or the payload only
+        let (schema, mut payload) = if value.get(SCHEMA).is_some() && value.get(PAYLOAD).is_some() {
+            (value[SCHEMA].take(), value[PAYLOAD].take())
+        } else {
+            (Value::Null, value)
+        };
+        if !payload.is_object() {
+            bail! {Error::Unexpected(format!(
+                "invalid debezium event: {}",
+                String::from_utf8_lossy(payload_bytes)
+            ))}
+        }
+        if payload.get(DDL).is_some() {
+            return Ok(Vec::new());
+        }
+
+        let source = payload[SOURCE].take();
+        let get_source_str = |key: &str| source[key].as_str().unwrap_or_default().to_string();
+        // pg events have both db and schema in source, mysql events have db only
+        let schema_name = match source.get(SCHEMA) {
+            Some(Value::String(v)) => v.clone(),
+            _ => get_source_str(DB),
+        };
+        let tb = get_source_str(TABLE);
+
+        let row_type = match payload[OP].as_str() {
+            Some("c") | Some("r") => RowType::Insert,
+            Some("u") => RowType::Update,
+            Some("d") => RowType::Delete,
+            op => {
+                bail! {Error::Unexpected(format!(
+                    "unsupported debezium op: {:?}, schema: {}, tb: {}",
+                    op, schema_name, tb
+                ))}
+            }
+        };
+
+        let col_types = Self::get_col_types(&schema);
+        let before = Self::json_to_col_values(payload[BEFORE].take(), &col_types)?;
+        let after = Self::json_to_col_values(payload[AFTER].take(), &col_types)?;
+        Ok(vec![DtData::Dml {
+            row_data: RowData::new(schema_name, tb, row_type, before, after),
+        }])
+    }
+
+    fn build_source(db_type: &DbType, schema: &str, tb: &str, position: &Position) -> Value {
+        let mut source = Map::new();
+        source.insert("connector".into(), json!(Self::get_connector(db_type)));
+        source.insert("name".into(), json!(CONNECTOR_NAME));
+        match db_type {
+            DbType::Pg => {
+                source.insert(SCHEMA.into(), json!(schema));
+            }
+            _ => {
+                source.insert(DB.into(), json!(schema));
+            }
+        }
+        source.insert(TABLE.into(), json!(tb));
+
+        let (timestamp, snapshot) = match position {
+            Position::MysqlCdc {
+                binlog_filename,
+                next_event_position,
+                gtid_set,
+                timestamp,
+                ..
+            } => {
+                source.insert("file".into(), json!(binlog_filename));
+                source.insert("pos".into(), json!(next_event_position));
+                if !gtid_set.is_empty() {
+                    source.insert("gtid".into(), json!(gtid_set));
+                }
+                (timestamp.as_str(), false)
+            }
+            Position::PgCdc { lsn, timestamp } => {
+                if let Some(lsn) = Self::parse_pg_lsn(lsn) {
+                    source.insert("lsn".into(), json!(lsn));
+                }
+                (timestamp.as_str(), false)
+            }
+            Position::RdbSnapshot { .. } => ("", true),
+            _ => ("", false),
+        };
+        source.insert(
+            TS_MS.into(),
+            json!(Position::parse_timestamp_millis(timestamp)),
+        );
+        source.insert("snapshot".into(), json!(snapshot.to_string()));
+        Value::Object(source)
+    }
+
+    fn get_connector(db_type: &DbType) -> String {
+        match db_type {
+            DbType::Pg => "postgresql".to_string(),
+            db_type => db_type.to_string(),
+        }
+    }
+
+    fn build_source_schema(db_type: &DbType, source: &Value) -> Value {
+        let mut fields = Vec::new();
+        if let Value::Object(source) = source {
+            for (key, value) in source {
+                let field_type = match value {
+                    Value::Number(_) => "int64",
+                    _ => "string",
+                };
+                fields.push(json!({ TYPE: field_type, "optional": true, FIELD: key }));
+            }
+        }
+        json!({
+            TYPE: "struct",
+            "name": format!("io.debezium.connector.{}.Source", Self::get_connector(db_type)),
+            "optional": false,
+            FIELDS: fields,
+            FIELD: SOURCE,
+        })
+    }
+
+    fn build_struct_schema(
+        name: &str,
+        field: &str,
+        optional: bool,
+        cols: &[String],
+        tb_meta: Option<&RdbTbMeta>,
+        images: &[&HashMap<String, ColValue>],
+    ) -> Value {
+        let mut fields = Vec::new();
+        for col in cols.iter() {
+            // get the type from any image in which the col is not null
+            let field_type = images
+                .iter()
+                .filter_map(|i| i.get(col))
+                .map(Self::col_value_to_schema_type)
+                .find(|i| !i.is_empty())
+                .unwrap_or("string");
+            let mut col_schema = json!({ TYPE: field_type, "optional": true, FIELD: col });
+            if let Some(origin_type) = tb_meta.and_then(|i| i.col_origin_type_map.get(col)) {
+                col_schema["parameters"] = json!({ SOURCE_COLUMN_TYPE: origin_type });
+            }
+            fields.push(col_schema);
+        }
+
+        let mut schema = json!({
+            TYPE: "struct",
+            "name": name,
+            "optional": optional,
+            FIELDS: fields,
+        });
+        if !field.is_empty() {
+            schema[FIELD] = json!(field);
+        }
+        schema
+    }
+
+    fn get_col_types(schema: &Value) -> HashMap<String, String> {
+        // only the types of before / after cols are needed
+        let mut col_types = HashMap::new();
+        if let Some(Value::Array(fields)) = schema.get(FIELDS) {
+            for field in fields {
+                if field[FIELD] != BEFORE && field[FIELD] != AFTER {
+                    continue;
+                }
+                if let Some(Value::Array(cols)) = field.get(FIELDS) {
+                    for col in cols {
+                        if let (Some(name), Some(col_type)) =
+                            (col[FIELD].as_str(), col[TYPE].as_str())
+                        {
+                            col_types.insert(name.to_string(), col_type.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        col_types
+    }
+
+    fn get_cols(row_data: &RowData, tb_meta: Option<&RdbTbMeta>) -> Vec<String> {
+        if let Some(tb_meta) = tb_meta {
+            return tb_meta.cols.clone();
+        }
+
+        let mut cols = Vec::new();
+        for col_values in [&row_data.before, &row_data.after].into_iter().flatten() {
+            for col in col_values.keys() {
+                if !cols.contains(col) {
+                    cols.push(col.clone());
+                }
+            }
+        }
+        cols.sort();
+        cols
+    }
+
+    fn col_values_to_json(
+        col_values: &Option<HashMap<String, ColValue>>,
+        cols: &[String],
+    ) -> Value {
+        match col_values {
+            Some(col_values) => {
+                let mut map = Map::new();
+                for col in cols.iter() {
+                    if let Some(col_value) = col_values.get(col) {
+                        map.insert(col.clone(), Self::col_value_to_json(col_value));
+                    }
+                }
+                Value::Object(map)
+            }
+            None => Value::Null,
+        }
+    }
+
+    fn json_to_col_values(
+        value: Value,
+        col_types: &HashMap<String, String>,
+    ) -> anyhow::Result<Option<HashMap<String, ColValue>>> {
+        match value {
+            Value::Object(map) => {
+                let mut col_values = HashMap::new();
+                for (col, value) in map {
+                    let col_type = col_types.get(&col).map(|i| i.as_str());
+                    let col_value = Self::json_to_col_value(value, col_type)?;
+                    col_values.insert(col, col_value);
+                }
+                Ok(Some(col_values))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn col_value_to_json(value: &ColValue) -> Value {
+        match value {
+            ColValue::Tiny(v) => json!(v),
+            ColValue::UnsignedTiny(v) => json!(v),
+            ColValue::Short(v) => json!(v),
+            ColValue::UnsignedShort(v) => json!(v),
+            ColValue::Long(v) => json!(v),
+            ColValue::UnsignedLong(v) => json!(v),
+            ColValue::LongLong(v) => json!(v),
+            ColValue::UnsignedLongLong(v) => json!(v),
+            ColValue::Year(v) => json!(v),
+            ColValue::Bit(v) => json!(v),
+            ColValue::Set(v) => json!(v),
+            ColValue::Enum(v) => json!(v),
+
+            ColValue::Float(v) => json!(v),
+            ColValue::Double(v) => json!(v),
+
+            // same as binary.handling.mode=hex of debezium
+            ColValue::Blob(v) | ColValue::RawString(v) => json!(hex::encode(v)),
+            ColValue::Json(v) => json!(String::from_utf8_lossy(v)),
+
+            // same as decimal.handling.mode=string of debezium,
+            // temporal values are kept as the strings from the source db
+            ColValue::Decimal(v)
+            | ColValue::Time(v)
+            | ColValue::Date(v)
+            | ColValue::DateTime(v)
+            | ColValue::Timestamp(v)
+            | ColValue::String(v)
+            | ColValue::Set2(v)
+            | ColValue::Enum2(v)
+            | ColValue::Json2(v) => json!(v),
+
+            ColValue::Json3(v) => json!(v.to_string()),
+            ColValue::MongoDoc(v) => json!(v.to_string()),
+
+            ColValue::Bool(v) => json!(v),
+            ColValue::None => Value::Null,
+        }
+    }
+
+    fn col_value_to_schema_type(value: &ColValue) -> &'static str {
+        match value {
+            ColValue::Bool(_) => "boolean",
+            ColValue::Tiny(_) => "int8",
+            ColValue::UnsignedTiny(_) | ColValue::Short(_) => "int16",
+            ColValue::UnsignedShort(_) | ColValue::Long(_) | ColValue::Year(_) => "int32",
+            ColValue::UnsignedLong(_)
+            | ColValue::LongLong(_)
+            // may lose precision
+            | ColValue::UnsignedLongLong(_)
+            | ColValue::Bit(_)
+            | ColValue::Set(_)
+            | ColValue::Enum(_) => "int64",
+            ColValue::Float(_) => "float32",
+            ColValue::Double(_) => "float64",
+            ColValue::Blob(_) | ColValue::RawString(_) => "bytes",
+            // unknown
+            ColValue::None => "",
+            _ => "string",
+        }
+    }
+
+    fn json_to_col_value(value: Value, col_type: Option<&str>) -> anyhow::Result<ColValue> {
+        let col_value = match (value, col_type) {
+            (Value::Null, _) => ColValue::None,
+            (Value::String(v), Some("bytes")) => ColValue::Blob(hex::decode(v)?),
+            (Value::String(v), _) => ColValue::String(v),
+            (Value::Bool(v), _) => ColValue::Bool(v),
+            (Value::Number(v), Some("float32")) | (Value::Number(v), Some("float64")) => {
+                ColValue::Double(v.as_f64().unwrap_or_default())
+            }
+            (Value::Number(v), _) => {
+                if let Some(i) = v.as_i64() {
+                    ColValue::LongLong(i)
+                } else if let Some(u) = v.as_u64() {
+                    ColValue::UnsignedLongLong(u)
+                } else {
+                    ColValue::Double(v.as_f64().unwrap_or_default())
+                }
+            }
+            (value, _) => ColValue::String(value.to_string()),
+        };
+        Ok(col_value)
+    }
+
+    fn parse_pg_lsn(lsn: &str) -> Option<u64> {
+        // 0/16B3748 -> (0 << 32) + 0x16B3748
+        let (high, low) = lsn.split_once('/')?;
+        let high = u64::from_str_radix(high, 16).ok()?;
+        let low = u64::from_str_radix(low, 16).ok()?;
+        Some((high << 32) + low)
+    }
+
+    async fn get_tb_meta<'a>(
+        &'a mut self,
+        row_data: &RowData,
+    ) -> anyhow::Result<Option<&'a RdbTbMeta>> {
+        if let Some(meta_manager) = self.meta_manager.as_mut() {
+            let tb_meta = meta_manager
+                .get_tb_meta(&row_data.schema, &row_data.tb)
+                .await?;
+            return Ok(Some(tb_meta));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_row_data_to_debezium() {
+        let mut before = HashMap::new();
+        before.insert("id".to_string(), ColValue::LongLong(1));
+        before.insert("name".to_string(), ColValue::String("a".into()));
+        before.insert("data".to_string(), ColValue::Blob(vec![1, 2, 255]));
+        before.insert("score".to_string(), ColValue::Double(1.5));
+        before.insert("null_col".to_string(), ColValue::None);
+        let mut after = before.clone();
+        after.insert("name".to_string(), ColValue::String("b".into()));
+
+        let position = Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: "mysql-bin.000001".into(),
+            next_event_position: 1234,
+            gtid_set: String::new(),
+            timestamp: Position::format_timestamp_millis(1700000000123),
+        };
+
+        for with_schema in [true, false] {
+            let mut converter = DebeziumConverter::new(None, DbType::Mysql, with_schema);
+            let row_datas = [
+                RowData::new(
+                    "db1".into(),
+                    "tb1".into(),
+                    RowType::Insert,
+                    None,
+                    Some(after.clone()),
+                ),
+                RowData::new(
+                    "db1".into(),
+                    "tb1".into(),
+                    RowType::Update,
+                    Some(before.clone()),
+                    Some(after.clone()),
+                ),
+                RowData::new(
+                    "db1".into(),
+                    "tb1".into(),
+                    RowType::Delete,
+                    Some(before.clone()),
+                    None,
+                ),
+            ];
+
+            for row_data in row_datas {
+                let payload = converter
+                    .row_data_to_debezium_value(&row_data, &position)
+                    .await
+                    .unwrap();
+
+                let value: Value = serde_json::from_slice(&payload).unwrap();
+                let event = if with_schema { &value[PAYLOAD] } else { &value };
+                assert_eq!(event[SOURCE]["file"], "mysql-bin.000001");
+                assert_eq!(event[SOURCE]["pos"], 1234);
+                assert_eq!(event[SOURCE][TS_MS], 1700000000123_i64);
+
+                let mut dt_datas = converter.debezium_value_to_dt_data(&payload).unwrap();
+                assert_eq!(dt_datas.len(), 1);
+                match dt_datas.remove(0) {
+                    DtData::Dml {
+                        row_data: decoded_row_data,
+                    } if with_schema => assert_eq!(decoded_row_data, row_data),
+                    // blob cols are decoded as strings without schema
+                    DtData::Dml {
+                        row_data: decoded_row_data,
+                    } => {
+                        assert_eq!(decoded_row_data.row_type, row_data.row_type);
+                        assert_eq!(decoded_row_data.schema, row_data.schema);
+                        assert_eq!(decoded_row_data.tb, row_data.tb);
+                    }
+                    _ => panic!(),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod debezium_converter;
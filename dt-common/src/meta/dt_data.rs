@@ -23,26 +23,13 @@ impl DtItem {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DtData {
-    Struct {
-        struct_data: StructData,
-    },
-    Ddl {
-        ddl_data: DdlData,
-    },
-    Dml {
-        row_data: RowData,
-    },
+    Struct { struct_data: StructData },
+    Ddl { ddl_data: DdlData },
+    Dml { row_data: RowData },
     Begin {},
-    Commit {
-        xid: String,
-    },
-    #[serde(skip)]
-    Redis {
-        entry: RedisEntry,
-    },
-    Foxlake {
-        file_meta: S3FileMeta,
-    },
+    Commit { xid: String },
+    Redis { entry: RedisEntry },
+    Foxlake { file_meta: S3FileMeta },
 }
 
 impl DtData {
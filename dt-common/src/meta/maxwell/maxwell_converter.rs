@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    error::Error,
+    meta::{
+        col_value::ColValue,
+        ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
+        dt_data::DtData,
+        position::Position,
+        rdb_meta_manager::RdbMetaManager,
+        rdb_tb_meta::RdbTbMeta,
+        row_data::RowData,
+        row_type::RowType,
+    },
+};
+
+use anyhow::bail;
+
+const DATABASE: &str = "database";
+const TABLE: &str = "table";
+const TYPE: &str = "type";
+const TS: &str = "ts";
+const XID: &str = "xid";
+const COMMIT: &str = "commit";
+const POSITION: &str = "position";
+const DATA: &str = "data";
+const OLD: &str = "old";
+const SQL: &str = "sql";
+const INSERT: &str = "insert";
+const UPDATE: &str = "update";
+const DELETE: &str = "delete";
+
+/// Converts RowData to / from the messages of Maxwell, e.g.
+/// {"database": "db1", "table": "tb1", "type": "update", "ts": 1700000000, "xid": 1234, "commit": true,
+///  "position": "mysql-bin.000001:1234", "data": {"id": 1, "name": "b"}, "old": {"name": "a"}}
+/// binary values are base64 strings, same as Maxwell
+#[derive(Clone)]
+pub struct MaxwellConverter {
+    pub meta_manager: Option<RdbMetaManager>,
+}
+
+impl MaxwellConverter {
+    pub fn new(meta_manager: Option<RdbMetaManager>) -> Self {
+        Self { meta_manager }
+    }
+
+    pub fn refresh_meta(&mut self, data: &[DdlData]) {
+        if let Some(meta_manager) = &mut self.meta_manager {
+            for ddl_data in data.iter() {
+                meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+            }
+        }
+    }
+
+    /// xid is the id of the source transaction, and only the last row of a transaction has commit: true,
+    /// both are set only if the commit of the transaction is sinked in the same batch as the row
+    pub async fn row_data_to_maxwell_value(
+        &mut self,
+        row_data: &RowData,
+        position: &Position,
+    ) -> anyhow::Result<Vec<u8>> {
+        let tb_meta = self.get_tb_meta(row_data).await?;
+
+        let (row_type, image) = match row_data.row_type {
+            RowType::Insert => (INSERT, row_data.after.as_ref()),
+            RowType::Update => (UPDATE, row_data.after.as_ref()),
+            RowType::Delete => (DELETE, row_data.before.as_ref()),
+        };
+        let empty = HashMap::new();
+        let image = image.unwrap_or(&empty);
+        let cols = match tb_meta {
+            Some(tb_meta) => tb_meta.cols.clone(),
+            None => {
+                let mut cols: Vec<String> = image.keys().cloned().collect();
+                cols.sort();
+                cols
+            }
+        };
+
+        let mut data = Map::new();
+        for col in cols.iter() {
+            let col_value = image.get(col).unwrap_or(&ColValue::None);
+            data.insert(col.clone(), Self::col_value_to_maxwell(col_value));
+        }
+
+        let mut value = json!({
+            DATABASE: row_data.schema,
+            TABLE: row_data.tb,
+            TYPE: row_type,
+            DATA: data,
+        });
+        if !row_data.xid.is_empty() {
+            value[XID] = match row_data.xid.parse::<u64>() {
+                Ok(xid) => json!(xid),
+                Err(_) => json!(row_data.xid),
+            };
+        }
+        if row_data.is_last_in_tx {
+            value[COMMIT] = json!(true);
+        }
+
+        // old contains the changed cols only
+        if let (RowType::Update, Some(before)) = (&row_data.row_type, &row_data.before) {
+            let mut old = Map::new();
+            for col in cols.iter() {
+                let before_value = before.get(col).unwrap_or(&ColValue::None);
+                if image.get(col) != Some(before_value) {
+                    old.insert(col.clone(), Self::col_value_to_maxwell(before_value));
+                }
+            }
+            value[OLD] = Value::Object(old);
+        }
+
+        Self::set_position(&mut value, position);
+        Ok(value.to_string().into_bytes())
+    }
+
+    /// Ddl is sent as a message like {"type": "table-alter", "database": "db1", "table": "tb1",
+    /// "sql": "ALTER TABLE ...", "ts": 1700000000}, without the def of the table
+    pub fn ddl_data_to_maxwell_value(&self, ddl_data: &DdlData, position: &Position) -> Vec<u8> {
+        let (schema, tb) = ddl_data.get_schema_tb();
+        let ddl_type = match ddl_data.ddl_type {
+            DdlType::CreateDatabase | DdlType::CreateSchema => "database-create",
+            DdlType::DropDatabase | DdlType::DropSchema => "database-drop",
+            DdlType::AlterDatabase | DdlType::AlterSchema => "database-alter",
+            DdlType::CreateTable => "table-create",
+            DdlType::DropTable => "table-drop",
+            _ => "table-alter",
+        };
+
+        let mut value = json!({
+            DATABASE: schema,
+            TABLE: tb,
+            TYPE: ddl_type,
+            SQL: ddl_data.query,
+        });
+        Self::set_position(&mut value, position);
+        value.to_string().into_bytes()
+    }
+
+    fn set_position(value: &mut Value, position: &Position) {
+        let ts_millis = match position {
+            Position::MysqlCdc {
+                binlog_filename,
+                next_event_position,
+                timestamp,
+                ..
+            } => {
+                value[POSITION] = json!(format!("{}:{}", binlog_filename, next_event_position));
+                Position::parse_timestamp_millis(timestamp)
+            }
+            Position::PgCdc { timestamp, .. } => Position::parse_timestamp_millis(timestamp),
+            _ => 0,
+        };
+        // ts of maxwell is in seconds
+        value[TS] = if ts_millis > 0 {
+            json!(ts_millis / 1000)
+        } else {
+            json!(Utc::now().timestamp())
+        };
+    }
+
+    /// Maxwell messages have no column types, binary values are decoded by the meta data of
+    /// the target table if available, otherwise they are kept as base64 strings.
+    /// Ddl and bootstrap-start / bootstrap-complete messages are skipped
+    pub async fn maxwell_value_to_dt_data(
+        &mut self,
+        payload: &[u8],
+    ) -> anyhow::Result<Vec<DtData>> {
+        let mut value: Value = serde_json::from_slice(payload)?;
+        if !value.is_object() {
+            bail! {Error::Unexpected(format!(
+                "invalid maxwell message: {}",
+                String::from_utf8_lossy(payload)
+            ))}
+        }
+        let schema = value[DATABASE].as_str().unwrap_or_default().to_string();
+        let tb = value[TABLE].as_str().unwrap_or_default().to_string();
+
+        let row_type = match value[TYPE].as_str() {
+            Some(INSERT) | Some("bootstrap-insert") => RowType::Insert,
+            Some(UPDATE) => RowType::Update,
+            Some(DELETE) => RowType::Delete,
+            // table-create, database-alter, bootstrap-start, ...
+            Some(row_type) if row_type.contains('-') => return Ok(Vec::new()),
+            row_type => {
+                bail! {Error::Unexpected(format!(
+                    "unsupported maxwell type: {:?}, schema: {}, tb: {}",
+                    row_type, schema, tb
+                ))}
+            }
+        };
+
+        let mut row_data = RowData::new(schema, tb, row_type, None, None);
+        let binary_cols = match self.get_tb_meta(&row_data).await? {
+            Some(tb_meta) => Self::get_binary_cols(tb_meta),
+            None => Vec::new(),
+        };
+
+        let image = Self::maxwell_to_col_values(value[DATA].take(), &binary_cols)?;
+        match row_data.row_type {
+            RowType::Insert => row_data.after = Some(image),
+            RowType::Delete => row_data.before = Some(image),
+            RowType::Update => {
+                // before = data overwritten by old
+                let mut before = image.clone();
+                before.extend(Self::maxwell_to_col_values(
+                    value[OLD].take(),
+                    &binary_cols,
+                )?);
+                row_data.before = Some(before);
+                row_data.after = Some(image);
+            }
+        }
+        row_data.refresh_data_size();
+        Ok(vec![DtData::Dml { row_data }])
+    }
+
+    fn get_binary_cols(tb_meta: &RdbTbMeta) -> Vec<String> {
+        let mut binary_cols = Vec::new();
+        for (col, origin_type) in tb_meta.col_origin_type_map.iter() {
+            let origin_type = origin_type.to_lowercase();
+            if origin_type.contains("binary")
+                || origin_type.contains("blob")
+                || origin_type == "bytea"
+            {
+                binary_cols.push(col.clone());
+            }
+        }
+        binary_cols
+    }
+
+    fn maxwell_to_col_values(
+        value: Value,
+        binary_cols: &[String],
+    ) -> anyhow::Result<HashMap<String, ColValue>> {
+        let mut col_values = HashMap::new();
+        if let Value::Object(map) = value {
+            for (col, value) in map {
+                let is_binary = binary_cols.contains(&col);
+                col_values.insert(col, Self::maxwell_to_col_value(value, is_binary)?);
+            }
+        }
+        Ok(col_values)
+    }
+
+    fn col_value_to_maxwell(value: &ColValue) -> Value {
+        match value {
+            ColValue::Tiny(v) => json!(v),
+            ColValue::UnsignedTiny(v) => json!(v),
+            ColValue::Short(v) => json!(v),
+            ColValue::UnsignedShort(v) => json!(v),
+            ColValue::Long(v) => json!(v),
+            ColValue::UnsignedLong(v) => json!(v),
+            ColValue::LongLong(v) => json!(v),
+            ColValue::UnsignedLongLong(v) => json!(v),
+            ColValue::Year(v) => json!(v),
+            ColValue::Bit(v) => json!(v),
+            ColValue::Set(v) => json!(v),
+            ColValue::Enum(v) => json!(v),
+            ColValue::Float(v) => json!(v),
+            ColValue::Double(v) => json!(v),
+            ColValue::Bool(v) => json!(v),
+
+            // same as Maxwell
+            ColValue::Blob(v) | ColValue::RawString(v) => json!(STANDARD.encode(v)),
+
+            // json values are embedded as objects, same as Maxwell
+            ColValue::Json(v) => Self::str_to_json(&String::from_utf8_lossy(v)),
+            ColValue::Json2(v) => Self::str_to_json(v),
+            ColValue::Json3(v) => v.clone(),
+
+            // decimals are kept as strings to avoid losing precision
+            ColValue::Decimal(v)
+            | ColValue::Time(v)
+            | ColValue::Date(v)
+            | ColValue::DateTime(v)
+            | ColValue::Timestamp(v)
+            | ColValue::String(v)
+            | ColValue::Set2(v)
+            | ColValue::Enum2(v) => json!(v),
+
+            ColValue::MongoDoc(v) => json!(v.to_string()),
+            ColValue::None => Value::Null,
+        }
+    }
+
+    fn maxwell_to_col_value(value: Value, is_binary: bool) -> anyhow::Result<ColValue> {
+        let col_value = match value {
+            Value::Null => ColValue::None,
+            Value::Bool(v) => ColValue::Bool(v),
+            Value::String(v) if is_binary => ColValue::Blob(STANDARD.decode(v)?),
+            Value::String(v) => ColValue::String(v),
+            Value::Number(v) => {
+                if let Some(i) = v.as_i64() {
+                    ColValue::LongLong(i)
+                } else if let Some(u) = v.as_u64() {
+                    ColValue::UnsignedLongLong(u)
+                } else {
+                    ColValue::Double(v.as_f64().unwrap_or_default())
+                }
+            }
+            // json values
+            value => ColValue::String(value.to_string()),
+        };
+        Ok(col_value)
+    }
+
+    fn str_to_json(v: &str) -> Value {
+        match serde_json::from_str(v) {
+            Ok(value) => value,
+            Err(_) => json!(v),
+        }
+    }
+
+    async fn get_tb_meta<'a>(
+        &'a mut self,
+        row_data: &RowData,
+    ) -> anyhow::Result<Option<&'a RdbTbMeta>> {
+        if let Some(meta_manager) = self.meta_manager.as_mut() {
+            let tb_meta = meta_manager
+                .get_tb_meta(&row_data.schema, &row_data.tb)
+                .await?;
+            return Ok(Some(tb_meta));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_row_data_to_maxwell() {
+        let mut before = HashMap::new();
+        before.insert("id".to_string(), ColValue::LongLong(1));
+        before.insert("name".to_string(), ColValue::String("a".into()));
+        before.insert("score".to_string(), ColValue::Double(1.5));
+        before.insert("null_col".to_string(), ColValue::None);
+        let mut after = before.clone();
+        after.insert("name".to_string(), ColValue::String("b".into()));
+
+        let position = Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: "mysql-bin.000001".into(),
+            next_event_position: 1234,
+            gtid_set: String::new(),
+            timestamp: Position::format_timestamp_millis(1700000000123),
+        };
+
+        let mut converter = MaxwellConverter::new(None);
+        let row_datas = [
+            RowData::new(
+                "db1".into(),
+                "tb1".into(),
+                RowType::Insert,
+                None,
+                Some(after.clone()),
+            ),
+            RowData::new(
+                "db1".into(),
+                "tb1".into(),
+                RowType::Update,
+                Some(before.clone()),
+                Some(after.clone()),
+            ),
+            RowData::new(
+                "db1".into(),
+                "tb1".into(),
+                RowType::Delete,
+                Some(before.clone()),
+                None,
+            ),
+        ];
+
+        let count = row_datas.len();
+        for (i, mut row_data) in row_datas.into_iter().enumerate() {
+            row_data.xid = "5678".into();
+            row_data.is_last_in_tx = i == count - 1;
+            let payload = converter
+                .row_data_to_maxwell_value(&row_data, &position)
+                .await
+                .unwrap();
+
+            let value: Value = serde_json::from_slice(&payload).unwrap();
+            assert_eq!(value[TS], 1700000000);
+            assert_eq!(value[POSITION], "mysql-bin.000001:1234");
+            assert_eq!(value[XID], 5678);
+            if row_data.is_last_in_tx {
+                assert_eq!(value[COMMIT], true);
+            } else {
+                assert!(value.get(COMMIT).is_none());
+            }
+            if row_data.row_type == RowType::Update {
+                // only the changed cols
+                assert_eq!(value[OLD], json!({"name": "a"}));
+            }
+
+            let dt_datas = converter.maxwell_value_to_dt_data(&payload).await.unwrap();
+            assert_eq!(dt_datas.len(), 1);
+            if let DtData::Dml {
+                row_data: decoded_row_data,
+            } = &dt_datas[0]
+            {
+                // xid and commit are not decoded
+                row_data.xid.clear();
+                row_data.is_last_in_tx = false;
+                assert_eq!(decoded_row_data, &row_data);
+            } else {
+                panic!()
+            }
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod maxwell_converter;
@@ -2,13 +2,16 @@
 
 pub mod adaptor;
 pub mod avro;
+pub mod canal;
 pub mod col_value;
 pub mod ddl_meta;
+pub mod debezium;
 pub mod dt_data;
 pub mod dt_queue;
 pub mod foreign_key;
 pub mod foxlake;
 pub mod kafka;
+pub mod maxwell;
 pub mod mongo;
 pub mod mysql;
 pub mod pg;
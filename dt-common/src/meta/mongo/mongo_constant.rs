@@ -6,4 +6,6 @@ impl MongoConstants {
     pub const DIFF_DOC: &'static str = "diff_doc";
     pub const SET: &'static str = "$set";
     pub const UNSET: &'static str = "$unset";
+    pub const PUSH: &'static str = "$push";
+    pub const PULL: &'static str = "$pull";
 }
@@ -1,7 +1,7 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use anyhow::Context;
-use chrono::DateTime;
+use chrono::{DateTime, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -17,6 +17,10 @@ pub enum Position {
         partition: i32,
         offset: i64,
     },
+    KafkaPartitions {
+        // topic -> partition -> offset of the last message
+        topics: BTreeMap<String, BTreeMap<i32, i64>>,
+    },
     RdbSnapshot {
         db_type: String,
         schema: String,
@@ -45,6 +49,12 @@ pub enum Position {
         operation_time: u32,
         timestamp: String,
     },
+    MongoShardCdc {
+        // shard name -> (time, increment) of the last oplog ts of the shard
+        shards: BTreeMap<String, (u32, u32)>,
+        operation_time: u32,
+        timestamp: String,
+    },
     Redis {
         repl_id: String,
         repl_port: u64,
@@ -52,6 +62,11 @@ pub enum Position {
         now_db_id: i64,
         timestamp: String,
     },
+    RedisCluster {
+        // master node address -> Position::Redis of the node
+        nodes: BTreeMap<String, Position>,
+        timestamp: String,
+    },
     FoxlakeS3 {
         schema: String,
         tb: String,
@@ -67,6 +82,14 @@ impl Position {
             String::new()
         }
     }
+
+    /// Reverse of format_timestamp_millis, 0 if the timestamp is empty or invalid
+    pub fn parse_timestamp_millis(timestamp: &str) -> i64 {
+        match NaiveDateTime::from_str(&timestamp.replacen(' ', "T", 1)) {
+            Ok(datetime) => datetime.and_utc().timestamp_millis(),
+            Err(_) => 0,
+        }
+    }
 }
 
 impl std::fmt::Display for Position {
@@ -121,6 +144,12 @@ mod test {
             "2023-03-28 05:33:47.000",
             Position::format_timestamp_millis(1679981627 * 1000)
         );
+
+        assert_eq!(
+            1679981627123,
+            Position::parse_timestamp_millis("2023-03-28 05:33:47.123")
+        );
+        assert_eq!(0, Position::parse_timestamp_millis(""));
     }
 
     #[test]
@@ -128,6 +157,9 @@ mod test {
         let strs = [
             r#"{"type":"None"}"#,
             r#"{"type":"RdbSnapshot","db_type":"mysql","schema":"test_db_1","tb":"numeric_table","order_col":"f_0","value":"127"}"#,
+            r#"{"type":"MongoShardCdc","shards":{"shard01":[1728525445,1],"shard02":[1728525446,3]},"operation_time":1728525446,"timestamp":"2024-10-10 01:57:26.000"}"#,
+            r#"{"type":"KafkaPartitions","topics":{"test":{"0":10,"1":-1},"test2":{"0":3}}}"#,
+            r#"{"type":"RedisCluster","nodes":{"172.28.0.11:6379":{"type":"Redis","repl_id":"5b1ab3e0e8b4e7c4f4c5f0a0e57c4f3b3a1d4c2e","repl_port":10008,"repl_offset":3021,"now_db_id":0,"timestamp":""}},"timestamp":""}"#,
         ];
 
         for str in strs {
@@ -8,4 +8,6 @@ pub struct ClusterNode {
     pub port: String,
     pub address: String,
     pub slots: Vec<u16>,
+    // slots in MIGRATING / IMPORTING state, e.g. [93->-292f8b365bb7edb5e285caf0b7e6ddc7265d2f4f]
+    pub open_slots: Vec<u16>,
 }
@@ -1,6 +1,7 @@
 pub mod cluster_node;
 pub mod command;
 pub mod redis_entry;
+pub mod redis_json_converter;
 pub mod redis_object;
 pub mod redis_statistic_type;
 pub mod redis_write_method;
@@ -1,11 +1,14 @@
 use anyhow::Context;
+use serde::{Deserialize, Serialize};
 
 use super::{
     command::key_parser::KeyParser,
     redis_object::{RedisCmd, RedisObject, RedisString},
 };
 
-#[derive(Debug, Clone)]
+// value and raw_bytes of snapshot entries are not serialized,
+// they should be converted to cmds before sending to consumers
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RedisEntry {
     pub id: u64,
     // whether the command is decoded from dump.rdb file
@@ -15,14 +18,29 @@ pub struct RedisEntry {
 
     pub expire_ms: i64,
     pub key: RedisString,
+    #[serde(skip)]
     pub value: RedisObject,
     pub value_type_byte: u8,
+    #[serde(skip)]
     pub raw_bytes: Vec<u8>,
 
     pub cmd: RedisCmd,
     pub data_size: usize,
     pub slot: i32,
     pub freq: i64,
+    // set if value only holds a chunk of the elements of a big key
+    #[serde(skip)]
+    pub chunk: Option<RedisEntryChunk>,
+}
+
+/// Big collections may be extracted in chunks, e.g. by RedisCheckExtractor
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedisEntryChunk {
+    // number of elements extracted before this chunk
+    pub offset: usize,
+    // number of elements of the whole key
+    pub total_len: usize,
+    pub is_last: bool,
 }
 
 impl RedisEntry {
@@ -43,6 +61,7 @@ impl RedisEntry {
             data_size: 0,
             slot: 0,
             freq: -1,
+            chunk: None,
         }
     }
 
@@ -0,0 +1,109 @@
+use anyhow::bail;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+
+use crate::{
+    error::Error,
+    meta::{
+        dt_data::DtData,
+        redis::{redis_entry::RedisEntry, redis_object::RedisCmd},
+    },
+};
+
+const DB_ID: &str = "db_id";
+const CMD: &str = "cmd";
+const KEYS: &str = "keys";
+const ARGS: &str = "args";
+const EXPIRE_MS: &str = "expire_ms";
+const POSITION: &str = "position";
+
+/// Converts a redis cmd to / from a json message, e.g.
+/// {"db_id": 0, "cmd": "SET", "keys": ["k1"], "args": ["U0VU", "azE=", "djE="], "expire_ms": 0,
+///  "position": "..."}
+/// args are base64 strings of the raw bytes, including the cmd name
+pub struct RedisJsonConverter {}
+
+impl RedisJsonConverter {
+    pub fn redis_entry_to_json_value(entry: &RedisEntry, position: &str) -> Vec<u8> {
+        let args: Vec<String> = entry.cmd.args.iter().map(|i| STANDARD.encode(i)).collect();
+        let value = json!({
+            DB_ID: entry.db_id,
+            CMD: entry.cmd.get_name(),
+            KEYS: entry.cmd.keys,
+            ARGS: args,
+            EXPIRE_MS: entry.expire_ms,
+            POSITION: position,
+        });
+        value.to_string().into_bytes()
+    }
+
+    pub fn json_value_to_dt_data(payload: &[u8]) -> anyhow::Result<DtData> {
+        let value: Value = serde_json::from_slice(payload)?;
+        let (Some(db_id), Some(args)) = (value[DB_ID].as_i64(), value[ARGS].as_array()) else {
+            bail! {Error::Unexpected(format!(
+                "invalid redis json message: {}",
+                String::from_utf8_lossy(payload)
+            ))}
+        };
+
+        let mut decoded_args = Vec::with_capacity(args.len());
+        for arg in args {
+            decoded_args.push(STANDARD.decode(arg.as_str().unwrap_or_default())?);
+        }
+        let keys = value[KEYS]
+            .as_array()
+            .map(|keys| {
+                keys.iter()
+                    .map(|i| i.as_str().unwrap_or_default().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut entry = RedisEntry::new();
+        entry.db_id = db_id;
+        entry.cmd = RedisCmd::from_args(decoded_args);
+        entry.cmd.keys = keys;
+        entry.expire_ms = value[EXPIRE_MS].as_i64().unwrap_or_default();
+        entry.data_size = entry.cmd.get_malloc_size();
+        Ok(DtData::Redis { entry })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redis_entry_to_json() {
+        let mut entry = RedisEntry::new();
+        entry.db_id = 2;
+        entry.expire_ms = 1000;
+        entry.cmd = RedisCmd::from_args(vec![
+            b"MSET".to_vec(),
+            b"k1".to_vec(),
+            vec![0, 255, 1],
+            b"k2".to_vec(),
+            b"v2".to_vec(),
+        ]);
+        entry.cmd.keys = vec!["k1".into(), "k2".into()];
+
+        let payload = RedisJsonConverter::redis_entry_to_json_value(&entry, "position");
+        let value: Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(value[CMD], "MSET");
+        assert_eq!(value[ARGS][2], STANDARD.encode([0, 255, 1]));
+
+        if let DtData::Redis {
+            entry: decoded_entry,
+        } = RedisJsonConverter::json_value_to_dt_data(&payload).unwrap()
+        {
+            assert_eq!(decoded_entry.db_id, entry.db_id);
+            assert_eq!(decoded_entry.expire_ms, entry.expire_ms);
+            assert_eq!(decoded_entry.cmd.args, entry.cmd.args);
+            assert_eq!(decoded_entry.cmd.keys, entry.cmd.keys);
+        } else {
+            panic!()
+        }
+
+        assert!(RedisJsonConverter::json_value_to_dt_data(b"{\"cmd\": \"SET\"}").is_err());
+    }
+}
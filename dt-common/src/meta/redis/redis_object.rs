@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use super::command::key_parser::KeyParser;
 
 const TYPE_STRING: &str = "string";
@@ -11,7 +13,7 @@ const TYPE_MODULE: &str = "module";
 const TYPE_STREAM: &str = "stream";
 const TYPE_UNKNOWN: &str = "unknown";
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum RedisObject {
     String(StringObject),
     List(ListObject),
@@ -20,6 +22,7 @@ pub enum RedisObject {
     Zset(ZsetObject),
     Module(ModuleObject),
     Stream(StreamObject),
+    #[default]
     Unknown,
 }
 
@@ -82,12 +85,33 @@ impl ListObject {
     }
 }
 
+/// Values saved by a module type, e.g. RedisModule_SaveUnsigned / RedisModule_SaveStringBuffer
+#[derive(Debug, Clone)]
+pub enum ModuleValue {
+    Int(i64),
+    Uint(u64),
+    Float(f32),
+    Double(f64),
+    String(RedisString),
+}
+
 #[derive(Debug, Clone)]
-pub struct ModuleObject {}
+pub struct ModuleObject {
+    pub key: RedisString,
+    // 9 chars module type name, e.g. ReJSON-RL, MBbloom--
+    pub module_name: String,
+    pub encver: u64,
+    pub values: Vec<ModuleValue>,
+}
 
 impl ModuleObject {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            key: RedisString::new(),
+            module_name: String::new(),
+            encver: 0,
+            values: vec![],
+        }
     }
 }
 
@@ -158,7 +182,7 @@ impl ZsetObject {
 }
 
 /// raw bytes
-#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[derive(PartialEq, Eq, Clone, Debug, Hash, Serialize, Deserialize)]
 pub struct RedisString {
     pub bytes: Vec<u8>,
 }
@@ -199,7 +223,7 @@ impl From<RedisString> for String {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RedisCmd {
     pub args: Vec<Vec<u8>>,
     pub name: String,
@@ -330,7 +354,16 @@ impl RedisObject {
                 }
                 size + v.key.bytes.len()
             }
-            RedisObject::Module(_) => 0,
+            RedisObject::Module(v) => {
+                let mut size = 0;
+                for value in v.values.iter() {
+                    size += match value {
+                        ModuleValue::String(s) => s.bytes.len(),
+                        _ => 8,
+                    };
+                }
+                size + v.key.bytes.len()
+            }
             RedisObject::Unknown => 0,
         }
     }
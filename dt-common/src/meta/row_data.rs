@@ -12,7 +12,7 @@ use crate::meta::adaptor::{
 
 use super::{
     col_value::ColValue, mysql::mysql_tb_meta::MysqlTbMeta, pg::pg_tb_meta::PgTbMeta,
-    rdb_tb_meta::RdbTbMeta, row_type::RowType,
+    position::Position, rdb_tb_meta::RdbTbMeta, row_type::RowType,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -23,6 +23,16 @@ pub struct RowData {
     pub before: Option<HashMap<String, ColValue>>,
     pub after: Option<HashMap<String, ColValue>>,
     pub data_size: usize,
+    // the source position of the row, filled by the pipeline before sinking,
+    // for message formats carrying source positions, e.g. debezium_json
+    #[serde(skip)]
+    pub position: Position,
+    // the source transaction id, and whether the row is the last one of the transaction,
+    // filled by the pipeline only if the commit of the transaction is sinked with the row
+    #[serde(skip)]
+    pub xid: String,
+    #[serde(skip)]
+    pub is_last_in_tx: bool,
 }
 
 impl std::fmt::Display for RowData {
@@ -46,6 +56,9 @@ impl RowData {
             before,
             after,
             data_size: 0,
+            position: Position::None,
+            xid: String::new(),
+            is_last_in_tx: false,
         };
         me.data_size = me.get_data_malloc_size();
         me
@@ -65,19 +78,27 @@ impl RowData {
             before: self.after.clone(),
             after: self.before.clone(),
             data_size: self.data_size,
+            position: self.position.clone(),
+            xid: self.xid.clone(),
+            is_last_in_tx: self.is_last_in_tx,
         }
     }
 
     pub fn split_update_row_data(self) -> (RowData, RowData) {
-        let delete = RowData::new(
+        let mut delete = RowData::new(
             self.schema.clone(),
             self.tb.clone(),
             RowType::Delete,
             self.before,
             None,
         );
+        delete.position = self.position.clone();
+        delete.xid = self.xid.clone();
 
-        let insert = RowData::new(self.schema, self.tb, RowType::Insert, None, self.after);
+        let mut insert = RowData::new(self.schema, self.tb, RowType::Insert, None, self.after);
+        insert.position = self.position;
+        insert.xid = self.xid;
+        insert.is_last_in_tx = self.is_last_in_tx;
         (delete, insert)
     }
 
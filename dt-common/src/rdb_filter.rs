@@ -4,20 +4,22 @@ use crate::{
     config::{
         config_enums::DbType, config_token_parser::ConfigTokenParser, filter_config::FilterConfig,
     },
+    log_warn,
     meta::{
-        ddl_meta::ddl_type::DdlType, row_type::RowType,
+        ddl_meta::ddl_type::DdlType, redis::redis_entry::RedisEntry, row_type::RowType,
         struct_meta::structure::structure_type::StructureType,
     },
     utils::sql_util::SqlUtil,
 };
 
 use anyhow::Context;
-use regex::Regex;
+use regex::{bytes, Regex};
 use serde::{Deserialize, Serialize};
 
 type IgnoreCols = HashMap<(String, String), HashSet<String>>;
 
 const JSON_PREFIX: &str = "json:";
+const REGEX_PREFIX: &str = "regex:";
 
 #[derive(Debug, Clone)]
 pub struct RdbFilter {
@@ -31,6 +33,9 @@ pub struct RdbFilter {
     pub do_structures: HashSet<String>,
     pub do_ddls: HashSet<String>,
     pub ignore_cmds: HashSet<String>,
+    pub do_keys: Vec<bytes::Regex>,
+    pub ignore_keys: Vec<bytes::Regex>,
+    pub ignore_key_types: HashSet<String>,
     pub cache: HashMap<(String, String), bool>,
 }
 
@@ -47,6 +52,9 @@ impl RdbFilter {
             do_structures: Self::parse_single_tokens(&config.do_structures, db_type)?,
             do_ddls: Self::parse_single_tokens(&config.do_ddls, db_type)?,
             ignore_cmds: Self::parse_single_tokens(&config.ignore_cmds, db_type)?,
+            do_keys: Self::parse_key_patterns(&config.do_keys)?,
+            ignore_keys: Self::parse_key_patterns(&config.ignore_keys)?,
+            ignore_key_types: Self::parse_single_tokens(&config.ignore_key_types, db_type)?,
             cache: HashMap::new(),
         })
     }
@@ -117,6 +125,104 @@ impl RdbFilter {
         self.ignore_cmds.contains(cmd)
     }
 
+    pub fn has_key_filter(&self) -> bool {
+        !self.do_keys.is_empty()
+            || !self.ignore_keys.is_empty()
+            || !self.ignore_key_types.is_empty()
+    }
+
+    pub fn filter_key(&self, key: &[u8]) -> bool {
+        if self.ignore_keys.iter().any(|i| i.is_match(key)) {
+            return true;
+        }
+        !self.do_keys.is_empty() && !self.do_keys.iter().any(|i| i.is_match(key))
+    }
+
+    pub fn filter_key_type(&self, key_type: &str) -> bool {
+        self.ignore_key_types.contains(key_type)
+    }
+
+    /// filter redis entry by db, key type and keys,
+    /// keys of an aof command should have been parsed by KeyParser before,
+    /// for multi-key commands like DEL / MSET, the filtered keys will be removed from the command
+    pub fn filter_redis_entry(&mut self, entry: &mut RedisEntry) -> bool {
+        if self.filter_schema(&entry.db_id.to_string()) {
+            return true;
+        }
+
+        if entry.is_base {
+            return self.filter_key_type(&entry.get_type())
+                || self.filter_key(entry.key.as_bytes());
+        }
+
+        // commands of GENERIC group (DEL, EXPIRE, RENAME ...) can not be filtered by key type
+        if let Some(key_type) = Self::get_cmd_key_type(&entry.cmd.group) {
+            if self.filter_key_type(key_type) {
+                return true;
+            }
+        }
+
+        let cmd = &mut entry.cmd;
+        if cmd.keys.is_empty() {
+            return false;
+        }
+
+        // key_indexes are 1-based
+        let filtered_keys: Vec<bool> = cmd
+            .key_indexes
+            .iter()
+            .map(|i| self.filter_key(&cmd.args[i - 1]))
+            .collect();
+        if filtered_keys.iter().all(|i| *i) {
+            return true;
+        }
+        if !filtered_keys.iter().any(|i| *i) {
+            return false;
+        }
+
+        // some keys filtered, args of each key: [key] or [key, value]
+        let key_step = match cmd.name.as_str() {
+            "DEL" | "UNLINK" | "EXISTS" | "TOUCH" => 1,
+            "MSET" | "MSETNX" => 2,
+            _ => {
+                log_warn!(
+                    "keys of cmd partially filtered, but keys can not be removed from it, cmd: [{}]",
+                    cmd
+                );
+                return false;
+            }
+        };
+
+        let mut args = vec![cmd.args[0].clone()];
+        let mut keys = Vec::new();
+        let mut key_indexes = Vec::new();
+        for (i, key_index) in cmd.key_indexes.iter().enumerate() {
+            if filtered_keys[i] {
+                continue;
+            }
+            keys.push(cmd.keys[i].clone());
+            key_indexes.push(args.len() + 1);
+            args.extend_from_slice(&cmd.args[key_index - 1..key_index - 1 + key_step]);
+        }
+        cmd.args = args;
+        cmd.keys = keys;
+        cmd.key_indexes = key_indexes;
+        false
+    }
+
+    fn get_cmd_key_type(cmd_group: &str) -> Option<&'static str> {
+        match cmd_group {
+            "STRING" | "BITMAP" | "HYPERLOGLOG" => Some("string"),
+            "LIST" => Some("list"),
+            "HASH" => Some("hash"),
+            "SET" => Some("set"),
+            "SORTED_SET" | "GEO" => Some("zset"),
+            "STREAM" => Some("stream"),
+            "BLOOM" | "TAIRHASH" | "TAIRSTRING" | "TAIRZSET" => Some("module"),
+            _ => None,
+        }
+    }
+
     pub fn get_ignore_cols(&self, schema: &str, tb: &str) -> Option<&HashSet<String>> {
         self.ignore_cols.get(&(schema.to_string(), tb.to_string()))
     }
@@ -206,6 +312,66 @@ impl RdbFilter {
         ConfigTokenParser::parse_config(config_str, db_type, &delimiters)
     }
 
+    fn parse_key_patterns(config_str: &str) -> anyhow::Result<Vec<bytes::Regex>> {
+        // do_keys=user:*,order:?
+        // do_keys=json:["user:*","regex:^order:\\d+$"]
+        let config_str = config_str.trim();
+        let patterns: Vec<String> = if config_str.starts_with(JSON_PREFIX) {
+            serde_json::from_str(config_str.trim_start_matches(JSON_PREFIX))?
+        } else {
+            config_str
+                .split(',')
+                .map(|i| i.trim().to_string())
+                .filter(|i| !i.is_empty())
+                .collect()
+        };
+
+        let mut results = Vec::new();
+        for pattern in patterns {
+            let regex = if let Some(regex) = pattern.strip_prefix(REGEX_PREFIX) {
+                regex.to_string()
+            } else {
+                Self::glob_to_regex(&pattern)
+            };
+            let regex = bytes::Regex::new(&regex)
+                .with_context(|| format!("invalid key pattern: [{}]", pattern))?;
+            results.push(regex);
+        }
+        Ok(results)
+    }
+
+    fn glob_to_regex(glob: &str) -> String {
+        // same as redis glob-style patterns in KEYS / SCAN:
+        // * : matching any chars, ? : matching a single char, [abc] / [^a] / [a-z] : matching chars in set,
+        // \ : escaping the next char
+        let mut regex = String::from("(?s-u)^");
+        let mut chars = glob.chars();
+        let mut in_set = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        regex.push_str(&regex::escape(&next.to_string()));
+                    }
+                }
+                '*' if !in_set => regex.push_str(".*"),
+                '?' if !in_set => regex.push('.'),
+                '[' if !in_set => {
+                    in_set = true;
+                    regex.push('[');
+                }
+                ']' if in_set => {
+                    in_set = false;
+                    regex.push(']');
+                }
+                '^' | '-' if in_set => regex.push(c),
+                _ => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+        regex
+    }
+
     fn parse_ignore_cols(config_str: &str) -> anyhow::Result<IgnoreCols> {
         let mut results = IgnoreCols::new();
         if config_str.trim().is_empty() {
@@ -230,6 +396,8 @@ impl RdbFilter {
 #[cfg(test)]
 mod tests {
 
+    use crate::meta::redis::{command::key_parser::KeyParser, redis_object::RedisCmd};
+
     use super::*;
 
     #[test]
@@ -824,4 +992,78 @@ mod tests {
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Update));
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Delete));
     }
+
+    #[test]
+    fn test_rdb_filter_keys() {
+        let config = FilterConfig {
+            do_schemas: "*".to_string(),
+            do_keys: r#"json:["user:*","order:?","regex:^item:\\d+$","tag:[ab]"]"#.to_string(),
+            ignore_keys: "user:tmp:*".to_string(),
+            ..Default::default()
+        };
+        let rdb_fitler = RdbFilter::from_config(&config, &DbType::Redis).unwrap();
+        assert!(!rdb_fitler.filter_key(b"user:1"));
+        assert!(!rdb_fitler.filter_key(b"user:"));
+        assert!(rdb_fitler.filter_key(b"user:tmp:1"));
+        assert!(!rdb_fitler.filter_key(b"order:1"));
+        assert!(rdb_fitler.filter_key(b"order:12"));
+        assert!(!rdb_fitler.filter_key(b"item:123"));
+        assert!(rdb_fitler.filter_key(b"item:abc"));
+        assert!(!rdb_fitler.filter_key(b"tag:a"));
+        assert!(rdb_fitler.filter_key(b"tag:c"));
+        assert!(rdb_fitler.filter_key(b"other"));
+
+        // dots are not wildcards
+        let config = FilterConfig {
+            do_schemas: "*".to_string(),
+            do_keys: "a.b,c\\*".to_string(),
+            ..Default::default()
+        };
+        let rdb_fitler = RdbFilter::from_config(&config, &DbType::Redis).unwrap();
+        assert!(!rdb_fitler.filter_key(b"a.b"));
+        assert!(rdb_fitler.filter_key(b"axb"));
+        assert!(!rdb_fitler.filter_key(b"c*"));
+        assert!(rdb_fitler.filter_key(b"cc"));
+    }
+
+    #[test]
+    fn test_rdb_filter_redis_entry() {
+        let config = FilterConfig {
+            do_schemas: "*".to_string(),
+            ignore_keys: "tmp:*".to_string(),
+            ignore_key_types: "stream".to_string(),
+            ..Default::default()
+        };
+        let mut rdb_fitler = RdbFilter::from_config(&config, &DbType::Redis).unwrap();
+        let key_parser = KeyParser::new();
+        let build_entry = |args: &[&str]| {
+            let mut entry = RedisEntry::new();
+            entry.cmd = RedisCmd::from_str_args(args);
+            entry.cmd.parse_keys(&key_parser).unwrap();
+            entry
+        };
+
+        let mut entry = build_entry(&["XADD", "s1", "*", "f", "v"]);
+        assert!(rdb_fitler.filter_redis_entry(&mut entry));
+
+        let mut entry = build_entry(&["SET", "tmp:1", "v"]);
+        assert!(rdb_fitler.filter_redis_entry(&mut entry));
+
+        let mut entry = build_entry(&["SET", "k1", "v"]);
+        assert!(!rdb_fitler.filter_redis_entry(&mut entry));
+
+        // filtered keys removed from multi-key commands
+        let mut entry = build_entry(&["MSET", "k1", "v1", "tmp:1", "v2", "k2", "v3"]);
+        assert!(!rdb_fitler.filter_redis_entry(&mut entry));
+        assert_eq!(entry.cmd.to_string(), "MSET k1 v1 k2 v3");
+        assert_eq!(entry.cmd.keys, vec!["k1", "k2"]);
+        assert_eq!(entry.cmd.key_indexes, vec![2, 4]);
+
+        let mut entry = build_entry(&["DEL", "tmp:1", "k1", "tmp:2"]);
+        assert!(!rdb_fitler.filter_redis_entry(&mut entry));
+        assert_eq!(entry.cmd.to_string(), "DEL k1");
+
+        let mut entry = build_entry(&["DEL", "tmp:1", "tmp:2"]);
+        assert!(rdb_fitler.filter_redis_entry(&mut entry));
+    }
 }
@@ -0,0 +1,74 @@
+pub struct KafkaUtil {}
+
+impl KafkaUtil {
+    /// The partition chosen by the default partitioner of the Java client for a non-empty key:
+    /// toPositive(murmur2(key)) % partition_count
+    pub fn get_partition(key: &[u8], partition_count: i32) -> i32 {
+        if partition_count <= 0 {
+            return 0;
+        }
+        (Self::murmur2(key) & 0x7fffffff) % partition_count
+    }
+
+    /// Same as org.apache.kafka.common.utils.Utils.murmur2
+    pub fn murmur2(data: &[u8]) -> i32 {
+        let length = data.len();
+        let seed: u32 = 0x9747b28c;
+        let m: u32 = 0x5bd1e995;
+        let r = 24;
+
+        let mut h: u32 = seed ^ length as u32;
+        let mut chunks = data.chunks_exact(4);
+        for chunk in chunks.by_ref() {
+            let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            k = k.wrapping_mul(m);
+            k ^= k >> r;
+            k = k.wrapping_mul(m);
+            h = h.wrapping_mul(m);
+            h ^= k;
+        }
+
+        let tail = chunks.remainder();
+        if tail.len() >= 3 {
+            h ^= (tail[2] as u32) << 16;
+        }
+        if tail.len() >= 2 {
+            h ^= (tail[1] as u32) << 8;
+        }
+        if !tail.is_empty() {
+            h ^= tail[0] as u32;
+            h = h.wrapping_mul(m);
+        }
+
+        h ^= h >> 13;
+        h = h.wrapping_mul(m);
+        h ^= h >> 15;
+        h as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_murmur2() {
+        // cases from UtilsTest of the Java client
+        let cases = [
+            ("21", -973932308),
+            ("foobar", -790332482),
+            ("a-little-bit-long-string", -985981536),
+            ("a-little-bit-longer-string", -1486304829),
+            (
+                "lkjh234lh9fiuh90y23oiuhsafujhadof229phr9h19h89h8",
+                -58897971,
+            ),
+            ("abc", 479470107),
+        ];
+        for (key, hash) in cases {
+            assert_eq!(KafkaUtil::murmur2(key.as_bytes()), hash);
+        }
+
+        assert_eq!(KafkaUtil::get_partition("foobar".as_bytes(), 3), 0);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod file_util;
+pub mod kafka_util;
 pub mod redis_util;
 pub mod sql_util;
 pub mod time_util;
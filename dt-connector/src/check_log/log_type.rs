@@ -7,6 +7,8 @@ pub enum LogType {
     Miss,
     #[strum(serialize = "diff")]
     Diff,
+    #[strum(serialize = "extra")]
+    Extra,
     #[strum(serialize = "unknown")]
     Unknown,
 }
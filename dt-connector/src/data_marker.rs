@@ -2,8 +2,12 @@ use std::collections::HashSet;
 
 use dt_common::{
     config::{config_enums::DbType, data_marker_config::DataMarkerConfig},
-    meta::dt_data::DtData,
+    meta::{
+        col_value::ColValue, dt_data::DtData, mongo::mongo_constant::MongoConstants,
+        row_data::RowData,
+    },
 };
+use mongodb::bson::{doc, Document};
 
 #[derive(Debug, Clone, Default)]
 pub struct DataMarker {
@@ -91,6 +95,14 @@ impl DataMarker {
 
     pub fn refresh(&mut self, dt_data: &DtData) {
         match dt_data {
+            DtData::Dml { row_data } if self.db_type == DbType::Mongo => {
+                // mongo, data_origin_node is in _id of the marker doc, since updates in oplog
+                // contain only changed fields (n), and deletes contain only _id
+                if let Some(data_origin_node) = Self::get_mongo_data_origin_node(row_data) {
+                    self.data_origin_node = data_origin_node;
+                }
+            }
+
             DtData::Dml { row_data } => {
                 // refresh should be only called when dt_data is a data marker
                 // update data_origin_node
@@ -117,4 +129,28 @@ impl DataMarker {
     pub fn filter(&self) -> bool {
         self.filter
     }
+
+    /// The marker doc written by MongoSinker:
+    /// { _id: { data_origin_node: "", src_node: "", dst_node: "" }, n: 1 }
+    pub fn get_mongo_marker_id(&self) -> Document {
+        doc! {
+            DATA_ORIGIN_NODE: self.data_origin_node.clone(),
+            "src_node": self.src_node.clone(),
+            "dst_node": self.dst_node.clone(),
+        }
+    }
+
+    fn get_mongo_data_origin_node(row_data: &RowData) -> Option<String> {
+        for col_values in [&row_data.after, &row_data.before].into_iter().flatten() {
+            if let Some(ColValue::MongoDoc(doc)) = col_values.get(MongoConstants::DOC) {
+                if let Ok(data_origin_node) = doc
+                    .get_document(MongoConstants::ID)
+                    .and_then(|id| id.get_str(DATA_ORIGIN_NODE))
+                {
+                    return Some(data_origin_node.to_string());
+                }
+            }
+        }
+        None
+    }
 }
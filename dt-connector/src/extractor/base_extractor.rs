@@ -17,6 +17,7 @@ use dt_common::{
         ddl_meta::ddl_parser::DdlParser,
         dt_data::{DtData, DtItem},
         position::Position,
+        redis::redis_entry::RedisEntry,
         row_data::RowData,
     },
     time_filter::TimeFilter,
@@ -100,6 +101,15 @@ impl BaseExtractor {
         self.push_dt_data(DtData::Dml { row_data }, position).await
     }
 
+    pub async fn push_redis_entry(
+        &mut self,
+        entry: RedisEntry,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        let entry = self.router.route_redis_entry(entry);
+        self.push_dt_data(DtData::Redis { entry }, position).await
+    }
+
     pub async fn push_ddl(&mut self, ddl_data: DdlData, position: Position) -> anyhow::Result<()> {
         let ddl_data = self.router.route_ddl(ddl_data);
         while !self.buffer.is_empty() {
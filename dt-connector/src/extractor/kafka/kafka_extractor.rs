@@ -1,16 +1,35 @@
 use crate::extractor::resumer::cdc_resumer::CdcResumer;
 use crate::{extractor::base_extractor::BaseExtractor, Extractor};
-use anyhow::Context;
+use anyhow::{bail, Context};
 use async_trait::async_trait;
-use std::sync::{Arc, Mutex};
+use regex::Regex;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{atomic::Ordering, Arc, Mutex},
+    time::Duration,
+};
 
-use dt_common::log_info;
-use dt_common::meta::{avro::avro_converter::AvroConverter, position::Position, syncer::Syncer};
+use dt_common::meta::{
+    avro::avro_converter::AvroConverter, canal::canal_converter::CanalConverter,
+    debezium::debezium_converter::DebeziumConverter, maxwell::maxwell_converter::MaxwellConverter,
+    position::Position, redis::redis_json_converter::RedisJsonConverter, syncer::Syncer,
+};
+use dt_common::{
+    config::config_enums::{KafkaAssignment, MessageFormat},
+    error::Error,
+    log_error, log_info, log_warn,
+    utils::time_util::TimeUtil,
+};
 use rdkafka::{
-    consumer::{Consumer, StreamConsumer},
+    consumer::{CommitMode, Consumer, StreamConsumer},
     ClientConfig, Message, Offset, TopicPartitionList,
 };
 
+const METADATA_TIMEOUT_SECS: u64 = 10;
+
+// topic -> partition -> offset of the last message
+type TopicOffsets = BTreeMap<String, BTreeMap<i32, i64>>;
+
 pub struct KafkaExtractor {
     pub base_extractor: BaseExtractor,
     pub url: String,
@@ -18,8 +37,14 @@ pub struct KafkaExtractor {
     pub topic: String,
     pub partition: i32,
     pub offset: i64,
+    pub assignment: KafkaAssignment,
     pub ack_interval_secs: u64,
+    pub message_format: MessageFormat,
+    pub properties: HashMap<String, String>,
     pub avro_converter: AvroConverter,
+    pub debezium_converter: DebeziumConverter,
+    pub canal_converter: CanalConverter,
+    pub maxwell_converter: MaxwellConverter,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
 }
@@ -27,59 +52,256 @@ pub struct KafkaExtractor {
 #[async_trait]
 impl Extractor for KafkaExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
-        if let Position::Kafka { offset, .. } = &self.resumer.current_position {
-            self.offset = offset.to_owned();
+        let topic_offsets = match &self.resumer.current_position {
+            Position::KafkaPartitions { topics } => topics.clone(),
+            Position::Kafka {
+                topic,
+                partition,
+                offset,
+            } => BTreeMap::from([(topic.clone(), BTreeMap::from([(*partition, *offset)]))]),
+            _ => BTreeMap::new(),
         };
 
         log_info!(
-            "KafkaCdcExtractor starts, topic: {}, partition: {}, offset: {}",
+            "KafkaCdcExtractor starts, topic: {}, partition: {}, offset: {}, assignment: {:?}, message_format: {}",
             self.topic,
             self.partition,
-            self.offset
+            self.offset,
+            self.assignment,
+            self.message_format
         );
-        let consumer = self.create_consumer();
-        self.extract_avro(consumer).await
+        let consumer = Arc::new(self.create_consumer()?);
+        match self.assignment {
+            KafkaAssignment::Manual => self.assign(&consumer, &topic_offsets)?,
+            KafkaAssignment::Group => {
+                if !topic_offsets.is_empty() {
+                    log_warn!(
+                        "assignment: group resumes from the offsets committed by the group, position ignored: {}",
+                        self.resumer.current_position
+                    );
+                }
+                self.subscribe(&consumer)?
+            }
+        }
+        self.start_ack(consumer.clone());
+        self.extract_messages(&consumer, topic_offsets).await
     }
 }
 
 impl KafkaExtractor {
-    async fn extract_avro(&mut self, consumer: StreamConsumer) -> anyhow::Result<()> {
+    async fn extract_messages(
+        &mut self,
+        consumer: &StreamConsumer,
+        mut topic_offsets: TopicOffsets,
+    ) -> anyhow::Result<()> {
         loop {
             let msg = consumer
                 .recv()
                 .await
                 .with_context(|| format!("KafkaCdcExtractor failed, topic: {}", self.topic))?;
+            // messages of the same partition are received in order, and they are pushed into
+            // the same buffer, so the order of messages with the same key is kept
+            topic_offsets
+                .entry(msg.topic().to_string())
+                .or_default()
+                .insert(msg.partition(), msg.offset());
+            // tombstones have no payload
             if let Some(payload) = msg.payload() {
-                let dt_data = self
-                    .avro_converter
-                    .avro_value_to_dt_data(payload.to_vec())?;
-                let position = Position::Kafka {
-                    topic: self.topic.clone(),
-                    partition: self.partition,
-                    offset: msg.offset(),
+                if payload.is_empty() {
+                    continue;
+                }
+                // a canal message may contain multiple rows
+                let dt_datas = match self.message_format {
+                    MessageFormat::Avro => vec![self
+                        .avro_converter
+                        .avro_value_to_dt_data(payload.to_vec())?],
+                    MessageFormat::DebeziumJson => {
+                        self.debezium_converter.debezium_value_to_dt_data(payload)?
+                    }
+                    MessageFormat::CanalJson => {
+                        self.canal_converter.canal_value_to_dt_data(payload)?
+                    }
+                    MessageFormat::MaxwellJson => {
+                        self.maxwell_converter
+                            .maxwell_value_to_dt_data(payload)
+                            .await?
+                    }
+                    MessageFormat::Json => {
+                        vec![RedisJsonConverter::json_value_to_dt_data(payload)?]
+                    }
+                    // rejected when loading the task config
+                    MessageFormat::ConfluentAvro => {
+                        bail! {Error::ExtractorError(
+                            "message_format: confluent_avro is not supported by kafka extractor"
+                                .into()
+                        )}
+                    }
+                };
+                let position = Position::KafkaPartitions {
+                    topics: topic_offsets.clone(),
                 };
-                self.base_extractor.push_dt_data(dt_data, position).await?;
+                for dt_data in dt_datas {
+                    self.base_extractor
+                        .push_dt_data(dt_data, position.clone())
+                        .await?;
+                }
             }
         }
     }
 
-    fn create_consumer(&self) -> StreamConsumer {
+    fn create_consumer(&self) -> anyhow::Result<StreamConsumer> {
         let mut config = ClientConfig::new();
         config.set("bootstrap.servers", &self.url);
         config.set("group.id", &self.group);
         config.set("auto.offset.reset", "latest");
         config.set("session.timeout.ms", "10000");
+        // offsets are committed after data are sinked
+        config.set("enable.auto.commit", "false");
+        // e.g. security.protocol, sasl.mechanism, ssl.ca.location
+        for (key, value) in self.properties.iter() {
+            config.set(key, value);
+        }
+        Ok(config.create()?)
+    }
 
-        let consumer: StreamConsumer = config.create().unwrap();
-        // only support extract data from one topic, one partition
+    fn subscribe(&self, consumer: &StreamConsumer) -> anyhow::Result<()> {
+        // partitions are assigned by the consumer group, and consumption starts from
+        // the offsets committed by the group, rebalances are not handled, messages received
+        // but not acked before a partition is revoked will be consumed again by its new owner
+        let topics = self.get_topics();
+        let topics: Vec<&str> = topics.iter().map(|i| i.as_str()).collect();
+        consumer.subscribe(&topics)?;
+        Ok(())
+    }
+
+    fn assign(
+        &self,
+        consumer: &StreamConsumer,
+        topic_offsets: &TopicOffsets,
+    ) -> anyhow::Result<()> {
         let mut tpl = TopicPartitionList::new();
-        if self.offset >= 0 {
-            tpl.add_partition_offset(&self.topic, self.partition, Offset::Offset(self.offset))
-                .unwrap();
+        for (topic, partitions) in self.get_topic_partitions(consumer)? {
+            for partition in partitions {
+                // resume from the message next to the last sinked one
+                let offset = topic_offsets
+                    .get(&topic)
+                    .and_then(|i| i.get(&partition))
+                    .map(|i| i + 1)
+                    .unwrap_or(self.offset);
+                log_info!(
+                    "assign topic: {}, partition: {}, offset: {}",
+                    topic,
+                    partition,
+                    offset
+                );
+                if offset >= 0 {
+                    tpl.add_partition_offset(&topic, partition, Offset::Offset(offset))?;
+                } else {
+                    tpl.add_partition(&topic, partition);
+                }
+            }
+        }
+
+        if tpl.count() == 0 {
+            bail! {Error::ExtractorError(format!(
+                "no kafka partitions found for topic: {}",
+                self.topic
+            ))}
+        }
+        consumer.assign(&tpl)?;
+        Ok(())
+    }
+
+    fn get_topic_partitions(
+        &self,
+        consumer: &StreamConsumer,
+    ) -> anyhow::Result<Vec<(String, Vec<i32>)>> {
+        let topics = self.get_topics();
+        let is_regex = topics.len() == 1 && topics[0].starts_with('^');
+        let regex = if is_regex {
+            Some(Regex::new(&topics[0])?)
+        } else {
+            None
+        };
+
+        // fetch metadata of all topics if there are multiple topics or a regex
+        let fetch_topic = if topics.len() == 1 && !is_regex {
+            Some(topics[0].as_str())
         } else {
-            tpl.add_partition(&self.topic, self.partition);
+            None
+        };
+        let metadata =
+            consumer.fetch_metadata(fetch_topic, Duration::from_secs(METADATA_TIMEOUT_SECS))?;
+
+        let mut topic_partitions = Vec::new();
+        for topic_meta in metadata.topics() {
+            let name = topic_meta.name();
+            let matched = match &regex {
+                Some(regex) => regex.is_match(name),
+                None => topics.iter().any(|i| i == name),
+            };
+            if !matched {
+                continue;
+            }
+
+            let partitions = if self.partition >= 0 {
+                vec![self.partition]
+            } else {
+                topic_meta.partitions().iter().map(|i| i.id()).collect()
+            };
+            topic_partitions.push((name.to_string(), partitions));
+        }
+        Ok(topic_partitions)
+    }
+
+    fn get_topics(&self) -> Vec<String> {
+        self.topic
+            .split(',')
+            .map(|i| i.trim().to_string())
+            .filter(|i| !i.is_empty())
+            .collect()
+    }
+
+    fn start_ack(&self, consumer: Arc<StreamConsumer>) {
+        if self.ack_interval_secs == 0 {
+            return;
+        }
+
+        let (syncer, shut_down, ack_interval_secs) = (
+            self.syncer.clone(),
+            self.base_extractor.shut_down.clone(),
+            self.ack_interval_secs,
+        );
+        tokio::spawn(async move {
+            let mut acked_position = Position::None;
+            while !shut_down.load(Ordering::Acquire) {
+                TimeUtil::sleep_millis(1000 * ack_interval_secs).await;
+                let position = syncer.lock().unwrap().committed_position.clone();
+                if position == acked_position {
+                    continue;
+                }
+                if let Err(err) = Self::ack(&consumer, &position) {
+                    log_error!("kafka ack failed, error: {:?}", err);
+                }
+                acked_position = position;
+            }
+        });
+    }
+
+    fn ack(consumer: &StreamConsumer, position: &Position) -> anyhow::Result<()> {
+        // commit the offsets of the next messages to be consumed
+        let mut tpl = TopicPartitionList::new();
+        if let Position::KafkaPartitions { topics } = position {
+            for (topic, partitions) in topics.iter() {
+                for (partition, offset) in partitions.iter() {
+                    tpl.add_partition_offset(topic, *partition, Offset::Offset(offset + 1))?;
+                }
+            }
+        }
+
+        if tpl.count() > 0 {
+            consumer.commit(&tpl, CommitMode::Async)?;
         }
-        consumer.assign(&tpl).unwrap();
-        consumer
+        Ok(())
     }
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, VecDeque},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc, Mutex,
@@ -7,10 +7,12 @@ use std::{
     time::Instant,
 };
 
+use anyhow::bail;
 use async_trait::async_trait;
 use chrono::Utc;
 use dt_common::meta::{
     col_value::ColValue,
+    dt_data::DtData,
     mongo::{mongo_cdc_source::MongoCdcSource, mongo_constant::MongoConstants},
     position::Position,
     row_data::RowData,
@@ -18,16 +20,20 @@ use dt_common::meta::{
     syncer::Syncer,
 };
 use dt_common::{
-    config::config_enums::DbType, log_error, log_info, rdb_filter::RdbFilter,
-    utils::time_util::TimeUtil,
+    config::config_enums::DbType, error::Error, log_error, log_info, log_warn,
+    rdb_filter::RdbFilter, utils::time_util::TimeUtil,
 };
 use mongodb::{
     bson::{doc, Bson, Document, Timestamp},
     change_stream::event::{OperationType, ResumeToken},
-    options::{ChangeStreamOptions, FullDocumentBeforeChangeType, FullDocumentType, UpdateOptions},
-    Client,
+    options::{
+        ChangeStreamOptions, ClientOptions, FullDocumentBeforeChangeType, FullDocumentType,
+        ServerAddress, UpdateOptions,
+    },
+    Client, Cursor,
 };
 use serde_json::json;
+use tokio::{sync::mpsc, task::JoinSet};
 
 use crate::{
     extractor::{base_extractor::BaseExtractor, resumer::cdc_resumer::CdcResumer},
@@ -35,6 +41,8 @@ use crate::{
 };
 
 const SYSTEM_DBS: [&str; 3] = ["admin", "config", "local"];
+// max entries buffered for each shard
+const SHARD_OPLOG_QUEUE_SIZE: usize = 1000;
 
 pub struct MongoCdcExtractor {
     pub base_extractor: BaseExtractor,
@@ -42,6 +50,9 @@ pub struct MongoCdcExtractor {
     pub resume_token: String,
     pub start_timestamp: u32,
     pub source: MongoCdcSource,
+    pub full_document: String,
+    pub full_document_before_change: String,
+    pub url: String,
     pub mongo_client: Client,
     pub app_name: String,
     pub heartbeat_interval_secs: u64,
@@ -64,6 +75,13 @@ impl Extractor for MongoCdcExtractor {
             log_info!("resume from: {}", self.resumer.current_position);
         };
 
+        // for sharded oplog, each shard resumes from its own ts in extract_sharded_oplog,
+        // operation_time is for shards not recorded in position
+        if let Position::MongoShardCdc { operation_time, .. } = &self.resumer.current_position {
+            self.start_timestamp = operation_time.to_owned();
+            log_info!("resume from: {}", self.resumer.current_position);
+        }
+
         log_info!(
             "MongoCdcExtractor starts, resume_token: {}, start_timestamp: {}, source: {:?} ",
             self.resume_token,
@@ -90,7 +108,148 @@ impl Extractor for MongoCdcExtractor {
 
 impl MongoCdcExtractor {
     async fn extract_oplog(&mut self) -> anyhow::Result<()> {
+        if Self::is_mongos(&self.mongo_client).await? {
+            return self.extract_sharded_oplog().await;
+        }
+
         let start_timestamp = self.parse_start_timestamp();
+        let mut cursor = Self::tail_oplog(&self.mongo_client, start_timestamp).await?;
+        let mut tx_position = None;
+        while cursor.advance().await.unwrap() {
+            let doc: Document = cursor.deserialize_current().unwrap();
+            // a large transaction is split into multiple applyOps entries,
+            // all entries except the last one are marked with partialTxn
+            let end_tx = !Self::is_partial_txn(&doc);
+            self.push_tx_rows(Self::parse_oplog(&doc), &mut tx_position, end_tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// For sharded clusters, tail oplogs of all shards and merge them by cluster time.
+    async fn extract_sharded_oplog(&mut self) -> anyhow::Result<()> {
+        // transactions are not merged across shards, so Begin / Commit can not be pushed
+        if self.base_extractor.data_marker.is_some() {
+            bail! {Error::ConfigError(
+                "data_marker is not supported for sharded oplog, use change_stream instead".into()
+            )}
+        }
+
+        let shard_hosts = Self::list_shards(&self.mongo_client).await?;
+        log_info!("tail oplogs of shards: {:?}", shard_hosts);
+
+        let shard_timestamps = if let Position::MongoShardCdc { shards, .. } =
+            &self.resumer.current_position
+        {
+            shards.clone()
+        } else {
+            BTreeMap::new()
+        };
+
+        let default_timestamp = self.parse_start_timestamp();
+        let mut shard_tasks = JoinSet::new();
+        let mut shard_names = Vec::new();
+        let mut receivers = Vec::new();
+        for (shard_name, shard_host) in shard_hosts {
+            let start_timestamp = match shard_timestamps.get(&shard_name) {
+                Some((time, increment)) => Timestamp {
+                    time: *time,
+                    increment: *increment,
+                },
+                None => default_timestamp,
+            };
+            let client = Self::create_shard_client(&self.url, &self.app_name, &shard_host).await?;
+            let cursor = Self::tail_oplog(&client, start_timestamp).await?;
+            // a shard task stops reading its oplog while its channel is full,
+            // e.g. the merge is waiting for entries of a slower shard
+            let (tx, rx) = mpsc::channel(SHARD_OPLOG_QUEUE_SIZE);
+            shard_tasks.spawn(Self::tail_shard_oplog(shard_name.clone(), cursor, tx));
+            shard_names.push(shard_name);
+            receivers.push(rx);
+        }
+
+        // the next entry of each shard, entries are only received from shards with empty queues
+        let mut queues: Vec<VecDeque<Document>> = vec![VecDeque::new(); shard_names.len()];
+        let mut shard_positions: BTreeMap<String, (u32, u32)> = shard_timestamps;
+        'merge: loop {
+            for (i, rx) in receivers.iter_mut().enumerate() {
+                if !queues[i].is_empty() {
+                    continue;
+                }
+                tokio::select! {
+                    received = rx.recv() => {
+                        let Some(doc) = received else {
+                            break 'merge;
+                        };
+                        queues[i].push_back(doc);
+                    }
+
+                    // a shard task ends only on errors or after the extractor is finished
+                    Some(joined) = shard_tasks.join_next() => joined??,
+                }
+            }
+
+            while let Some((i, doc)) = Self::pop_earliest_oplog(&mut queues) {
+                let (time, increment) = Self::get_ts(&doc);
+                shard_positions.insert(shard_names[i].clone(), (time, increment));
+                for (row_data, _) in Self::parse_oplog(&doc) {
+                    let position = Self::build_shard_position(&shard_positions, time);
+                    self.push_row_to_buf(row_data, position).await?;
+                }
+            }
+        }
+
+        while let Some(joined) = shard_tasks.join_next().await {
+            joined??;
+        }
+        Ok(())
+    }
+
+    async fn tail_shard_oplog(
+        shard_name: String,
+        mut cursor: Cursor<Document>,
+        tx: mpsc::Sender<Document>,
+    ) -> anyhow::Result<()> {
+        while cursor.advance().await? {
+            let doc: Document = cursor.deserialize_current()?;
+            if tx.send(doc).await.is_err() {
+                // the extractor is finished
+                return Ok(());
+            }
+        }
+        // the merge can not go on without entries of this shard
+        bail! {Error::ExtractorError(format!(
+            "oplog cursor of shard: {} is closed",
+            shard_name
+        ))}
+    }
+
+    /// An entry can be popped only if all shards have been read beyond its ts,
+    /// idle shards still write periodic noop entries, so the merge never stalls for long
+    fn pop_earliest_oplog(queues: &mut [VecDeque<Document>]) -> Option<(usize, Document)> {
+        if queues.iter().any(|queue| queue.is_empty()) {
+            return None;
+        }
+        let (i, _) = queues
+            .iter()
+            .enumerate()
+            .map(|(i, queue)| (i, Self::get_ts(queue.front().unwrap())))
+            .min_by_key(|(_, ts)| *ts)?;
+        queues[i].pop_front().map(|doc| (i, doc))
+    }
+
+    fn build_shard_position(shard_positions: &BTreeMap<String, (u32, u32)>, time: u32) -> Position {
+        Position::MongoShardCdc {
+            shards: shard_positions.clone(),
+            operation_time: time,
+            timestamp: Position::format_timestamp_millis(time as i64 * 1000),
+        }
+    }
+
+    async fn tail_oplog(
+        client: &Client,
+        start_timestamp: Timestamp,
+    ) -> anyhow::Result<Cursor<Document>> {
         let filter = doc! {
             "ts": { "$gte": start_timestamp }
         };
@@ -98,117 +257,192 @@ impl MongoCdcExtractor {
             .cursor_type(mongodb::options::CursorType::TailableAwait)
             .build();
 
-        let oplog = self
-            .mongo_client
-            .database("local")
-            .collection::<Document>("oplog.rs");
-        let mut cursor = oplog.find(filter, options).await.unwrap();
+        let oplog = client.database("local").collection::<Document>("oplog.rs");
+        Ok(oplog.find(filter, options).await?)
+    }
 
-        while cursor.advance().await.unwrap() {
-            let doc: Document = cursor.deserialize_current().unwrap();
-            // https://github.com/mongodb/mongo/blob/master/src/mongo/db/repl/oplog.cpp
-            // op:
-            //     "i" insert
-            //     "u" update
-            //     "d" delete
-            //     "c" db cmd
-            //     "n" no op
-            //     "xi" insert global index key
-            //     "xd" delete global index key
-
-            let op = Self::get_op(&doc);
-            let mut row_type = RowType::Insert;
-            let mut before = HashMap::new();
-            let mut after = HashMap::new();
-            let o = doc.get("o");
-            let o2 = doc.get("o2");
-            let ts = doc.get("ts");
-            let ns = doc.get("ns");
+    fn parse_oplog(doc: &Document) -> Vec<(RowData, Position)> {
+        // https://github.com/mongodb/mongo/blob/master/src/mongo/db/repl/oplog.cpp
+        // op:
+        //     "i" insert
+        //     "u" update
+        //     "d" delete
+        //     "c" db cmd
+        //     "n" no op
+        //     "xi" insert global index key
+        //     "xd" delete global index key
+
+        // entries written by chunk migrations between shards, not user changes
+        if Self::is_from_migrate(doc) {
+            return vec![];
+        }
 
-            match op.as_str() {
-                "i" => {
-                    after.insert(
-                        MongoConstants::DOC.to_string(),
-                        ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
-                    );
-                }
-                "u" => {
-                    row_type = RowType::Update;
-                    // for update op log, doc.o contains only diff instead of full doc
-                    let after_doc = o.unwrap().as_document().unwrap();
-                    // refer: https://www.mongodb.com/community/forums/t/oplog-update-entry-without-set-and-unset/171771
-                    // https://www.mongodb.com/docs/manual/reference/operator/update/#update-operators-1
-                    // in MongoDB 4.4 and earlier, after_doc contains $set with all new document fields,
-                    // after that, after_doc contains diff with only changed fields.
-                    let diff_doc = if let Some(doc) = after_doc.get("diff") {
-                        let doc = doc.as_document().unwrap();
-                        if let Some(i_doc) = doc.get("i") {
-                            doc! {MongoConstants::SET: i_doc.as_document().unwrap()}
-                        } else if let Some(u_doc) = doc.get("u") {
-                            doc! {MongoConstants::SET: u_doc.as_document().unwrap()}
-                        } else if let Some(d_doc) = doc.get("d") {
-                            doc! {MongoConstants::UNSET: d_doc.as_document().unwrap()}
-                        } else {
-                            doc! {}
-                        }
-                    } else if let Some(set_doc) = after_doc.get(MongoConstants::SET) {
-                        doc! {MongoConstants::SET: set_doc.as_document().unwrap()}
-                    } else if let Some(unset_doc) = after_doc.get(MongoConstants::UNSET) {
-                        doc! {MongoConstants::UNSET: unset_doc.as_document().unwrap()}
+        let op = Self::get_op(doc);
+        let mut row_type = RowType::Insert;
+        let mut before = HashMap::new();
+        let mut after = HashMap::new();
+        let o = doc.get("o");
+        let o2 = doc.get("o2");
+        let ts = doc.get("ts");
+        let ns = doc.get("ns");
+
+        match op.as_str() {
+            "i" => {
+                after.insert(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
+                );
+            }
+            "u" => {
+                row_type = RowType::Update;
+                // for update op log, doc.o contains only diff instead of full doc
+                let after_doc = o.unwrap().as_document().unwrap();
+                // refer: https://www.mongodb.com/community/forums/t/oplog-update-entry-without-set-and-unset/171771
+                // https://www.mongodb.com/docs/manual/reference/operator/update/#update-operators-1
+                // in MongoDB 4.4 and earlier, after_doc contains $set with all new document fields,
+                // after that, after_doc contains diff with only changed fields.
+                let diff_doc = if let Some(doc) = after_doc.get("diff") {
+                    let doc = doc.as_document().unwrap();
+                    if let Some(i_doc) = doc.get("i") {
+                        doc! {MongoConstants::SET: i_doc.as_document().unwrap()}
+                    } else if let Some(u_doc) = doc.get("u") {
+                        doc! {MongoConstants::SET: u_doc.as_document().unwrap()}
+                    } else if let Some(d_doc) = doc.get("d") {
+                        doc! {MongoConstants::UNSET: d_doc.as_document().unwrap()}
                     } else {
                         doc! {}
-                    };
-
-                    if diff_doc.is_empty() {
-                        log_error!(
-                            "update op_log is neither $set nor $unset, ignore, o2: {:?}, o: {:?}",
-                            o2,
-                            o
-                        );
-                        continue;
                     }
+                } else if let Some(set_doc) = after_doc.get(MongoConstants::SET) {
+                    doc! {MongoConstants::SET: set_doc.as_document().unwrap()}
+                } else if let Some(unset_doc) = after_doc.get(MongoConstants::UNSET) {
+                    doc! {MongoConstants::UNSET: unset_doc.as_document().unwrap()}
+                } else {
+                    doc! {}
+                };
 
-                    after.insert(
-                        MongoConstants::DIFF_DOC.to_string(),
-                        ColValue::MongoDoc(diff_doc.clone()),
+                if diff_doc.is_empty() {
+                    log_error!(
+                        "update op_log is neither $set nor $unset, ignore, o2: {:?}, o: {:?}",
+                        o2,
+                        o
                     );
-                    before.insert(
-                        MongoConstants::DOC.to_string(),
-                        ColValue::MongoDoc(o2.unwrap().as_document().unwrap().clone()),
-                    );
-                }
-                "d" => {
-                    row_type = RowType::Delete;
-                    before.insert(
-                        MongoConstants::DOC.to_string(),
-                        ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
-                    );
-                }
-                // TODO, DDL
-                "c" | "xi" | "xd" => {
-                    // after version 7.0, the oplog generated by deleteMany is "c" instead of "d"
-                    let data = Self::extract_oplog_delete_many(&doc);
-                    for (row_data, position) in data {
-                        self.push_row_to_buf(row_data, position).await.unwrap();
-                    }
-                    continue;
-                }
-                "n" => {
-                    // TODO, heartbeat
-                    // Document({"op": String("n"), "ns": String(""), "o": Document({"msg": String("periodic noop")}), "ts": Timestamp { time: 1693470874, increment: 1 }, "t": Int64(67), "v": Int64(2), "wall": DateTime(2023-08-31 8:34:34.19 +00:00:00)})
-                    continue;
-                }
-                _ => {
-                    continue;
+                    return vec![];
                 }
+
+                after.insert(
+                    MongoConstants::DIFF_DOC.to_string(),
+                    ColValue::MongoDoc(diff_doc.clone()),
+                );
+                before.insert(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(o2.unwrap().as_document().unwrap().clone()),
+                );
+            }
+            "d" => {
+                row_type = RowType::Delete;
+                before.insert(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
+                );
+            }
+            // TODO, DDL
+            "c" | "xi" | "xd" => {
+                // after version 7.0, the oplog generated by deleteMany is "c" instead of "d",
+                // and changes in a multi-document transaction are also in applyOps
+                return Self::extract_oplog_apply_ops(doc);
             }
+            "n" => {
+                // TODO, heartbeat
+                // Document({"op": String("n"), "ns": String(""), "o": Document({"msg": String("periodic noop")}), "ts": Timestamp { time: 1693470874, increment: 1 }, "t": Int64(67), "v": Int64(2), "wall": DateTime(2023-08-31 8:34:34.19 +00:00:00)})
+                return vec![];
+            }
+            _ => {
+                return vec![];
+            }
+        }
 
-            // get db & tb
-            let (row_data, position) =
-                Self::build_oplog_row_data(&ns, &ts, row_type, before, after);
-            self.push_row_to_buf(row_data, position).await.unwrap();
+        // get db & tb
+        vec![Self::build_oplog_row_data(&ns, &ts, row_type, before, after)]
+    }
+
+    fn is_from_migrate(doc: &Document) -> bool {
+        doc.get_bool("fromMigrate").unwrap_or(false)
+    }
+
+    fn get_ts(doc: &Document) -> (u32, u32) {
+        match doc.get_timestamp("ts") {
+            Ok(ts) => (ts.time, ts.increment),
+            Err(_) => (0, 0),
         }
-        Ok(())
+    }
+
+    async fn is_mongos(client: &Client) -> anyhow::Result<bool> {
+        let hello = client
+            .database("admin")
+            .run_command(doc! {"isMaster": 1}, None)
+            .await?;
+        Ok(hello.get_str("msg").unwrap_or_default() == "isdbgrid")
+    }
+
+    async fn is_shard_member(client: &Client) -> bool {
+        // requires the clusterMonitor role, treat as unknown if not permitted
+        match client
+            .database("admin")
+            .run_command(doc! {"getCmdLineOpts": 1}, None)
+            .await
+        {
+            Ok(opts) => {
+                let cluster_role = opts
+                    .get_document("parsed")
+                    .and_then(|parsed| parsed.get_document("sharding"))
+                    .and_then(|sharding| sharding.get_str("clusterRole"));
+                matches!(cluster_role, Ok("shardsvr"))
+            }
+            Err(err) => {
+                log_warn!("failed to get cluster role, error: {}", err);
+                false
+            }
+        }
+    }
+
+    async fn list_shards(client: &Client) -> anyhow::Result<Vec<(String, String)>> {
+        let result = client
+            .database("admin")
+            .run_command(doc! {"listShards": 1}, None)
+            .await?;
+        let mut shards = Vec::new();
+        for shard in result.get_array("shards")? {
+            if let Some(shard) = shard.as_document() {
+                shards.push((
+                    shard.get_str(MongoConstants::ID)?.to_string(),
+                    shard.get_str("host")?.to_string(),
+                ));
+            }
+        }
+        Ok(shards)
+    }
+
+    async fn create_shard_client(
+        url: &str,
+        app_name: &str,
+        shard_host: &str,
+    ) -> anyhow::Result<Client> {
+        // shard_host: shard01/host1:27018,host2:27018
+        let (repl_set_name, hosts) = match shard_host.split_once('/') {
+            Some((repl_set_name, hosts)) => (Some(repl_set_name.to_string()), hosts),
+            None => (None, shard_host),
+        };
+
+        // reuse credentials and other options of the mongos url
+        let mut client_options = ClientOptions::parse_async(url).await?;
+        client_options.app_name = Some(app_name.to_string());
+        client_options.hosts = hosts
+            .split(',')
+            .map(ServerAddress::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        client_options.direct_connection = Some(repl_set_name.is_none());
+        client_options.repl_set_name = repl_set_name;
+        Ok(Client::with_options(client_options)?)
     }
 
     fn get_op(doc: &Document) -> String {
@@ -219,7 +453,7 @@ impl MongoCdcExtractor {
         op.into()
     }
 
-    fn extract_oplog_delete_many(doc: &Document) -> Vec<(RowData, Position)> {
+    fn extract_oplog_apply_ops(doc: &Document) -> Vec<(RowData, Position)> {
         // Some(Document({
         //     "applyOps": Array([Document({
         //         "op": String("d"),
@@ -269,26 +503,16 @@ impl MongoCdcExtractor {
 
             let item = ops.as_document().unwrap();
             let op = Self::get_op(item);
-            let ns = item.get("ns");
-
-            if op.as_str() != "d" {
+            if !["i", "u", "d"].contains(&op.as_str()) {
                 continue;
             }
 
-            let o = item.get("o");
-            let mut before = HashMap::new();
-            before.insert(
-                MongoConstants::DOC.to_string(),
-                ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
-            );
-
-            data.push(Self::build_oplog_row_data(
-                &ns,
-                &ts,
-                RowType::Delete,
-                before,
-                HashMap::new(),
-            ));
+            // ops in applyOps share the ts of the outer entry
+            let mut item = item.clone();
+            if let Some(ts) = ts {
+                item.insert("ts", ts.clone());
+            }
+            data.extend(Self::parse_oplog(&item));
         }
         data
     }
@@ -332,89 +556,227 @@ impl MongoCdcExtractor {
             (Some(token), None)
         };
 
+        // change stream opened on a shard member only contains changes of that shard
+        if !Self::is_mongos(&self.mongo_client).await?
+            && Self::is_shard_member(&self.mongo_client).await
+        {
+            bail! {Error::ConfigError(
+                "change stream of sharded cluster should be opened on mongos".into()
+            )}
+        }
+
         // refer: https://www.mongodb.com/docs/manual/changeStreams/
         // Starting in MongoDB 6.0, you can use change stream events to output the version of
         // a document before and after changes (the document pre- and post-images)
         let stream_options = ChangeStreamOptions::builder()
             .start_at_operation_time(start_timestamp)
             .start_after(resume_token)
-            .full_document(Some(FullDocumentType::UpdateLookup))
-            .full_document_before_change(Some(FullDocumentBeforeChangeType::WhenAvailable))
+            .full_document(Self::parse_full_document(&self.full_document)?)
+            .full_document_before_change(Self::parse_full_document_before_change(
+                &self.full_document_before_change,
+            )?)
             .build();
 
         let mut change_stream = self.mongo_client.watch(None, stream_options).await.unwrap();
+        let mut tx_position = None;
+        let mut current_txn = None;
         loop {
-            let result = change_stream.next_if_any().await.unwrap();
-            if let Some(doc) = result {
-                let resume_token = doc.id;
-                let position = if let Some(operation_time) = doc.cluster_time {
-                    Position::MongoCdc {
-                        resume_token: json!(resume_token).to_string(),
-                        operation_time: operation_time.time,
-                        timestamp: Position::format_timestamp_millis(
-                            operation_time.time as i64 * 1000,
-                        ),
-                    }
-                } else {
-                    Position::MongoCdc {
-                        resume_token: json!(resume_token).to_string(),
-                        operation_time: 0,
-                        timestamp: String::new(),
-                    }
-                };
+            // next_if_any returns None once the server has no more changes for now (the getMore
+            // awaitData timeout), change streams don't mark the last event of a transaction,
+            // so the current transaction is considered complete when the stream goes idle
+            let result = change_stream.next_if_any().await?;
+            let doc = if let Some(doc) = result {
+                doc
+            } else {
+                self.push_tx_rows(vec![], &mut tx_position, true).await?;
+                current_txn = None;
+                continue;
+            };
+            let resume_token = doc.id;
+            let position = if let Some(operation_time) = doc.cluster_time {
+                Position::MongoCdc {
+                    resume_token: json!(resume_token).to_string(),
+                    operation_time: operation_time.time,
+                    timestamp: Position::format_timestamp_millis(operation_time.time as i64 * 1000),
+                }
+            } else {
+                Position::MongoCdc {
+                    resume_token: json!(resume_token).to_string(),
+                    operation_time: 0,
+                    timestamp: String::new(),
+                }
+            };
 
-                let (mut db, mut tb) = (String::new(), String::new());
-                if let Some(ns) = doc.ns {
-                    db = ns.db.clone();
-                    if let Some(coll) = ns.coll {
-                        tb = coll.clone();
-                    }
+            let (mut db, mut tb) = (String::new(), String::new());
+            if let Some(ns) = doc.ns {
+                db = ns.db.clone();
+                if let Some(coll) = ns.coll {
+                    tb = coll.clone();
+                }
+            }
+
+            let mut row_type = RowType::Insert;
+            let mut before = HashMap::new();
+            let mut after = HashMap::new();
+
+            // pre-image if available, otherwise only _id
+            let before_doc = doc.full_document_before_change.or(doc.document_key);
+
+            match doc.operation_type {
+                OperationType::Insert => {
+                    after.insert(
+                        MongoConstants::DOC.to_string(),
+                        ColValue::MongoDoc(doc.full_document.unwrap()),
+                    );
                 }
 
-                let mut row_type = RowType::Insert;
-                let mut before = HashMap::new();
-                let mut after = HashMap::new();
+                OperationType::Delete => {
+                    row_type = RowType::Delete;
+                    before.insert(
+                        MongoConstants::DOC.to_string(),
+                        ColValue::MongoDoc(before_doc.unwrap()),
+                    );
+                }
 
-                match doc.operation_type {
-                    OperationType::Insert => {
+                OperationType::Update | OperationType::Replace => {
+                    row_type = RowType::Update;
+                    before.insert(
+                        MongoConstants::DOC.to_string(),
+                        ColValue::MongoDoc(before_doc.unwrap()),
+                    );
+                    if let Some(document) = doc.full_document {
                         after.insert(
                             MongoConstants::DOC.to_string(),
-                            ColValue::MongoDoc(doc.full_document.unwrap()),
+                            ColValue::MongoDoc(document),
                         );
                     }
-
-                    OperationType::Delete => {
-                        row_type = RowType::Delete;
-                        before.insert(
-                            MongoConstants::DOC.to_string(),
-                            ColValue::MongoDoc(doc.document_key.unwrap()),
+                    // post-image is missing if full_document = off, or the document has been
+                    // deleted before lookup, the sinker can still apply the partial update
+                    if let Some(update_description) = doc.update_description {
+                        let diff_doc = Self::build_diff_doc(
+                            update_description.updated_fields,
+                            update_description.removed_fields,
+                        );
+                        after.insert(
+                            MongoConstants::DIFF_DOC.to_string(),
+                            ColValue::MongoDoc(diff_doc),
                         );
                     }
 
-                    OperationType::Update | OperationType::Replace => {
-                        row_type = RowType::Update;
-                        if let Some(document) = doc.full_document {
-                            before.insert(
-                                MongoConstants::DOC.to_string(),
-                                ColValue::MongoDoc(doc.document_key.unwrap()),
-                            );
-                            after.insert(
-                                MongoConstants::DOC.to_string(),
-                                ColValue::MongoDoc(document),
-                            );
-                        }
-                    }
-
-                    // TODO, heartbeat and DDL
-                    _ => {
+                    if after.is_empty() {
+                        log_error!(
+                            "update event without post-image or diff, ignore, db: {}, tb: {}",
+                            db,
+                            tb
+                        );
                         continue;
                     }
                 }
 
-                let row_data = RowData::new(db, tb, row_type, Some(before), Some(after));
-                self.push_row_to_buf(row_data, position).await.unwrap();
+                // TODO, heartbeat and DDL
+                _ => {
+                    continue;
+                }
+            }
+
+            let row_data = RowData::new(db, tb, row_type, Some(before), Some(after));
+            // events of the same transaction share lsid and txnNumber
+            let txn = match (doc.lsid, doc.txn_number) {
+                (Some(lsid), Some(txn_number)) => Some((lsid, txn_number)),
+                _ => None,
+            };
+            if txn.is_none() || txn != current_txn {
+                // the last transaction ends when a new one comes
+                self.push_tx_rows(vec![], &mut tx_position, true).await?;
+            }
+            let end_tx = txn.is_none();
+            current_txn = txn;
+            self.push_tx_rows(vec![(row_data, position)], &mut tx_position, end_tx)
+                .await?;
+        }
+    }
+
+    /// With data_marker, changes of a transaction are pushed between Begin / Commit,
+    /// so the marker written at the head of the transaction only affects changes of it.
+    /// tx_position: position of the last change in the current transaction, None if not in transaction.
+    async fn push_tx_rows(
+        &mut self,
+        rows: Vec<(RowData, Position)>,
+        tx_position: &mut Option<Position>,
+        end_tx: bool,
+    ) -> anyhow::Result<()> {
+        if self.base_extractor.data_marker.is_none() {
+            for (row_data, position) in rows {
+                self.push_row_to_buf(row_data, position).await?;
+            }
+            return Ok(());
+        }
+
+        for (row_data, position) in rows {
+            if tx_position.is_none() {
+                self.base_extractor
+                    .push_dt_data(DtData::Begin {}, position.clone())
+                    .await?;
             }
+            *tx_position = Some(position.clone());
+            self.push_row_to_buf(row_data, position).await?;
         }
+
+        if end_tx {
+            if let Some(position) = tx_position.take() {
+                self.base_extractor
+                    .push_dt_data(DtData::Commit { xid: String::new() }, position)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_partial_txn(doc: &Document) -> bool {
+        doc.get_document("o")
+            .and_then(|o| o.get_bool("partialTxn"))
+            .unwrap_or(false)
+    }
+
+    fn build_diff_doc(updated_fields: Document, removed_fields: Vec<String>) -> Document {
+        let mut diff_doc = Document::new();
+        if !updated_fields.is_empty() {
+            diff_doc.insert(MongoConstants::SET, updated_fields);
+        }
+        if !removed_fields.is_empty() {
+            let mut unset_doc = Document::new();
+            for field in removed_fields {
+                unset_doc.insert(field, "");
+            }
+            diff_doc.insert(MongoConstants::UNSET, unset_doc);
+        }
+        diff_doc
+    }
+
+    fn parse_full_document(str: &str) -> anyhow::Result<Option<FullDocumentType>> {
+        let full_document = match str {
+            "" | "off" => None,
+            "update_lookup" => Some(FullDocumentType::UpdateLookup),
+            "when_available" => Some(FullDocumentType::WhenAvailable),
+            "required" => Some(FullDocumentType::Required),
+            _ => bail! {Error::ConfigError(format!("unsupported full_document: {}", str))},
+        };
+        Ok(full_document)
+    }
+
+    fn parse_full_document_before_change(
+        str: &str,
+    ) -> anyhow::Result<Option<FullDocumentBeforeChangeType>> {
+        let full_document_before_change = match str {
+            "" | "off" => None,
+            "when_available" => Some(FullDocumentBeforeChangeType::WhenAvailable),
+            "required" => Some(FullDocumentBeforeChangeType::Required),
+            _ => bail! {Error::ConfigError(format!(
+                "unsupported full_document_before_change: {}",
+                str
+            ))},
+        };
+        Ok(full_document_before_change)
     }
 
     async fn push_row_to_buf(
@@ -422,10 +784,16 @@ impl MongoCdcExtractor {
         row_data: RowData,
         position: Position,
     ) -> anyhow::Result<()> {
-        if SYSTEM_DBS.contains(&row_data.schema.as_str())
-            || self
-                .filter
-                .filter_event(&row_data.schema, &row_data.tb, &row_data.row_type)
+        if SYSTEM_DBS.contains(&row_data.schema.as_str()) {
+            return Ok(());
+        }
+        // data marker should be pushed even if filtered, it will be discarded by base_extractor
+        if self
+            .filter
+            .filter_event(&row_data.schema, &row_data.tb, &row_data.row_type)
+            && !self
+                .base_extractor
+                .is_data_marker_info(&row_data.schema, &row_data.tb)
         {
             return Ok(());
         }
@@ -484,35 +852,9 @@ impl MongoCdcExtractor {
         client: &Client,
     ) -> anyhow::Result<()> {
         let (received_resume_token, received_operation_time, received_timestamp) =
-            if let Position::MongoCdc {
-                resume_token,
-                operation_time,
-                timestamp,
-            } = &syncer.lock().unwrap().received_position
-            {
-                (
-                    resume_token.to_owned(),
-                    *operation_time,
-                    timestamp.to_owned(),
-                )
-            } else {
-                (String::new(), 0, String::new())
-            };
+            Self::get_position_info(&syncer.lock().unwrap().received_position);
         let (committed_resume_token, committed_operation_time, committed_timestamp) =
-            if let Position::MongoCdc {
-                resume_token,
-                operation_time,
-                timestamp,
-            } = &syncer.lock().unwrap().committed_position
-            {
-                (
-                    resume_token.to_owned(),
-                    *operation_time,
-                    timestamp.to_owned(),
-                )
-            } else {
-                (String::new(), 0, String::new())
-            };
+            Self::get_position_info(&syncer.lock().unwrap().committed_position);
 
         let query_doc = doc! {MongoConstants::ID: app_name };
         let update_doc = doc! {MongoConstants::SET: doc! {MongoConstants::ID: app_name,
@@ -535,4 +877,78 @@ impl MongoCdcExtractor {
         }
         Ok(())
     }
+
+    fn get_position_info(position: &Position) -> (String, u32, String) {
+        match position {
+            Position::MongoCdc {
+                resume_token,
+                operation_time,
+                timestamp,
+            } => (
+                resume_token.to_owned(),
+                *operation_time,
+                timestamp.to_owned(),
+            ),
+            Position::MongoShardCdc {
+                operation_time,
+                timestamp,
+                ..
+            } => (String::new(), *operation_time, timestamp.to_owned()),
+            _ => (String::new(), 0, String::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_oplog(time: u32, increment: u32) -> Document {
+        let ts = Timestamp { time, increment };
+        let id = time as i64;
+        doc! {
+            "op": "i",
+            "ns": "test_db.tb_1",
+            "ts": ts,
+            "o": { "_id": id },
+        }
+    }
+
+    #[test]
+    fn test_pop_earliest_oplog() {
+        let mut queues = vec![VecDeque::new(), VecDeque::new()];
+        queues[0].push_back(mock_oplog(100, 1));
+        queues[0].push_back(mock_oplog(102, 1));
+
+        // shard 1 has not been read, entries of shard 0 may be later than its next entry
+        assert!(MongoCdcExtractor::pop_earliest_oplog(&mut queues).is_none());
+
+        queues[1].push_back(mock_oplog(101, 1));
+        let mut merged = Vec::new();
+        while let Some((i, doc)) = MongoCdcExtractor::pop_earliest_oplog(&mut queues) {
+            merged.push((i, MongoCdcExtractor::get_ts(&doc)));
+        }
+        assert_eq!(merged, vec![(0, (100, 1)), (1, (101, 1))]);
+        assert_eq!(queues[0].len(), 1);
+        assert!(queues[1].is_empty());
+    }
+
+    #[test]
+    fn test_build_shard_position() {
+        let mut shard_positions = BTreeMap::new();
+        shard_positions.insert("shard_1".to_string(), (100, 1));
+        shard_positions.insert("shard_2".to_string(), (101, 2));
+
+        let position = MongoCdcExtractor::build_shard_position(&shard_positions, 101);
+        let Position::MongoShardCdc {
+            shards,
+            operation_time,
+            ..
+        } = &position
+        else {
+            panic!("unexpected position: {}", position);
+        };
+        assert_eq!(shards, &shard_positions);
+        assert_eq!(*operation_time, 101);
+    }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
 
@@ -59,11 +59,13 @@ impl BatchCheckExtractor for MongoCheckExtractor {
             .collection::<Document>(tb);
 
         let mut ids = Vec::new();
+        let mut keys = HashSet::new();
         for check_log in check_logs.iter() {
             // check log has only one col: _id
             if let Some(Some(col_value)) = check_log.id_col_values.get(MongoConstants::ID) {
                 let key: MongoKey = serde_json::from_str(col_value).unwrap();
                 ids.push(key.to_mongo_id());
+                keys.insert(key);
             }
         }
 
@@ -77,7 +79,9 @@ impl BatchCheckExtractor for MongoCheckExtractor {
         while cursor.advance().await.unwrap() {
             let doc = cursor.deserialize_current().unwrap();
             let mut after = HashMap::new();
-            let id: String = MongoKey::from_doc(&doc).unwrap().to_string();
+            let key = MongoKey::from_doc(&doc).unwrap();
+            keys.remove(&key);
+            let id: String = key.to_string();
             after.insert(MongoConstants::ID.to_string(), ColValue::String(id));
             after.insert(MongoConstants::DOC.to_string(), ColValue::MongoDoc(doc));
             let mut row_data = RowData::new(
@@ -98,6 +102,30 @@ impl BatchCheckExtractor for MongoCheckExtractor {
                 .await
                 .unwrap();
         }
+
+        // docs in extra logs which still don't exist in src should be deleted from dst
+        if log_type == &LogType::Extra {
+            for key in keys {
+                let doc = doc! {MongoConstants::ID: key.to_mongo_id()};
+                let mut before = HashMap::new();
+                before.insert(
+                    MongoConstants::ID.to_string(),
+                    ColValue::String(key.to_string()),
+                );
+                before.insert(MongoConstants::DOC.to_string(), ColValue::MongoDoc(doc));
+                let row_data = RowData::new(
+                    schema.clone(),
+                    tb.clone(),
+                    RowType::Delete,
+                    Some(before),
+                    None,
+                );
+                self.base_extractor
+                    .push_row(row_data, Position::None)
+                    .await
+                    .unwrap();
+            }
+        }
         Ok(())
     }
 }
@@ -1,10 +1,13 @@
 use std::io::{Cursor, Read};
 
 pub mod rdb;
+pub mod redis_aof_file_extractor;
 pub mod redis_cdc_extractor;
+pub mod redis_check_extractor;
 pub mod redis_client;
 pub mod redis_psync_extractor;
 pub mod redis_reshard_extractor;
+pub mod redis_reshard_planner;
 pub mod redis_resp_reader;
 pub mod redis_resp_types;
 pub mod redis_scan_extractor;
@@ -1,6 +1,6 @@
 use anyhow::bail;
 use dt_common::error::Error;
-use dt_common::meta::redis::redis_object::{ModuleObject, RedisString};
+use dt_common::meta::redis::redis_object::{ModuleObject, ModuleValue, RedisString};
 
 use crate::extractor::redis::rdb::reader::rdb_reader::RdbReader;
 
@@ -9,6 +9,16 @@ pub struct ModuleParser {}
 const MODULE_TYPE_NAME_CHAR_SET: &str =
     "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
 
+const RDB_MODULE_OPCODE_EOF: u64 = 0; // End of module value.
+const RDB_MODULE_OPCODE_SINT: u64 = 1; // Signed integer.
+const RDB_MODULE_OPCODE_UINT: u64 = 2; // Unsigned integer.
+const RDB_MODULE_OPCODE_FLOAT: u64 = 3; // Float.
+const RDB_MODULE_OPCODE_DOUBLE: u64 = 4; // Double.
+const RDB_MODULE_OPCODE_STRING: u64 = 5; // String.
+
+// the lower 10 bits of module id are the encoding version
+const MODULE_ENCVER_BITS: u64 = 10;
+
 impl ModuleParser {
     pub fn load_from_buffer(
         reader: &mut RdbReader,
@@ -23,17 +33,45 @@ impl ModuleParser {
         }
 
         let module_id = reader.read_length()?;
-        let module_name = Self::module_type_name_by_id(module_id);
-        // Not supported
-        bail! {Error::RedisRdbError(format!(
-            "unsupported module type: [{}]",
-            module_name
-        ))}
+        let mut obj = ModuleObject::new();
+        obj.module_name = Self::module_type_name_by_id(module_id);
+        obj.encver = module_id & ((1 << MODULE_ENCVER_BITS) - 1);
+        obj.values = Self::read_module_values(reader, &obj.module_name)?;
+        obj.key = key;
+        Ok(obj)
+    }
+
+    /// Values of RDB_TYPE_MODULE_2 are self-described by opcodes and end with RDB_MODULE_OPCODE_EOF,
+    /// refer: https://github.com/redis/redis/blob/unstable/src/rdb.c, rdbLoadCheckModuleValue
+    pub fn read_module_values(
+        reader: &mut RdbReader,
+        module_name: &str,
+    ) -> anyhow::Result<Vec<ModuleValue>> {
+        let mut values = Vec::new();
+        let mut opcode = reader.read_length()?;
+        while opcode != RDB_MODULE_OPCODE_EOF {
+            let value = match opcode {
+                RDB_MODULE_OPCODE_SINT => ModuleValue::Int(reader.read_length()? as i64),
+                RDB_MODULE_OPCODE_UINT => ModuleValue::Uint(reader.read_length()?),
+                RDB_MODULE_OPCODE_FLOAT => ModuleValue::Float(reader.read_binary_float()?),
+                RDB_MODULE_OPCODE_DOUBLE => ModuleValue::Double(reader.read_double()?),
+                RDB_MODULE_OPCODE_STRING => ModuleValue::String(reader.read_string()?),
+                _ => {
+                    bail! {Error::RedisRdbError(format!(
+                        "module opcode not found. module_name=[{}], opcode=[{}]",
+                        module_name, opcode
+                    ))}
+                }
+            };
+            values.push(value);
+            opcode = reader.read_length()?;
+        }
+        Ok(values)
     }
 
     pub fn module_type_name_by_id(module_id: u64) -> String {
         let mut name_list: Vec<u8> = vec![0; 9];
-        let mut module_id = module_id >> 10;
+        let mut module_id = module_id >> MODULE_ENCVER_BITS;
         let name_char_set = MODULE_TYPE_NAME_CHAR_SET.chars().collect::<Vec<char>>();
 
         for i in (0..9).rev() {
@@ -1,5 +1,8 @@
 use anyhow::bail;
-use dt_common::meta::redis::{redis_entry::RedisEntry, redis_object::RedisCmd};
+use dt_common::meta::redis::{
+    redis_entry::RedisEntry,
+    redis_object::{RedisCmd, RedisString},
+};
 use dt_common::{error::Error, log_debug, log_info};
 use sqlx::types::chrono;
 
@@ -7,24 +10,17 @@ use crate::extractor::redis::{rdb::entry_parser::module2_parser::ModuleParser, S
 
 use super::{entry_parser::entry_parser::EntryParser, reader::rdb_reader::RdbReader};
 
-const _K_FLAG_FUNCTION2: u8 = 245; // function library data
-const _K_FLAG_FUNCTION: u8 = 246; // old function library data for 7.0 rc1 and rc2
+pub const K_FLAG_FUNCTION2: u8 = 245; // function library data
+const K_FLAG_FUNCTION: u8 = 246; // old function library data for 7.0 rc1 and rc2
 const K_FLAG_MODULE_AUX: u8 = 247; // Module auxiliary data.
 const K_FLAG_IDLE: u8 = 0xf8; // LRU idle time.
 const K_FLAG_FREQ: u8 = 0xf9; // LFU frequency.
-const K_FLAG_AUX: u8 = 0xfa; // RDB aux field.
+pub const K_FLAG_AUX: u8 = 0xfa; // RDB aux field.
 const K_FLAG_RESIZE_DB: u8 = 0xfb; // Hash table resize hint.
-const K_FLAG_EXPIRE_MS: u8 = 0xfc; // Expire time in milliseconds.
+pub const K_FLAG_EXPIRE_MS: u8 = 0xfc; // Expire time in milliseconds.
 const K_FLAG_EXPIRE: u8 = 0xfd; // Old expire time in seconds.
-const K_FLAG_SELECT: u8 = 0xfe; // DB number of the following keys.
-const K_EOF: u8 = 0xff; // End of the RDB file.
-
-const RDB_MODULE_OPCODE_EOF: u64 = 0; // End of module value.
-const RDB_MODULE_OPCODE_SINT: u64 = 1; // Signed integer.
-const RDB_MODULE_OPCODE_UINT: u64 = 2; // Unsigned integer.
-const RDB_MODULE_OPCODE_FLOAT: u64 = 3; // Float.
-const RDB_MODULE_OPCODE_DOUBLE: u64 = 4; // Double.
-const RDB_MODULE_OPCODE_STRING: u64 = 5; // String.
+pub const K_FLAG_SELECT: u8 = 0xfe; // DB number of the following keys.
+pub const K_EOF: u8 = 0xff; // End of the RDB file.
 
 pub struct RdbParser<'a> {
     pub reader: RdbReader<'a>,
@@ -57,6 +53,32 @@ impl RdbParser<'_> {
         log_debug!("rdb type_byte: {}", type_byte);
 
         match type_byte {
+            K_FLAG_FUNCTION2 => {
+                let code = self.reader.read_string()?;
+                let mut cmd = RedisCmd::new();
+                cmd.add_str_arg("function");
+                cmd.add_str_arg("load");
+                cmd.add_str_arg("replace");
+                cmd.add_redis_arg(&code);
+                log_info!(
+                    "RDB function library: {}",
+                    Self::get_function_library_name(&code)
+                );
+
+                // functions are not bound to any db
+                let mut entry = RedisEntry::new();
+                entry.is_base = true;
+                entry.db_id = self.now_db_id;
+                entry.cmd = cmd;
+                return Ok(Some(entry));
+            }
+
+            K_FLAG_FUNCTION => {
+                bail! {Error::RedisRdbError(
+                    "function library of pre-GA format (redis 7.0 rc1, rc2) is not supported".into()
+                )}
+            }
+
             K_FLAG_MODULE_AUX => {
                 let module_id = self.reader.read_length()?; // module id
                 let module_name = ModuleParser::module_type_name_by_id(module_id);
@@ -68,30 +90,7 @@ impl RdbParser<'_> {
                 // refer: https://github.com/redis/redis/blob/unstable/src/rdb.c#L3183
                 let _when_opcode = self.reader.read_length()?;
                 let _when = self.reader.read_length()?;
-                let mut opcode = self.reader.read_length()?;
-                while opcode != RDB_MODULE_OPCODE_EOF {
-                    match opcode {
-                        RDB_MODULE_OPCODE_SINT | RDB_MODULE_OPCODE_UINT => {
-                            self.reader.read_length()?;
-                        }
-                        RDB_MODULE_OPCODE_FLOAT => {
-                            self.reader.read_float()?;
-                        }
-                        RDB_MODULE_OPCODE_DOUBLE => {
-                            self.reader.read_double()?;
-                        }
-                        RDB_MODULE_OPCODE_STRING => {
-                            self.reader.read_string()?;
-                        }
-                        _ => {
-                            bail! {Error::RedisRdbError(format!(
-                                "module aux opcode not found. module_name=[{}], opcode=[{}]",
-                                module_name, opcode
-                            ))}
-                        }
-                    }
-                    opcode = self.reader.read_length()?;
-                }
+                ModuleParser::read_module_values(&mut self.reader, &module_name)?;
             }
 
             K_FLAG_IDLE => {
@@ -203,4 +202,15 @@ impl RdbParser<'_> {
 
         Ok(None)
     }
+
+    /// The first line of library code is like: #!lua name=mylib
+    fn get_function_library_name(code: &RedisString) -> String {
+        let code = code.to_string();
+        let first_line = code.lines().next().unwrap_or_default();
+        first_line
+            .split_whitespace()
+            .find_map(|i| i.strip_prefix("name="))
+            .unwrap_or_default()
+            .to_string()
+    }
 }
@@ -25,4 +25,9 @@ impl RdbReader<'_> {
         let buf = self.read_bytes(8)?;
         Ok(LittleEndian::read_f64(&buf))
     }
+
+    pub fn read_binary_float(&mut self) -> anyhow::Result<f32> {
+        let buf = self.read_bytes(4)?;
+        Ok(LittleEndian::read_f32(&buf))
+    }
 }
@@ -0,0 +1,251 @@
+use std::fs;
+use std::path::Path;
+
+use super::redis_cdc_extractor::RedisCdcExtractor;
+use super::redis_resp_reader::RedisRespReader;
+use super::redis_resp_types::Value;
+use super::redis_snapshot_file_extractor::RedisSnapshotFileExtractor;
+use crate::extractor::base_extractor::BaseExtractor;
+use crate::Extractor;
+use anyhow::{bail, Context};
+use async_std::io::BufReader;
+use async_trait::async_trait;
+use dt_common::error::Error;
+use dt_common::meta::position::Position;
+use dt_common::meta::redis::command::key_parser::KeyParser;
+use dt_common::rdb_filter::RdbFilter;
+use dt_common::{log_info, log_warn};
+
+const MANIFEST_SUFFIX: &str = ".manifest";
+const AOF_FILE_TYPE_BASE: &str = "b";
+const AOF_FILE_TYPE_INCR: &str = "i";
+
+/// Extracts data from a Redis 7 multi-part aof directory, which contains:
+/// - a manifest file, e.g. appendonly.aof.manifest
+/// - a base file in rdb or aof format, e.g. appendonly.aof.1.base.rdb
+/// - incremental aof files, e.g. appendonly.aof.1.incr.aof, appendonly.aof.2.incr.aof
+/// - history files (type h) which are ignored
+pub struct RedisAofFileExtractor {
+    pub aof_dir: String,
+    pub filter: RdbFilter,
+    pub base_extractor: BaseExtractor,
+    pub now_db_id: i64,
+}
+
+#[derive(Debug, PartialEq)]
+struct AofFileInfo {
+    file_name: String,
+    seq: u64,
+    file_type: String,
+}
+
+#[async_trait]
+impl Extractor for RedisAofFileExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!("RedisAofFileExtractor starts, aof_dir: {}", self.aof_dir);
+        let manifest = fs::read_to_string(self.find_manifest()?)?;
+        let aof_files = Self::parse_manifest(&manifest)?;
+
+        let mut base_files = Vec::new();
+        let mut incr_files = Vec::new();
+        for aof_file in aof_files {
+            match aof_file.file_type.as_str() {
+                AOF_FILE_TYPE_BASE => base_files.push(aof_file),
+                AOF_FILE_TYPE_INCR => incr_files.push(aof_file),
+                _ => {}
+            }
+        }
+        if base_files.len() > 1 {
+            bail! {Error::ExtractorError(format!(
+                "more than one base file found in aof manifest: {:?}",
+                base_files
+            ))}
+        }
+        incr_files.sort_by_key(|i| i.seq);
+
+        if let Some(base_file) = base_files.first() {
+            let file_path = self.get_file_path(&base_file.file_name);
+            log_info!("start loading aof base file: {}", file_path);
+            if base_file.file_name.ends_with(".rdb") {
+                RedisSnapshotFileExtractor::extract_rdb_file(
+                    &mut self.base_extractor,
+                    &mut self.filter,
+                    &file_path,
+                )
+                .await?;
+            } else {
+                self.replay_aof_file(&file_path, false).await?;
+            }
+        }
+
+        for (i, incr_file) in incr_files.iter().enumerate() {
+            let file_path = self.get_file_path(&incr_file.file_name);
+            log_info!("start replaying aof incr file: {}", file_path);
+            self.replay_aof_file(&file_path, i == incr_files.len() - 1)
+                .await?;
+        }
+
+        log_info!(
+            "end extracting data from aof dir, all count: {}",
+            self.base_extractor.monitor.counters.record_count
+        );
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+impl RedisAofFileExtractor {
+    async fn replay_aof_file(&mut self, file_path: &str, is_last: bool) -> anyhow::Result<()> {
+        let file_len = fs::metadata(file_path)
+            .with_context(|| format!("aof file not found: {}", file_path))?
+            .len() as usize;
+        let file = async_std::fs::File::open(file_path).await?;
+        let mut reader = BufReader::new(file);
+
+        let key_parser = KeyParser::new();
+        let mut offset = 0;
+        while offset < file_len {
+            let mut resp_reader = RedisRespReader { read_len: 0 };
+            let value = match resp_reader.decode(&mut reader).await {
+                Ok(value) => value,
+                // same as aof-load-truncated=yes of redis, the last incomplete command
+                // may be left if redis crashed while appending the last incr file
+                Err(err) if is_last => {
+                    log_warn!(
+                        "ignore the incomplete tail of aof file: {}, offset: {}, file size: {}, error: {}",
+                        file_path,
+                        offset,
+                        file_len,
+                        err
+                    );
+                    break;
+                }
+                Err(err) => {
+                    bail! {Error::ExtractorError(format!(
+                        "invalid aof file: {}, offset: {}, error: {}",
+                        file_path, offset, err
+                    ))}
+                }
+            };
+            offset += resp_reader.read_len;
+
+            if Value::Nil == value {
+                continue;
+            }
+
+            let cmd = RedisCdcExtractor::handle_redis_value(value)?;
+            if cmd.args.is_empty() {
+                continue;
+            }
+
+            // switch db
+            if cmd.get_name().eq_ignore_ascii_case("select") {
+                self.now_db_id = cmd.get_str_arg(1).parse::<i64>()?;
+                continue;
+            }
+
+            RedisCdcExtractor::push_cmd(
+                &mut self.base_extractor,
+                &mut self.filter,
+                &key_parser,
+                cmd,
+                self.now_db_id,
+                Position::None,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    fn find_manifest(&self) -> anyhow::Result<String> {
+        for dir_entry in fs::read_dir(&self.aof_dir)
+            .with_context(|| format!("aof dir not found: {}", self.aof_dir))?
+        {
+            let file_name = dir_entry?.file_name().to_string_lossy().to_string();
+            if file_name.ends_with(MANIFEST_SUFFIX) {
+                return Ok(self.get_file_path(&file_name));
+            }
+        }
+        bail! {Error::ExtractorError(format!(
+            "no aof manifest file found in: {}",
+            self.aof_dir
+        ))}
+    }
+
+    fn get_file_path(&self, file_name: &str) -> String {
+        Path::new(&self.aof_dir)
+            .join(file_name)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Each line of manifest is like: file appendonly.aof.1.base.rdb seq 1 type b
+    fn parse_manifest(manifest: &str) -> anyhow::Result<Vec<AofFileInfo>> {
+        let mut aof_files = Vec::new();
+        for line in manifest.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.len() % 2 != 0 {
+                bail! {Error::ExtractorError(format!(
+                    "invalid aof manifest line: {}",
+                    line
+                ))}
+            }
+
+            let mut aof_file = AofFileInfo {
+                file_name: String::new(),
+                seq: 0,
+                file_type: String::new(),
+            };
+            for kv in tokens.chunks(2) {
+                match kv[0] {
+                    "file" => aof_file.file_name = kv[1].trim_matches('"').to_string(),
+                    "seq" => aof_file.seq = kv[1].parse()?,
+                    "type" => aof_file.file_type = kv[1].to_string(),
+                    // unknown fields may be added by future versions
+                    _ => {}
+                }
+            }
+
+            if aof_file.file_name.is_empty() || aof_file.file_type.is_empty() {
+                bail! {Error::ExtractorError(format!(
+                    "invalid aof manifest line: {}",
+                    line
+                ))}
+            }
+            aof_files.push(aof_file);
+        }
+        Ok(aof_files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = "file appendonly.aof.2.base.rdb seq 2 type b\n\
+            file appendonly.aof.1.incr.aof seq 1 type h\n\
+            file appendonly.aof.4.incr.aof seq 4 type i\n\
+            file \"appendonly.aof.3.incr.aof\" seq 3 type i\n";
+        let aof_files = RedisAofFileExtractor::parse_manifest(manifest).unwrap();
+        assert_eq!(aof_files.len(), 4);
+        assert_eq!(
+            aof_files[0],
+            AofFileInfo {
+                file_name: "appendonly.aof.2.base.rdb".into(),
+                seq: 2,
+                file_type: "b".into(),
+            }
+        );
+        assert_eq!(aof_files[3].file_name, "appendonly.aof.3.incr.aof");
+        assert_eq!(aof_files[3].seq, 3);
+
+        assert!(RedisAofFileExtractor::parse_manifest("file a.aof seq").is_err());
+        assert!(RedisAofFileExtractor::parse_manifest("seq 1 type i").is_err());
+    }
+}
@@ -2,6 +2,7 @@ use super::redis_client::RedisClient;
 use super::redis_psync_extractor::RedisPsyncExtractor;
 use super::redis_resp_types::Value;
 use crate::extractor::base_extractor::BaseExtractor;
+use crate::extractor::extractor_monitor::ExtractorMonitor;
 use crate::extractor::resumer::cdc_resumer::CdcResumer;
 use crate::Extractor;
 use anyhow::bail;
@@ -13,13 +14,20 @@ use dt_common::log_error;
 use dt_common::log_info;
 use dt_common::log_warn;
 use dt_common::meta::dt_data::DtData;
+use dt_common::meta::dt_queue::DtQueue;
 use dt_common::meta::position::Position;
+use dt_common::meta::redis::cluster_node::ClusterNode;
+use dt_common::meta::redis::command::key_parser::KeyParser;
 use dt_common::meta::redis::redis_entry::RedisEntry;
 use dt_common::meta::redis::redis_object::RedisCmd;
 use dt_common::meta::syncer::Syncer;
+use dt_common::monitor::monitor::Monitor;
 use dt_common::rdb_filter::RdbFilter;
+use dt_common::time_filter::TimeFilter;
+use dt_common::utils::redis_util::RedisUtil;
 use dt_common::utils::sql_util::SqlUtil;
 use dt_common::utils::time_util::TimeUtil;
+use std::collections::BTreeMap;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -27,6 +35,12 @@ use std::sync::Mutex;
 use std::time::Instant;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
+use tokio::task::JoinHandle;
+
+const CLUSTER_NODE_BUFFER_SIZE: usize = 10000;
+const CLUSTER_TOPOLOGY_CHECK_INTERVAL_SECS: u64 = 10;
+const CLUSTER_RETRY_INTERVAL_SECS: u64 = 5;
+const CLUSTER_MAX_RETRY_TIMES: usize = 10;
 
 pub struct RedisCdcExtractor {
     pub base_extractor: BaseExtractor,
@@ -41,11 +55,17 @@ pub struct RedisCdcExtractor {
     pub syncer: Arc<Mutex<Syncer>>,
     pub filter: RdbFilter,
     pub resumer: CdcResumer,
+    pub is_cluster: bool,
 }
 
 #[async_trait]
 impl Extractor for RedisCdcExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
+        if self.is_cluster {
+            self.extract_cluster().await?;
+            return self.base_extractor.wait_task_finish().await;
+        }
+
         if let Position::Redis {
             repl_id,
             repl_port,
@@ -117,6 +137,7 @@ impl RedisCdcExtractor {
             log_warn!("heartbeat disabled, heartbeat_tb should be like db.key");
         }
 
+        let key_parser = KeyParser::new();
         let mut heartbeat_timestamp = String::new();
         let mut start_time = Instant::now();
         loop {
@@ -132,7 +153,7 @@ impl RedisCdcExtractor {
             }
 
             self.repl_offset += n as u64;
-            let cmd = self.handle_redis_value(value).await?;
+            let cmd = Self::handle_redis_value(value)?;
 
             if !cmd.args.is_empty() {
                 let cmd_name = cmd.get_name().to_ascii_lowercase();
@@ -161,45 +182,12 @@ impl RedisCdcExtractor {
                     timestamp: heartbeat_timestamp.clone(),
                 };
 
-                // transaction begin
-                // if there is only 1 command in a transaction, MULTI/EXEC won't be saved in aof.
-                // but in our two-way sync scenario, it is OK since we will always add an additional
-                // SET command as data marker following MULTI
-                if cmd_name == "multi" {
-                    // since not all commands are wrapped by MULTI and EXEC,
-                    // in two-way sync scenario, we must push both DtData::Begin and DtData::Commit
-                    // to buf to make sure:
-                    // 1, only the first command following MULTI be considered as data marker info.
-                    // 2, data_marker will be reset follwing EXEC.
-                    self.base_extractor
-                        .push_dt_data(DtData::Begin {}, position)
-                        .await?;
-                    // ignore MULTI & EXEC, otherwise we may get error: "MULTI calls can not be nested"
-                    continue;
-                }
-
-                // transaction end
-                if cmd_name == "exec" {
-                    self.base_extractor
-                        .push_dt_data(DtData::Commit { xid: String::new() }, position)
-                        .await?;
-                    continue;
-                }
-
-                // filter dangerous cmds, eg: flushdb, flushall
-                if self.filter.filter_cmd(&cmd_name) {
-                    continue;
-                }
-
-                // build entry and push it to buffer
-                let mut entry = RedisEntry::new();
-                entry.cmd = cmd;
-                entry.db_id = self.now_db_id;
-
-                RedisPsyncExtractor::push_to_buf(
+                Self::push_cmd(
                     &mut self.base_extractor,
                     &mut self.filter,
-                    entry,
+                    &key_parser,
+                    cmd,
+                    self.now_db_id,
                     position,
                 )
                 .await?;
@@ -207,7 +195,67 @@ impl RedisCdcExtractor {
         }
     }
 
-    async fn handle_redis_value(&mut self, value: Value) -> anyhow::Result<RedisCmd> {
+    /// Push a command from aof to buffer, shared by psync and aof files
+    pub async fn push_cmd(
+        base_extractor: &mut BaseExtractor,
+        filter: &mut RdbFilter,
+        key_parser: &KeyParser,
+        cmd: RedisCmd,
+        db_id: i64,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        let cmd_name = cmd.get_name().to_ascii_lowercase();
+
+        // transaction begin
+        // if there is only 1 command in a transaction, MULTI/EXEC won't be saved in aof.
+        // but in our two-way sync scenario, it is OK since we will always add an additional
+        // SET command as data marker following MULTI
+        if cmd_name == "multi" {
+            // since not all commands are wrapped by MULTI and EXEC,
+            // in two-way sync scenario, we must push both DtData::Begin and DtData::Commit
+            // to buf to make sure:
+            // 1, only the first command following MULTI be considered as data marker info.
+            // 2, data_marker will be reset follwing EXEC.
+            base_extractor
+                .push_dt_data(DtData::Begin {}, position)
+                .await?;
+            // ignore MULTI & EXEC, otherwise we may get error: "MULTI calls can not be nested"
+            return Ok(());
+        }
+
+        // transaction end
+        if cmd_name == "exec" {
+            base_extractor
+                .push_dt_data(DtData::Commit { xid: String::new() }, position)
+                .await?;
+            return Ok(());
+        }
+
+        // filter dangerous cmds, eg: flushdb, flushall
+        if filter.filter_cmd(&cmd_name) {
+            return Ok(());
+        }
+
+        // build entry and push it to buffer
+        let mut entry = RedisEntry::new();
+        entry.cmd = cmd;
+        entry.db_id = db_id;
+
+        // keys are needed by key filter and key map
+        if filter.has_key_filter() || base_extractor.router.has_key_map() {
+            if let Err(err) = entry.cmd.parse_keys(key_parser) {
+                log_warn!(
+                    "failed to parse keys, key filter and key map skipped, cmd: [{}], error: {}",
+                    entry.cmd,
+                    err
+                );
+            }
+        }
+
+        RedisPsyncExtractor::push_to_buf(base_extractor, filter, entry, position).await
+    }
+
+    pub fn handle_redis_value(value: Value) -> anyhow::Result<RedisCmd> {
         let mut cmd = RedisCmd::new();
         match value {
             Value::Bulk(values) => {
@@ -234,8 +282,7 @@ impl RedisCdcExtractor {
     async fn keep_alive_ack(&mut self) -> anyhow::Result<()> {
         // send replconf ack to keep the connection alive
         let mut position_repl_offset = self.repl_offset;
-        if let Position::Redis { repl_offset, .. } = self.syncer.lock().unwrap().committed_position
-        {
+        if let Some(repl_offset) = self.get_committed_repl_offset() {
             if repl_offset >= self.repl_offset {
                 position_repl_offset = repl_offset
             }
@@ -251,6 +298,24 @@ impl RedisCdcExtractor {
         Ok(())
     }
 
+    fn get_committed_repl_offset(&self) -> Option<u64> {
+        // node extractors of a cluster share the task syncer, whose committed position
+        // holds the positions of all masters, find the one of this node by repl_id
+        let get_repl_offset = |position: &Position| match position {
+            Position::Redis {
+                repl_id,
+                repl_offset,
+                ..
+            } if *repl_id == self.repl_id => Some(*repl_offset),
+            _ => None,
+        };
+
+        match &self.syncer.lock().unwrap().committed_position {
+            Position::RedisCluster { nodes, .. } => nodes.values().find_map(get_repl_offset),
+            position => get_repl_offset(position),
+        }
+    }
+
     async fn start_heartbeat(
         &self,
         db_id: i64,
@@ -312,3 +377,287 @@ impl RedisCdcExtractor {
         Ok(())
     }
 }
+
+impl RedisCdcExtractor {
+    async fn extract_cluster(&mut self) -> anyhow::Result<()> {
+        let mut node_positions =
+            if let Position::RedisCluster { nodes, .. } = &self.resumer.current_position {
+                log_info!("resume from: {}", self.resumer.current_position);
+                nodes.clone()
+            } else {
+                BTreeMap::new()
+            };
+
+        // psync from all masters concurrently, re-discover masters and resume if any node
+        // fails (e.g. failover) or the topology changes
+        let mut retry_times = 0;
+        loop {
+            let record_count = self.base_extractor.monitor.counters.record_count;
+            let err = match self.extract_cluster_nodes(&mut node_positions).await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+
+            if self.base_extractor.monitor.counters.record_count > record_count {
+                retry_times = 0;
+            }
+            retry_times += 1;
+            if retry_times > CLUSTER_MAX_RETRY_TIMES {
+                return Err(err);
+            }
+
+            log_warn!(
+                "psync from redis cluster interrupted, will re-discover masters and resume, retry times: {}, error: {:?}",
+                retry_times,
+                err
+            );
+            TimeUtil::sleep_millis(CLUSTER_RETRY_INTERVAL_SECS * 1000).await;
+        }
+    }
+
+    async fn extract_cluster_nodes(
+        &mut self,
+        node_positions: &mut BTreeMap<String, Position>,
+    ) -> anyhow::Result<()> {
+        let mut conn = RedisUtil::create_redis_conn(&self.conn.url).await?;
+        let nodes = Self::get_cluster_master_nodes(&mut conn)?;
+        log_info!(
+            "psync from cluster masters: {:?}",
+            nodes.iter().map(|node| &node.address).collect::<Vec<_>>()
+        );
+
+        *node_positions = self.match_node_positions(&nodes, node_positions).await?;
+
+        let mut node_buffers = Vec::new();
+        let mut handles = Vec::new();
+        for node in nodes.iter() {
+            let buffer = Arc::new(DtQueue::new(CLUSTER_NODE_BUFFER_SIZE, 0));
+            let mut extractor = self
+                .create_node_extractor(node, node_positions.get(&node.address), buffer.clone())
+                .await?;
+            handles.push(tokio::spawn(async move { extractor.extract().await }));
+            node_buffers.push(buffer);
+        }
+
+        let result = self
+            .forward_node_data(
+                &mut conn,
+                &nodes,
+                &node_buffers,
+                &mut handles,
+                node_positions,
+            )
+            .await;
+        for handle in handles.iter() {
+            handle.abort();
+        }
+        result
+    }
+
+    async fn forward_node_data(
+        &mut self,
+        conn: &mut redis::Connection,
+        nodes: &[ClusterNode],
+        node_buffers: &[Arc<DtQueue>],
+        handles: &mut [JoinHandle<anyhow::Result<()>>],
+        node_positions: &mut BTreeMap<String, Position>,
+    ) -> anyhow::Result<()> {
+        // data between MULTI and EXEC of a node must be pushed together,
+        // otherwise data marker of two-way sync may be mixed up with other nodes
+        let mut tx_node = None;
+        let mut timestamp = String::new();
+        let mut last_check_time = Instant::now();
+        loop {
+            let mut idle = true;
+            for (i, buffer) in node_buffers.iter().enumerate() {
+                while tx_node.is_none() || tx_node == Some(i) {
+                    let item = match buffer.pop() {
+                        Ok(item) => item,
+                        Err(_) => break,
+                    };
+                    idle = false;
+
+                    if item.dt_data.is_begin() {
+                        tx_node = Some(i);
+                    } else if item.dt_data.is_commit() {
+                        tx_node = None;
+                    }
+
+                    // entries from rdb have no position
+                    let position = if let Position::Redis {
+                        timestamp: node_timestamp,
+                        ..
+                    } = &item.position
+                    {
+                        // only the node holding the heartbeat key generates timestamp
+                        if !node_timestamp.is_empty() {
+                            timestamp = node_timestamp.clone();
+                        }
+                        node_positions.insert(nodes[i].address.clone(), item.position);
+                        Position::RedisCluster {
+                            nodes: node_positions.clone(),
+                            timestamp: timestamp.clone(),
+                        }
+                    } else {
+                        item.position
+                    };
+                    self.base_extractor
+                        .push_dt_data(item.dt_data, position)
+                        .await?;
+
+                    // pop one item from each node in turn if not in transaction
+                    if tx_node.is_none() {
+                        break;
+                    }
+                }
+            }
+
+            for (i, handle) in handles.iter_mut().enumerate() {
+                if handle.is_finished() {
+                    let result = handle.await;
+                    bail! {Error::ExtractorError(format!(
+                        "psync from cluster node: {} stopped, result: {:?}",
+                        nodes[i].address, result
+                    ))}
+                }
+            }
+
+            if last_check_time.elapsed().as_secs() >= CLUSTER_TOPOLOGY_CHECK_INTERVAL_SECS {
+                last_check_time = Instant::now();
+                let mut addresses: Vec<_> = nodes.iter().map(|node| &node.address).collect();
+                let current_nodes = Self::get_cluster_master_nodes(conn)?;
+                let mut current_addresses: Vec<_> =
+                    current_nodes.iter().map(|node| &node.address).collect();
+                addresses.sort();
+                current_addresses.sort();
+                if addresses != current_addresses {
+                    bail! {Error::ExtractorError(format!(
+                        "cluster masters changed from {:?} to {:?}",
+                        addresses, current_addresses
+                    ))}
+                }
+            }
+
+            if idle {
+                TimeUtil::sleep_millis(1).await;
+            }
+        }
+    }
+
+    async fn match_node_positions(
+        &self,
+        nodes: &[ClusterNode],
+        saved_positions: &BTreeMap<String, Position>,
+    ) -> anyhow::Result<BTreeMap<String, Position>> {
+        let mut node_positions = BTreeMap::new();
+        for node in nodes.iter() {
+            if let Some(position) = saved_positions.get(&node.address) {
+                node_positions.insert(node.address.clone(), position.clone());
+                continue;
+            }
+
+            // a master promoted by failover keeps the replication id of the old master,
+            // so it can continue psync from the position of the old master
+            let node_url = RedisUtil::get_cluster_node_url(&self.conn.url, &node.address)?;
+            let mut conn = RedisUtil::create_redis_conn(&node_url).await?;
+            let repl_ids = RedisUtil::get_repl_ids(&mut conn)?;
+            let matched = saved_positions.iter().find(|(_, position)| {
+                matches!(position, Position::Redis { repl_id, .. } if repl_ids.contains(repl_id))
+            });
+
+            if let Some((address, position)) = matched {
+                log_info!(
+                    "cluster node: {} resumes from the position of node: {}",
+                    node.address,
+                    address
+                );
+                node_positions.insert(node.address.clone(), position.clone());
+            } else if !saved_positions.is_empty() {
+                log_warn!(
+                    "no position found for cluster node: {}, will do full sync from it",
+                    node.address
+                );
+            }
+        }
+        Ok(node_positions)
+    }
+
+    async fn create_node_extractor(
+        &self,
+        node: &ClusterNode,
+        position: Option<&Position>,
+        buffer: Arc<DtQueue>,
+    ) -> anyhow::Result<RedisCdcExtractor> {
+        let (repl_id, repl_offset) = match position {
+            Some(Position::Redis {
+                repl_id,
+                repl_offset,
+                ..
+            }) => (repl_id.clone(), *repl_offset),
+            _ => (String::new(), 0),
+        };
+
+        // heartbeat key can only be written to the master holding its slot
+        let heartbeat_db_key = ConfigTokenParser::parse(
+            &self.heartbeat_key,
+            &['.'],
+            &SqlUtil::get_escape_pairs(&DbType::Redis),
+        );
+        let heartbeat_interval_secs = if heartbeat_db_key.len() == 2
+            && node
+                .slots
+                .contains(&KeyParser::calc_slot(heartbeat_db_key[1].as_bytes()))
+        {
+            self.heartbeat_interval_secs
+        } else {
+            0
+        };
+
+        // node extractors have their own monitors to avoid counting data twice
+        let monitor = {
+            let monitor = self.base_extractor.monitor.monitor.lock().unwrap();
+            Monitor::new(
+                "extractor",
+                monitor.time_window_secs,
+                monitor.max_sub_count,
+                monitor.count_window,
+            )
+        };
+        let base_extractor = BaseExtractor {
+            buffer,
+            router: self.base_extractor.router.clone(),
+            // node extractors should never shut down the task
+            shut_down: Arc::new(AtomicBool::new(false)),
+            monitor: ExtractorMonitor::new(Arc::new(Mutex::new(monitor))),
+            // data marker works when data of all nodes are pushed to the task buffer
+            data_marker: None,
+            time_filter: TimeFilter::default(),
+        };
+
+        let node_url = RedisUtil::get_cluster_node_url(&self.conn.url, &node.address)?;
+        Ok(RedisCdcExtractor {
+            base_extractor,
+            conn: RedisClient::new(&node_url).await?,
+            repl_id,
+            repl_offset,
+            repl_port: self.repl_port,
+            now_db_id: 0,
+            keepalive_interval_secs: self.keepalive_interval_secs,
+            heartbeat_interval_secs,
+            heartbeat_key: self.heartbeat_key.clone(),
+            // share the task syncer so the node acks the offset committed by the sinkers
+            syncer: self.syncer.clone(),
+            filter: self.filter.clone(),
+            resumer: CdcResumer::default(),
+            is_cluster: false,
+        })
+    }
+
+    fn get_cluster_master_nodes(conn: &mut redis::Connection) -> anyhow::Result<Vec<ClusterNode>> {
+        // masters without slots (e.g. failed ones) hold no data
+        Ok(RedisUtil::get_cluster_master_nodes(conn)?
+            .into_iter()
+            .filter(|node| !node.slots.is_empty())
+            .collect())
+    }
+}
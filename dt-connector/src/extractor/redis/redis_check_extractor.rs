@@ -0,0 +1,488 @@
+use std::{collections::HashMap, io::Cursor};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    error::Error,
+    log_info, log_warn,
+    meta::{
+        position::Position,
+        redis::{
+            command::{cmd_encoder::CmdEncoder, key_parser::KeyParser},
+            redis_entry::{RedisEntry, RedisEntryChunk},
+            redis_object::{
+                HashObject, ListObject, RedisCmd, RedisObject, RedisString, SetObject, ZSetEntry,
+                ZsetObject,
+            },
+        },
+    },
+    utils::redis_util::RedisUtil,
+};
+use redis::{Connection, ConnectionLike, Value};
+
+use crate::{
+    check_log::check_log::CheckLog,
+    extractor::{base_check_extractor::BaseCheckExtractor, base_extractor::BaseExtractor},
+    sinker::redis::redis_checker::KEY_COL,
+    BatchCheckExtractor, Extractor,
+};
+
+use super::{
+    rdb::{entry_parser::entry_parser::EntryParser, reader::rdb_reader::RdbReader},
+    StreamReader,
+};
+
+// DUMP payload: type byte + value + rdb version (2 bytes) + crc64 (8 bytes)
+const DUMP_TAIL_LEN: usize = 10;
+
+pub struct RedisCheckExtractor {
+    pub base_extractor: BaseExtractor,
+    pub url: String,
+    pub check_log_dir: String,
+    pub batch_size: usize,
+    pub is_cluster: bool,
+    // node address -> connection, the only node is keyed by url if not cluster
+    pub conns: HashMap<String, Connection>,
+    pub slot_node_map: HashMap<u16, &'static str>,
+}
+
+#[async_trait]
+impl Extractor for RedisCheckExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!("RedisCheckExtractor starts");
+        self.init_conns().await?;
+        let base_check_extractor = BaseCheckExtractor {
+            check_log_dir: self.check_log_dir.clone(),
+            batch_size: self.batch_size,
+        };
+        base_check_extractor.extract(self).await?;
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+#[async_trait]
+impl BatchCheckExtractor for RedisCheckExtractor {
+    async fn batch_extract(&mut self, check_logs: &[CheckLog]) -> anyhow::Result<()> {
+        // logs in the same batch share the same db
+        let db_id: i64 = check_logs[0].schema.parse()?;
+
+        let mut node_keys: HashMap<String, Vec<RedisString>> = HashMap::new();
+        for check_log in check_logs.iter() {
+            if let Some(Some(key)) = check_log.id_col_values.get(KEY_COL) {
+                let key = RedisString::from(key.clone());
+                let node = self.get_node(&key);
+                node_keys.entry(node).or_default().push(key);
+            }
+        }
+
+        for (node, keys) in node_keys {
+            let (keys, big_keys) = self.split_big_keys(&node, db_id, keys)?;
+            for entry in self.dump_keys(&node, db_id, keys)? {
+                self.base_extractor
+                    .push_redis_entry(entry, Position::None)
+                    .await?;
+            }
+            for (key, key_type, total_len) in big_keys {
+                self.extract_big_key(&node, db_id, key, &key_type, total_len)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RedisCheckExtractor {
+    async fn init_conns(&mut self) -> anyhow::Result<()> {
+        let mut conn = RedisUtil::create_redis_conn(&self.url).await?;
+        if !self.is_cluster {
+            self.conns.insert(self.url.clone(), conn);
+            return Ok(());
+        }
+
+        let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
+        for node in nodes.iter().filter(|node| !node.slots.is_empty()) {
+            let node_url = RedisUtil::get_cluster_node_url(&self.url, &node.address)?;
+            let node_conn = RedisUtil::create_redis_conn(&node_url).await?;
+            self.conns.insert(node.address.clone(), node_conn);
+        }
+        self.slot_node_map = RedisUtil::get_slot_address_map(&nodes);
+        Ok(())
+    }
+
+    fn get_node(&self, key: &RedisString) -> String {
+        if !self.is_cluster {
+            return self.url.clone();
+        }
+        let slot = KeyParser::calc_slot(key.as_bytes());
+        self.slot_node_map[&slot].to_string()
+    }
+
+    /// Splits keys into small ones to be dumped and big collections with more than batch_size
+    /// elements, returned as (key, type, length), to be extracted in chunks
+    fn split_big_keys(
+        &mut self,
+        node: &str,
+        db_id: i64,
+        keys: Vec<RedisString>,
+    ) -> anyhow::Result<(Vec<RedisString>, Vec<(RedisString, String, usize)>)> {
+        let cmds: Vec<RedisCmd> = keys.iter().map(|key| Self::key_cmd("TYPE", key)).collect();
+        let key_types = self.query_pipeline(node, db_id, &cmds)?;
+
+        let mut small_keys = Vec::new();
+        let mut collection_keys = Vec::new();
+        for (key, key_type) in keys.into_iter().zip(key_types) {
+            let key_type = Self::to_string(key_type);
+            match key_type.as_str() {
+                "hash" | "set" | "zset" | "list" => collection_keys.push((key, key_type)),
+                _ => small_keys.push(key),
+            }
+        }
+
+        let cmds: Vec<RedisCmd> = collection_keys
+            .iter()
+            .map(|(key, key_type)| {
+                let name = match key_type.as_str() {
+                    "hash" => "HLEN",
+                    "set" => "SCARD",
+                    "zset" => "ZCARD",
+                    _ => "LLEN",
+                };
+                Self::key_cmd(name, key)
+            })
+            .collect();
+        let lens = self.query_pipeline(node, db_id, &cmds)?;
+
+        let mut big_keys = Vec::new();
+        for ((key, key_type), len) in collection_keys.into_iter().zip(lens) {
+            let len = match len {
+                Value::Int(len) => len as usize,
+                _ => 0,
+            };
+            if len > self.batch_size {
+                big_keys.push((key, key_type, len));
+            } else {
+                small_keys.push(key);
+            }
+        }
+        Ok((small_keys, big_keys))
+    }
+
+    /// Elements of a big collection are read by HSCAN / SSCAN / ZSCAN / LRANGE in chunks of
+    /// batch_size instead of DUMP, each chunk is pushed as an entry holding part of the value
+    async fn extract_big_key(
+        &mut self,
+        node: &str,
+        db_id: i64,
+        key: RedisString,
+        key_type: &str,
+        total_len: usize,
+    ) -> anyhow::Result<()> {
+        let expire_ms = match self
+            .query_pipeline(node, db_id, &[Self::key_cmd("PTTL", &key)])?
+            .pop()
+        {
+            Some(Value::Int(pttl)) if pttl > 0 => pttl,
+            _ => 0,
+        };
+
+        // an entry is pushed after the next chunk is read, so that the last one can be marked
+        let mut pending: Option<RedisEntry> = None;
+        let mut offset = 0;
+        let mut cursor = "0".to_string();
+        loop {
+            let (value, len, is_end) =
+                self.read_chunk(node, db_id, &key, key_type, offset, &mut cursor)?;
+            if len > 0 {
+                let mut entry = RedisEntry::new();
+                entry.is_base = true;
+                entry.db_id = db_id;
+                entry.key = key.clone();
+                entry.value = value;
+                entry.expire_ms = expire_ms;
+                entry.data_size = entry.get_data_malloc_size();
+                entry.chunk = Some(RedisEntryChunk {
+                    offset,
+                    total_len,
+                    is_last: false,
+                });
+                offset += len;
+
+                if let Some(entry) = pending.replace(entry) {
+                    self.base_extractor
+                        .push_redis_entry(entry, Position::None)
+                        .await?;
+                }
+            }
+            if is_end {
+                break;
+            }
+        }
+
+        match pending {
+            Some(mut entry) => {
+                if let Some(chunk) = entry.chunk.as_mut() {
+                    chunk.is_last = true;
+                }
+                self.base_extractor
+                    .push_redis_entry(entry, Position::None)
+                    .await
+            }
+            None => {
+                log_warn!("key no longer exists in src, db: {}, key: {}", db_id, key);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns (chunk value, element count of the chunk, whether the whole key has been read)
+    fn read_chunk(
+        &mut self,
+        node: &str,
+        db_id: i64,
+        key: &RedisString,
+        key_type: &str,
+        offset: usize,
+        cursor: &mut String,
+    ) -> anyhow::Result<(RedisObject, usize, bool)> {
+        if key_type == "list" {
+            let mut cmd = Self::key_cmd("LRANGE", key);
+            cmd.add_str_arg(&offset.to_string());
+            cmd.add_str_arg(&(offset + self.batch_size - 1).to_string());
+            let result = self.query_pipeline(node, db_id, &[cmd])?.pop();
+            let mut obj = ListObject::new();
+            obj.key = key.clone();
+            obj.elements = Self::to_redis_strings(result);
+            let len = obj.elements.len();
+            return Ok((RedisObject::List(obj), len, len < self.batch_size));
+        }
+
+        let name = match key_type {
+            "hash" => "HSCAN",
+            "set" => "SSCAN",
+            _ => "ZSCAN",
+        };
+        let mut cmd = Self::key_cmd(name, key);
+        cmd.add_str_arg(cursor.as_str());
+        cmd.add_str_arg("COUNT");
+        cmd.add_str_arg(&self.batch_size.to_string());
+        let mut result = match self.query_pipeline(node, db_id, &[cmd])?.pop() {
+            Some(Value::Array(result)) => result.into_iter(),
+            _ => Vec::new().into_iter(),
+        };
+        *cursor = result.next().map(Self::to_string).unwrap_or_default();
+        let elements = Self::to_redis_strings(result.next());
+        // the iteration ends when the cursor goes back to 0
+        let is_end = cursor.as_str() == "0" || cursor.is_empty();
+
+        // SCAN may return an element more than once, which does no harm to check and revise
+        let (value, len) = match key_type {
+            "hash" => {
+                let mut obj = HashObject::new();
+                obj.key = key.clone();
+                let mut elements = elements.into_iter();
+                while let (Some(field), Some(value)) = (elements.next(), elements.next()) {
+                    obj.value.insert(field, value);
+                }
+                let len = obj.value.len();
+                (RedisObject::Hash(obj), len)
+            }
+            "set" => {
+                let mut obj = SetObject::new();
+                obj.key = key.clone();
+                obj.elements = elements;
+                let len = obj.elements.len();
+                (RedisObject::Set(obj), len)
+            }
+            _ => {
+                let mut obj = ZsetObject::new();
+                obj.key = key.clone();
+                let mut elements = elements.into_iter();
+                while let (Some(member), Some(score)) = (elements.next(), elements.next()) {
+                    obj.elements.push(ZSetEntry { member, score });
+                }
+                let len = obj.elements.len();
+                (RedisObject::Zset(obj), len)
+            }
+        };
+        Ok((value, len, is_end))
+    }
+
+    fn dump_keys(
+        &mut self,
+        node: &str,
+        db_id: i64,
+        keys: Vec<RedisString>,
+    ) -> anyhow::Result<Vec<RedisEntry>> {
+        let mut cmds = Vec::new();
+        for key in keys.iter() {
+            cmds.push(Self::key_cmd("DUMP", key));
+            cmds.push(Self::key_cmd("PTTL", key));
+        }
+        let mut results = self.query_pipeline(node, db_id, &cmds)?.into_iter();
+
+        let mut entries = Vec::new();
+        for key in keys {
+            let (dump, pttl) = (results.next(), results.next());
+            let dump = match dump {
+                Some(Value::BulkString(dump)) => dump,
+                _ => {
+                    log_warn!("key no longer exists in src, db: {}, key: {}", db_id, key);
+                    continue;
+                }
+            };
+            let expire_ms = match pttl {
+                Some(Value::Int(pttl)) if pttl > 0 => pttl,
+                _ => 0,
+            };
+
+            let mut entry = Self::parse_dump(key, &dump)?;
+            entry.db_id = db_id;
+            entry.expire_ms = expire_ms;
+            entry.data_size = entry.get_data_malloc_size();
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// Sends cmds in a pipeline after SELECT db_id, results of cmds are returned
+    fn query_pipeline(
+        &mut self,
+        node: &str,
+        db_id: i64,
+        cmds: &[RedisCmd],
+    ) -> anyhow::Result<Vec<Value>> {
+        if cmds.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut packed_cmds =
+            CmdEncoder::encode(&RedisCmd::from_str_args(&["SELECT", &db_id.to_string()]));
+        for cmd in cmds.iter() {
+            packed_cmds.extend_from_slice(&CmdEncoder::encode(cmd));
+        }
+        let conn = self.conns.get_mut(node).unwrap();
+        Ok(conn.req_packed_commands(&packed_cmds, 1, cmds.len())?)
+    }
+
+    fn key_cmd(name: &str, key: &RedisString) -> RedisCmd {
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg(name);
+        cmd.add_redis_arg(key);
+        cmd
+    }
+
+    fn to_string(value: Value) -> String {
+        match value {
+            Value::SimpleString(v) => v,
+            Value::BulkString(v) => String::from_utf8_lossy(&v).to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn to_redis_strings(value: Option<Value>) -> Vec<RedisString> {
+        match value {
+            Some(Value::Array(values)) => values
+                .into_iter()
+                .filter_map(|v| match v {
+                    Value::BulkString(v) => Some(RedisString::from(v)),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn parse_dump(key: RedisString, dump: &[u8]) -> anyhow::Result<RedisEntry> {
+        if dump.len() <= DUMP_TAIL_LEN {
+            bail! {Error::RedisRdbError(format!(
+                "invalid dump payload, key: {}",
+                key
+            ))}
+        }
+
+        let type_byte = dump[0];
+        let raw_bytes = &dump[1..dump.len() - DUMP_TAIL_LEN];
+        let mut cursor = Cursor::new(raw_bytes);
+        let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(&mut cursor);
+        let mut reader = RdbReader {
+            conn: &mut stream_reader,
+            rdb_length: raw_bytes.len(),
+            position: 0,
+            copy_raw: false,
+            raw_bytes: Vec::new(),
+        };
+
+        let value = EntryParser::parse_object(&mut reader, type_byte, key.clone())?;
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.key = key;
+        entry.value = value;
+        entry.value_type_byte = type_byte;
+        entry.raw_bytes = raw_bytes.to_vec();
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_dump(type_byte: u8, value: &[u8]) -> Vec<u8> {
+        let mut dump = vec![type_byte];
+        dump.extend_from_slice(value);
+        // rdb version + crc64, not verified when parsing
+        dump.extend_from_slice(&[11, 0]);
+        dump.extend_from_slice(&[0; 8]);
+        dump
+    }
+
+    #[test]
+    fn test_parse_dump() {
+        let key = RedisString::from("k".to_string());
+
+        // string: len + bytes
+        let dump = build_dump(0, b"\x05hello");
+        let entry = RedisCheckExtractor::parse_dump(key.clone(), &dump).unwrap();
+        assert!(entry.is_raw());
+        assert_eq!(entry.raw_bytes, b"\x05hello");
+        match entry.value {
+            RedisObject::String(obj) => assert_eq!(obj.value.as_bytes(), b"hello"),
+            _ => panic!("unexpected type: {}", entry.value.get_type()),
+        }
+
+        // hash: field count + (field, value) pairs
+        let dump = build_dump(4, b"\x01\x01f\x01v");
+        let entry = RedisCheckExtractor::parse_dump(key.clone(), &dump).unwrap();
+        match entry.value {
+            RedisObject::Hash(obj) => {
+                assert_eq!(obj.value.len(), 1);
+                assert_eq!(
+                    obj.value[&RedisString::from("f".to_string())].as_bytes(),
+                    b"v"
+                );
+            }
+            _ => panic!("unexpected type: {}", entry.value.get_type()),
+        }
+
+        // payload without value
+        assert!(RedisCheckExtractor::parse_dump(key, &build_dump(0, b"")).is_err());
+    }
+
+    #[test]
+    fn test_to_redis_strings() {
+        let value = Value::Array(vec![
+            Value::BulkString(b"a".to_vec()),
+            Value::Nil,
+            Value::BulkString(b"b".to_vec()),
+        ]);
+        let strings = RedisCheckExtractor::to_redis_strings(Some(value));
+        assert_eq!(
+            strings,
+            vec![
+                RedisString::from("a".to_string()),
+                RedisString::from("b".to_string())
+            ]
+        );
+        assert!(RedisCheckExtractor::to_redis_strings(None).is_empty());
+    }
+}
@@ -174,14 +174,21 @@ impl RedisPsyncExtractor<'_> {
         mut entry: RedisEntry,
         position: Position,
     ) -> anyhow::Result<()> {
-        // currently only support db filter
-        if filter.filter_schema(&entry.db_id.to_string()) {
+        // data marker should be neither filtered nor routed
+        if let Some(data_marker) = &base_extractor.data_marker {
+            if !entry.is_base && entry.cmd.get_str_arg(1) == data_marker.marker {
+                entry.data_size = entry.get_data_malloc_size();
+                return base_extractor
+                    .push_dt_data(DtData::Redis { entry }, position)
+                    .await;
+            }
+        }
+
+        if filter.filter_redis_entry(&mut entry) {
             return Ok(());
         }
 
         entry.data_size = entry.get_data_malloc_size();
-        base_extractor
-            .push_dt_data(DtData::Redis { entry }, position)
-            .await
+        base_extractor.push_redis_entry(entry, position).await
     }
 }
@@ -1,120 +1,162 @@
-use std::{cmp, collections::HashMap};
+use std::{collections::HashMap, fs, path::Path};
 
 use async_trait::async_trait;
 use dt_common::{
-    log_debug, log_info,
+    log_debug, log_error, log_info,
     meta::redis::{
         cluster_node::ClusterNode, command::cmd_encoder::CmdEncoder, redis_object::RedisCmd,
     },
     utils::{redis_util::RedisUtil, url_util::UrlUtil},
 };
 use redis::{Connection, ConnectionLike};
-use serde_json::json;
 
 use crate::{extractor::base_extractor::BaseExtractor, Extractor};
 
-const SLOTS_COUNT: usize = 16384;
+use super::redis_reshard_planner::{RedisReshardPlanner, ReshardPlan, SlotMove};
 
 pub struct RedisReshardExtractor {
     pub base_extractor: BaseExtractor,
     pub url: String,
+    // e.g. 127.0.0.1:6371:1,127.0.0.1:6372:2, slots are spread evenly if empty
+    pub node_weights: String,
+    // e.g. 127.0.0.1:6371:0-8191,127.0.0.1:6372:8192-16383, takes precedence over node_weights
+    pub target_slot_map: String,
+    // where the plan is saved, an existing plan is loaded and resumed
+    pub plan_file: String,
+    // only generate the plan without moving slots
+    pub dry_run: bool,
 }
 
 #[async_trait]
 impl Extractor for RedisReshardExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
         log_info!("RedisReshardExtractor starts");
-        self.reshard().await.unwrap();
+        self.reshard().await?;
         self.base_extractor.wait_task_finish().await
     }
 }
 
 impl RedisReshardExtractor {
-    pub async fn reshard(&self) -> anyhow::Result<()> {
+    pub async fn reshard(&mut self) -> anyhow::Result<()> {
         let mut conn = RedisUtil::create_redis_conn(&self.url).await?;
         let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
-        let slot_address_map = RedisUtil::get_slot_address_map(&nodes);
-        let avg_slot_count = SLOTS_COUNT / nodes.len();
-
-        // find nodes with slots to be moved out
-        let mut move_out_slots = Vec::new();
         for node in nodes.iter() {
-            log_info!("node: [{}] has [{}] slots", node.id, node.slots.len());
-            for i in avg_slot_count..node.slots.len() {
-                move_out_slots.push(node.slots[i]);
-            }
+            log_info!(
+                "node: [{}], address: [{}] has [{}] slots",
+                node.id,
+                node.address,
+                node.slots.len()
+            );
         }
 
-        // find nodes with slots to be moved in
-        let mut node_move_in_slots = HashMap::new();
-        let mut i = 0;
-        for node in nodes.iter() {
-            if avg_slot_count <= node.slots.len() || move_out_slots.len() <= i {
-                continue;
+        let plan = self.get_plan(&nodes)?;
+        Self::log_plan(&plan);
+        if self.dry_run {
+            if self.plan_file.is_empty() {
+                log_info!("reshard plan: {}", serde_json::to_string(&plan)?);
             }
+            log_info!("dry run, no slots are moved");
+            return Ok(());
+        }
 
-            let count = cmp::min(move_out_slots.len() - i, avg_slot_count - node.slots.len());
-            let slots = move_out_slots[i..i + count].to_vec();
-            i += count;
+        // validate the plan before any slot is set to MIGRATING / IMPORTING
+        let pending_moves = RedisReshardPlanner::get_pending_moves(&nodes, &plan)?;
+        log_info!(
+            "slots to move: {}, slots moved by previous runs: {}",
+            pending_moves.len(),
+            plan.moves.len() - pending_moves.len()
+        );
+        self.move_slots(&nodes, &pending_moves).await
+    }
 
-            log_info!("will move slots to: [{}], slots: {}", node.id, json!(slots));
-            node_move_in_slots.insert(node.id.clone(), slots);
+    fn get_plan(&self, nodes: &[ClusterNode]) -> anyhow::Result<ReshardPlan> {
+        if !self.dry_run && !self.plan_file.is_empty() && Path::new(&self.plan_file).exists() {
+            log_info!("load reshard plan from: {}", self.plan_file);
+            let plan = serde_json::from_str(&fs::read_to_string(&self.plan_file)?)?;
+            return Ok(plan);
         }
 
-        self.move_slots(&nodes, &node_move_in_slots, &slot_address_map)
-            .await?;
+        let plan = if !self.target_slot_map.is_empty() {
+            let target_slot_map =
+                RedisReshardPlanner::parse_target_slot_map(&self.target_slot_map)?;
+            RedisReshardPlanner::plan_by_slot_map(nodes, &target_slot_map)?
+        } else {
+            let node_weights = RedisReshardPlanner::parse_node_weights(&self.node_weights)?;
+            RedisReshardPlanner::plan_by_weights(nodes, &node_weights)?
+        };
 
-        Ok(())
+        // save the plan before moving slots, so an interrupted task can resume with the same plan
+        if !self.plan_file.is_empty() {
+            fs::write(&self.plan_file, serde_json::to_string_pretty(&plan)?)?;
+            log_info!("reshard plan saved to: {}", self.plan_file);
+        }
+        Ok(plan)
+    }
+
+    fn log_plan(plan: &ReshardPlan) {
+        let mut move_counts: HashMap<(&str, &str), usize> = HashMap::new();
+        for slot_move in plan.moves.iter() {
+            *move_counts
+                .entry((slot_move.src.as_str(), slot_move.dst.as_str()))
+                .or_default() += 1;
+        }
+        for ((src, dst), count) in move_counts.iter() {
+            log_info!("will move {} slots from: [{}] to: [{}]", count, src, dst);
+        }
     }
 
     async fn move_slots(
-        &self,
+        &mut self,
         nodes: &[ClusterNode],
-        node_move_in_slots: &HashMap<String, Vec<u16>>,
-        slot_address_map: &HashMap<u16, &str>,
+        moves: &[SlotMove],
     ) -> anyhow::Result<()> {
-        for (dst_node_id, move_in_slots) in node_move_in_slots.iter() {
-            // get dst_node by id
-            let dst_node = nodes.iter().find(|i| i.id == *dst_node_id).unwrap();
-            let mut dst_conn = self.get_node_conn(dst_node).await?;
-
-            let mut cur_src_node: Option<ClusterNode> = None;
-            let mut cur_src_conn: Option<Connection> = None;
-            for slot in move_in_slots.iter() {
-                // get src_node by address
-                let src_address = slot_address_map.get(slot).unwrap().to_string();
-                let src_node = nodes.iter().find(|i| i.address == *src_address).unwrap();
-
-                // get src conn
-                let src_node_changed =
-                    cur_src_node.is_none() || src_node.id != cur_src_node.as_ref().unwrap().id;
-                if src_node_changed {
-                    cur_src_node = Some(src_node.clone());
-                    cur_src_conn = Some(self.get_node_conn(src_node).await?);
-                }
-
-                // move slot
-                self.setslot_and_migrate(
-                    src_node,
-                    dst_node,
-                    cur_src_conn.as_mut().unwrap(),
-                    &mut dst_conn,
-                    *slot,
-                )
-                .await?;
+        let mut cur_src: Option<(&ClusterNode, Connection)> = None;
+        let mut cur_dst: Option<(&ClusterNode, Connection)> = None;
+        for (i, slot_move) in moves.iter().enumerate() {
+            // moves are grouped by dst, reuse connections of the same nodes
+            if cur_src.is_none() || cur_src.as_ref().unwrap().0.address != slot_move.src {
+                let src_node = nodes
+                    .iter()
+                    .find(|node| node.address == slot_move.src)
+                    .unwrap();
+                cur_src = Some((src_node, self.get_node_conn(src_node).await?));
             }
+            if cur_dst.is_none() || cur_dst.as_ref().unwrap().0.address != slot_move.dst {
+                let dst_node = nodes
+                    .iter()
+                    .find(|node| node.address == slot_move.dst)
+                    .unwrap();
+                cur_dst = Some((dst_node, self.get_node_conn(dst_node).await?));
+            }
+            let (src_node, src_conn) = cur_src.as_mut().unwrap();
+            let (dst_node, dst_conn) = cur_dst.as_mut().unwrap();
+
+            let key_count =
+                self.setslot_and_migrate(src_node, dst_node, src_conn, dst_conn, slot_move.slot)?;
+
+            // report progress, each migrated key is counted as a record
+            self.base_extractor.monitor.counters.record_count += key_count;
+            self.base_extractor.monitor.try_flush(false);
+            log_info!(
+                "moved slot {} with {} keys, progress: {}/{}",
+                slot_move.slot,
+                key_count,
+                i + 1,
+                moves.len()
+            );
         }
+        self.base_extractor.monitor.try_flush(true);
         Ok(())
     }
 
-    async fn setslot_and_migrate(
+    fn setslot_and_migrate(
         &self,
         src_node: &ClusterNode,
         dst_node: &ClusterNode,
         src_conn: &mut Connection,
         dst_conn: &mut Connection,
         slot: u16,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<usize> {
         log_info!(
             "moving slot {} from {} to {}",
             slot,
@@ -125,6 +167,38 @@ impl RedisReshardExtractor {
         let keys = Self::get_keys_in_slot(src_conn, slot)?;
         log_info!("slot {} has {} keys", slot, keys.len());
 
+        if let Err(err) = self.migrate_slot(src_node, dst_node, src_conn, dst_conn, slot, &keys) {
+            // keys already migrated are in dst, setting the slot back to STABLE would make them
+            // unreachable, so leave it in MIGRATING / IMPORTING state, clients are redirected by ASK
+            // meanwhile, and the remaining keys will be migrated when the plan is resumed
+            log_error!(
+                "failed to move slot {} from {} to {}, the slot is left in migrating / importing state, rerun the task with the same plan_file to resume, error: {}",
+                slot,
+                src_node.id,
+                dst_node.id,
+                err
+            );
+            return Err(err);
+        }
+
+        log_info!(
+            "moved slot {} from {} to {}",
+            slot,
+            src_node.id,
+            dst_node.id
+        );
+        Ok(keys.len())
+    }
+
+    fn migrate_slot(
+        &self,
+        src_node: &ClusterNode,
+        dst_node: &ClusterNode,
+        src_conn: &mut Connection,
+        dst_conn: &mut Connection,
+        slot: u16,
+        keys: &[String],
+    ) -> anyhow::Result<()> {
         // cluster setslot importing
         let dst_cmd = RedisCmd::from_str_args(&[
             "cluster",
@@ -144,7 +218,8 @@ impl RedisReshardExtractor {
         dst_conn.req_packed_command(&CmdEncoder::encode(&dst_cmd))?;
         src_conn.req_packed_command(&CmdEncoder::encode(&src_cmd))?;
 
-        // migrate
+        // migrate with the credentials of the cluster url, dst may require auth
+        let auth_args = self.get_migrate_auth_args()?;
         for key in keys.iter() {
             log_debug!(
                 "migrating key: [{}] in slot {} from {} to {}",
@@ -153,16 +228,17 @@ impl RedisReshardExtractor {
                 src_node.id,
                 dst_node.id
             );
-            let cmd = RedisCmd::from_str_args(&[
+            let mut args = vec![
                 "migrate",
-                &dst_node.host,
-                &dst_node.port,
+                dst_node.host.as_str(),
+                dst_node.port.as_str(),
                 "",
                 "0",
                 "5000",
-                "keys",
-                key,
-            ]);
+            ];
+            args.extend(auth_args.iter().map(String::as_str));
+            args.extend(["keys", key.as_str()]);
+            let cmd = RedisCmd::from_str_args(&args);
             src_conn.req_packed_command(&CmdEncoder::encode(&cmd))?;
         }
 
@@ -176,13 +252,6 @@ impl RedisReshardExtractor {
         ]);
         dst_conn.req_packed_command(&CmdEncoder::encode(&cmd))?;
         src_conn.req_packed_command(&CmdEncoder::encode(&cmd))?;
-        log_info!(
-            "moved slot {} from {} to {}",
-            slot,
-            src_node.id,
-            dst_node.id
-        );
-
         Ok(())
     }
 
@@ -196,10 +265,22 @@ impl RedisReshardExtractor {
     }
 
     async fn get_node_conn(&self, node: &ClusterNode) -> anyhow::Result<Connection> {
+        let url = RedisUtil::get_cluster_node_url(&self.url, &node.address)?;
+        RedisUtil::create_redis_conn(&url).await
+    }
+
+    fn get_migrate_auth_args(&self) -> anyhow::Result<Vec<String>> {
         let url_info = UrlUtil::parse(&self.url)?;
         let username = url_info.username();
-        let password = url_info.password().unwrap_or("").to_string();
-        let url = format!("redis://{}:{}@{}", username, password, node.address);
-        RedisUtil::create_redis_conn(&url).await
+        let password = url_info.password().unwrap_or("");
+        let args = if !username.is_empty() {
+            // AUTH2 since redis 6.0 for ACL users
+            vec!["auth2".into(), username.into(), password.into()]
+        } else if !password.is_empty() {
+            vec!["auth".into(), password.into()]
+        } else {
+            Vec::new()
+        };
+        Ok(args)
     }
 }
@@ -0,0 +1,385 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::bail;
+use dt_common::{error::Error, meta::redis::cluster_node::ClusterNode};
+use serde::{Deserialize, Serialize};
+
+pub const SLOTS_COUNT: usize = 16384;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotMove {
+    pub slot: u16,
+    // address of the source node, e.g. 127.0.0.1:6371
+    pub src: String,
+    // address of the target node
+    pub dst: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReshardPlan {
+    pub moves: Vec<SlotMove>,
+}
+
+pub struct RedisReshardPlanner {}
+
+impl RedisReshardPlanner {
+    /// Distributes slots by node weights, nodes not in node_weights have weight 1 if node_weights
+    /// is empty, which spreads slots evenly. Each node keeps its leading slots within its target count.
+    pub fn plan_by_weights(
+        nodes: &[ClusterNode],
+        node_weights: &HashMap<String, u32>,
+    ) -> anyhow::Result<ReshardPlan> {
+        Self::check_addresses(nodes, node_weights.keys())?;
+
+        let mut weights = Vec::new();
+        for node in nodes.iter() {
+            let weight = if node_weights.is_empty() {
+                1
+            } else if let Some(weight) = node_weights.get(&node.address) {
+                *weight
+            } else {
+                bail! {Error::ConfigError(format!(
+                    "weight of redis node: {} is not set",
+                    node.address
+                ))}
+            };
+            weights.push(weight as usize);
+        }
+
+        let total_weight: usize = weights.iter().sum();
+        if total_weight == 0 {
+            bail! {Error::ConfigError("total weight of redis nodes is 0".into())}
+        }
+
+        // target slot count of each node, the remainders go to nodes with larger fractions
+        let mut targets: Vec<usize> = weights
+            .iter()
+            .map(|w| SLOTS_COUNT * w / total_weight)
+            .collect();
+        let mut fractions: Vec<(usize, usize)> = weights
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| **w > 0)
+            .map(|(i, w)| (SLOTS_COUNT * w % total_weight, i))
+            .collect();
+        fractions.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        let remainder = SLOTS_COUNT - targets.iter().sum::<usize>();
+        for (_, i) in fractions.iter().take(remainder) {
+            targets[*i] += 1;
+        }
+
+        // slots to be moved out of nodes with more slots than target
+        let mut move_out_slots = Vec::new();
+        for (node, target) in nodes.iter().zip(targets.iter()) {
+            for slot in node.slots.iter().skip(*target) {
+                move_out_slots.push((*slot, node.address.clone()));
+            }
+        }
+
+        // fill nodes with fewer slots than target
+        let mut plan = ReshardPlan::default();
+        let mut move_out_iter = move_out_slots.into_iter();
+        for (node, target) in nodes.iter().zip(targets.iter()) {
+            for _ in node.slots.len()..*target {
+                if let Some((slot, src)) = move_out_iter.next() {
+                    plan.moves.push(SlotMove {
+                        slot,
+                        src,
+                        dst: node.address.clone(),
+                    });
+                }
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Moves slots to the nodes in target_slot_map, slots not in the map stay where they are
+    pub fn plan_by_slot_map(
+        nodes: &[ClusterNode],
+        target_slot_map: &HashMap<u16, String>,
+    ) -> anyhow::Result<ReshardPlan> {
+        Self::check_addresses(nodes, target_slot_map.values())?;
+
+        let mut plan = ReshardPlan::default();
+        for dst_node in nodes.iter() {
+            let mut slots: Vec<u16> = target_slot_map
+                .iter()
+                .filter(|(_, dst)| **dst == dst_node.address)
+                .map(|(slot, _)| *slot)
+                .collect();
+            slots.sort();
+
+            for slot in slots {
+                let src_node = Self::get_slot_owner(nodes, slot)?;
+                if src_node.address != dst_node.address {
+                    plan.moves.push(SlotMove {
+                        slot,
+                        src: src_node.address.clone(),
+                        dst: dst_node.address.clone(),
+                    });
+                }
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Validates the plan against the current cluster and returns the moves not finished yet,
+    /// moves whose slot is already owned by dst were finished by a previous run, slots left in
+    /// migrating/importing state by an interrupted move of the plan are moved again
+    pub fn get_pending_moves(
+        nodes: &[ClusterNode],
+        plan: &ReshardPlan,
+    ) -> anyhow::Result<Vec<SlotMove>> {
+        for node in nodes.iter() {
+            for slot in node.open_slots.iter() {
+                let is_planned = plan
+                    .moves
+                    .iter()
+                    .any(|i| i.slot == *slot && (i.src == node.address || i.dst == node.address));
+                if !is_planned {
+                    bail! {Error::RedisResultError(format!(
+                        "redis node: {} has slot {} in migrating/importing state not moved by the plan, fix it before resharding",
+                        node.address, slot
+                    ))}
+                }
+            }
+        }
+
+        let mut planned_slots = HashSet::new();
+        let mut pending_moves = Vec::new();
+        for slot_move in plan.moves.iter() {
+            if slot_move.src == slot_move.dst {
+                bail! {Error::ConfigError(format!(
+                    "invalid reshard plan, slot {} has the same src and dst: {}",
+                    slot_move.slot, slot_move.src
+                ))}
+            }
+            if !planned_slots.insert(slot_move.slot) {
+                bail! {Error::ConfigError(format!(
+                    "invalid reshard plan, slot {} is moved more than once",
+                    slot_move.slot
+                ))}
+            }
+            if !nodes.iter().any(|i| i.address == slot_move.dst) {
+                bail! {Error::ConfigError(format!(
+                    "invalid reshard plan, redis node: {} is not a master in cluster",
+                    slot_move.dst
+                ))}
+            }
+
+            let owner = Self::get_slot_owner(nodes, slot_move.slot)?;
+            if owner.address == slot_move.dst {
+                continue;
+            }
+            if owner.address != slot_move.src {
+                bail! {Error::ConfigError(format!(
+                    "invalid reshard plan, slot {} is owned by {} instead of {}",
+                    slot_move.slot, owner.address, slot_move.src
+                ))}
+            }
+            pending_moves.push(slot_move.clone());
+        }
+        Ok(pending_moves)
+    }
+
+    /// e.g. 127.0.0.1:6371:1,127.0.0.1:6372:2
+    pub fn parse_node_weights(config: &str) -> anyhow::Result<HashMap<String, u32>> {
+        let mut node_weights = HashMap::new();
+        for token in Self::split_config(config) {
+            if let Some((address, weight)) = token.rsplit_once(':') {
+                if let Ok(weight) = weight.parse::<u32>() {
+                    node_weights.insert(address.to_string(), weight);
+                    continue;
+                }
+            }
+            bail! {Error::ConfigError(format!("invalid node_weights: {}", token))}
+        }
+        Ok(node_weights)
+    }
+
+    /// e.g. 127.0.0.1:6371:0-8191,127.0.0.1:6372:8192-16383
+    pub fn parse_target_slot_map(config: &str) -> anyhow::Result<HashMap<u16, String>> {
+        let mut target_slot_map = HashMap::new();
+        for token in Self::split_config(config) {
+            let parsed = token.rsplit_once(':').and_then(|(address, range)| {
+                Self::parse_slot_range(range).map(|range| (address, range))
+            });
+            let (address, (start, end)) = match parsed {
+                Some(parsed) => parsed,
+                None => bail! {Error::ConfigError(format!("invalid target_slot_map: {}", token))},
+            };
+
+            for slot in start..=end {
+                if target_slot_map.insert(slot, address.to_string()).is_some() {
+                    bail! {Error::ConfigError(format!(
+                        "slot {} is set more than once in target_slot_map",
+                        slot
+                    ))}
+                }
+            }
+        }
+        Ok(target_slot_map)
+    }
+
+    fn parse_slot_range(range: &str) -> Option<(u16, u16)> {
+        let (start, end) = range.split_once('-').unwrap_or((range, range));
+        let start = start.parse::<u16>().ok()?;
+        let end = end.parse::<u16>().ok()?;
+        if start > end || end as usize >= SLOTS_COUNT {
+            return None;
+        }
+        Some((start, end))
+    }
+
+    fn split_config(config: &str) -> impl Iterator<Item = &str> {
+        config
+            .split(',')
+            .map(|i| i.trim())
+            .filter(|i| !i.is_empty())
+    }
+
+    fn check_addresses<'a>(
+        nodes: &[ClusterNode],
+        addresses: impl Iterator<Item = &'a String>,
+    ) -> anyhow::Result<()> {
+        for address in addresses {
+            if !nodes.iter().any(|i| i.address == *address) {
+                bail! {Error::ConfigError(format!(
+                    "redis node: {} is not a master in cluster",
+                    address
+                ))}
+            }
+        }
+        Ok(())
+    }
+
+    fn get_slot_owner(nodes: &[ClusterNode], slot: u16) -> anyhow::Result<&ClusterNode> {
+        if let Some(node) = nodes.iter().find(|i| i.slots.contains(&slot)) {
+            Ok(node)
+        } else {
+            bail! {Error::RedisResultError(format!(
+                "slot {} is not owned by any redis node",
+                slot
+            ))}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_node(address: &str, slots: std::ops::Range<u16>) -> ClusterNode {
+        ClusterNode {
+            is_master: true,
+            id: address.to_string(),
+            master_id: String::new(),
+            host: String::new(),
+            port: String::new(),
+            address: address.to_string(),
+            slots: slots.collect(),
+            open_slots: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_plan_by_weights() {
+        let nodes = vec![
+            mock_node("node_1:6379", 0..16384),
+            mock_node("node_2:6379", 0..0),
+            mock_node("node_3:6379", 0..0),
+        ];
+
+        // evenly
+        let plan = RedisReshardPlanner::plan_by_weights(&nodes, &HashMap::new()).unwrap();
+        let count = |dst: &str| plan.moves.iter().filter(|i| i.dst == dst).count();
+        assert_eq!(plan.moves.len(), 16384 - 5462);
+        assert_eq!(count("node_2:6379"), 5461);
+        assert_eq!(count("node_3:6379"), 5461);
+
+        // weighted
+        let node_weights =
+            RedisReshardPlanner::parse_node_weights("node_1:6379:1,node_2:6379:3,node_3:6379:0")
+                .unwrap();
+        let plan = RedisReshardPlanner::plan_by_weights(&nodes, &node_weights).unwrap();
+        let count = |dst: &str| plan.moves.iter().filter(|i| i.dst == dst).count();
+        assert_eq!(count("node_2:6379"), 12288);
+        assert_eq!(count("node_3:6379"), 0);
+        assert!(plan.moves.iter().all(|i| i.src == "node_1:6379"));
+        assert_eq!(plan.moves[0].slot, 4096);
+
+        // unknown node
+        let node_weights = RedisReshardPlanner::parse_node_weights("node_4:6379:1").unwrap();
+        assert!(RedisReshardPlanner::plan_by_weights(&nodes, &node_weights).is_err());
+    }
+
+    #[test]
+    fn test_plan_by_slot_map() {
+        let nodes = vec![
+            mock_node("node_1:6379", 0..8192),
+            mock_node("node_2:6379", 8192..16384),
+        ];
+
+        let target_slot_map = RedisReshardPlanner::parse_target_slot_map(
+            "node_1:6379:0-100,node_2:6379:101-200,node_1:6379:16383",
+        )
+        .unwrap();
+        let plan = RedisReshardPlanner::plan_by_slot_map(&nodes, &target_slot_map).unwrap();
+        assert_eq!(plan.moves.len(), 101);
+        assert_eq!(
+            plan.moves[0],
+            SlotMove {
+                slot: 16383,
+                src: "node_2:6379".into(),
+                dst: "node_1:6379".into(),
+            }
+        );
+        assert_eq!(plan.moves[1].slot, 101);
+
+        assert!(RedisReshardPlanner::parse_target_slot_map("node_1:6379:0-16384").is_err());
+        assert!(
+            RedisReshardPlanner::parse_target_slot_map("node_1:6379:0-1,node_2:6379:1").is_err()
+        );
+    }
+
+    #[test]
+    fn test_get_pending_moves() {
+        let mut nodes = vec![
+            mock_node("node_1:6379", 1..16384),
+            mock_node("node_2:6379", 0..1),
+        ];
+
+        let new_move = |slot: u16| SlotMove {
+            slot,
+            src: "node_1:6379".into(),
+            dst: "node_2:6379".into(),
+        };
+        // slot 0 was moved by the last run
+        let plan = ReshardPlan {
+            moves: vec![new_move(0), new_move(1), new_move(2)],
+        };
+        let pending_moves = RedisReshardPlanner::get_pending_moves(&nodes, &plan).unwrap();
+        assert_eq!(pending_moves, vec![new_move(1), new_move(2)]);
+
+        // duplicate slots
+        let plan = ReshardPlan {
+            moves: vec![new_move(1), new_move(1)],
+        };
+        assert!(RedisReshardPlanner::get_pending_moves(&nodes, &plan).is_err());
+
+        // slot 1 left in migrating state by the last run
+        nodes[0].open_slots.push(1);
+        nodes[1].open_slots.push(1);
+        let plan = ReshardPlan {
+            moves: vec![new_move(1)],
+        };
+        let pending_moves = RedisReshardPlanner::get_pending_moves(&nodes, &plan).unwrap();
+        assert_eq!(pending_moves, vec![new_move(1)]);
+
+        // slots in migrating state not moved by the plan
+        let plan = ReshardPlan {
+            moves: vec![new_move(2)],
+        };
+        assert!(RedisReshardPlanner::get_pending_moves(&nodes, &plan).is_err());
+    }
+}
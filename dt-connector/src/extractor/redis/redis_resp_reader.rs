@@ -1,7 +1,6 @@
 use anyhow::{bail, Ok};
 use async_recursion::async_recursion;
-use async_std::io::BufReader;
-use async_std::net::TcpStream;
+use async_std::io::BufRead;
 use async_std::prelude::*;
 
 use super::redis_resp_types::Value;
@@ -16,7 +15,10 @@ const OK_RESPONSE: &[u8] = &[79, 75];
 
 impl RedisRespReader {
     #[async_recursion]
-    pub async fn decode(&mut self, reader: &mut BufReader<TcpStream>) -> anyhow::Result<Value> {
+    pub async fn decode<R: BufRead + Unpin + Send>(
+        &mut self,
+        reader: &mut R,
+    ) -> anyhow::Result<Value> {
         let mut res: Vec<u8> = Vec::new();
         reader.read_until(b'\n', &mut res).await?;
 
@@ -83,6 +85,8 @@ impl RedisRespReader {
                 }
                 Ok(Value::Bulk(array))
             }
+            // annotations in aof files, e.g. "#TS:1628217470\r\n" if aof-timestamp-enabled
+            b'#' => Ok(Value::Nil),
             prefix => bail!(format!("invalid RESP type: {:?}", prefix)),
         }
     }
@@ -30,6 +30,13 @@ impl Extractor for RedisScanExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
         log_info!("RedisScanExtractor starts");
 
+        // memory report is built on rdb entries instead of SCAN
+        if let RedisStatisticType::Memory = self.statistic_type {
+            bail! {Error::ConfigError(
+                "statistic_type=memory is not supported by scan, use extract_type=snapshot or snapshot_file".into()
+            )}
+        }
+
         if let RedisStatisticType::HotKey = self.statistic_type {
             let maxmemory_policy = self.get_maxmemory_policy().await?;
             if maxmemory_policy != "allkeys-lfu" {
@@ -64,6 +71,7 @@ impl Extractor for RedisScanExtractor {
                     match self.statistic_type {
                         RedisStatisticType::HotKey => self.analyze_hot_key(db_id, key).await?,
                         RedisStatisticType::BigKey => self.analyze_big_key(db_id, key).await?,
+                        RedisStatisticType::Memory => {}
                     }
                 }
 
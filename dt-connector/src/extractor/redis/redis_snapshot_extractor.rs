@@ -3,28 +3,35 @@ use super::redis_psync_extractor::RedisPsyncExtractor;
 use crate::extractor::base_extractor::BaseExtractor;
 use crate::Extractor;
 use async_trait::async_trait;
+use dt_common::log_info;
 use dt_common::rdb_filter::RdbFilter;
+use dt_common::utils::redis_util::RedisUtil;
 
 pub struct RedisSnapshotExtractor {
     pub base_extractor: BaseExtractor,
     pub conn: RedisClient,
     pub repl_port: u64,
     pub filter: RdbFilter,
+    pub is_cluster: bool,
 }
 
 #[async_trait]
 impl Extractor for RedisSnapshotExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
-        let mut psync_extractor = RedisPsyncExtractor {
-            base_extractor: &mut self.base_extractor,
-            conn: &mut self.conn,
-            repl_id: String::new(),
-            repl_offset: 0,
-            repl_port: self.repl_port,
-            now_db_id: 0,
-            filter: self.filter.clone(),
-        };
-        psync_extractor.extract().await?;
+        if self.is_cluster {
+            self.extract_cluster().await?;
+        } else {
+            let mut psync_extractor = RedisPsyncExtractor {
+                base_extractor: &mut self.base_extractor,
+                conn: &mut self.conn,
+                repl_id: String::new(),
+                repl_offset: 0,
+                repl_port: self.repl_port,
+                now_db_id: 0,
+                filter: self.filter.clone(),
+            };
+            psync_extractor.extract().await?;
+        }
         self.base_extractor.wait_task_finish().await
     }
 
@@ -32,3 +39,33 @@ impl Extractor for RedisSnapshotExtractor {
         self.conn.close().await
     }
 }
+
+impl RedisSnapshotExtractor {
+    async fn extract_cluster(&mut self) -> anyhow::Result<()> {
+        let mut conn = RedisUtil::create_redis_conn(&self.conn.url).await?;
+        // masters without slots (e.g. failed ones) hold no data
+        let nodes: Vec<_> = RedisUtil::get_cluster_master_nodes(&mut conn)?
+            .into_iter()
+            .filter(|node| !node.slots.is_empty())
+            .collect();
+
+        // receive rdb from masters one by one
+        for node in nodes.iter() {
+            log_info!("start receiving rdb from cluster node: {}", node.address);
+            let node_url = RedisUtil::get_cluster_node_url(&self.conn.url, &node.address)?;
+            let mut node_conn = RedisClient::new(&node_url).await?;
+            let mut psync_extractor = RedisPsyncExtractor {
+                base_extractor: &mut self.base_extractor,
+                conn: &mut node_conn,
+                repl_id: String::new(),
+                repl_offset: 0,
+                repl_port: self.repl_port,
+                now_db_id: 0,
+                filter: self.filter.clone(),
+            };
+            psync_extractor.extract().await?;
+            node_conn.close().await?;
+        }
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@ use crate::extractor::redis::rdb::rdb_parser::RdbParser;
 use crate::extractor::redis::rdb::reader::rdb_reader::RdbReader;
 use crate::extractor::redis::redis_psync_extractor::RedisPsyncExtractor;
 use crate::Extractor;
+use anyhow::Context;
 use async_trait::async_trait;
 use dt_common::log_info;
 use dt_common::meta::position::Position;
@@ -25,8 +26,21 @@ struct RdbFileReader {
 #[async_trait]
 impl Extractor for RedisSnapshotFileExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
-        let file = File::open(&self.file_path).expect("rdb file not found");
-        let metadata = fs::metadata(&self.file_path).expect("rdb file with wrong meta");
+        Self::extract_rdb_file(&mut self.base_extractor, &mut self.filter, &self.file_path).await?;
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+impl RedisSnapshotFileExtractor {
+    pub async fn extract_rdb_file(
+        base_extractor: &mut BaseExtractor,
+        filter: &mut RdbFilter,
+        file_path: &str,
+    ) -> anyhow::Result<()> {
+        let file = File::open(file_path)
+            .with_context(|| format!("failed to open rdb file: {}", file_path))?;
+        let metadata = fs::metadata(file_path)
+            .with_context(|| format!("failed to read metadata of rdb file: {}", file_path))?;
         let mut file_reader = RdbFileReader { file };
         let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(&mut file_reader);
 
@@ -53,24 +67,19 @@ impl Extractor for RedisSnapshotFileExtractor {
 
         loop {
             if let Some(entry) = parser.load_entry()? {
-                RedisPsyncExtractor::push_to_buf(
-                    &mut self.base_extractor,
-                    &mut self.filter,
-                    entry,
-                    Position::None,
-                )
-                .await?;
+                RedisPsyncExtractor::push_to_buf(base_extractor, filter, entry, Position::None)
+                    .await?;
             }
 
             if parser.is_end {
                 log_info!(
                     "end extracting data from rdb, all count: {}",
-                    self.base_extractor.monitor.counters.record_count
+                    base_extractor.monitor.counters.record_count
                 );
                 break;
             }
         }
-        self.base_extractor.wait_task_finish().await
+        Ok(())
     }
 }
 
@@ -14,7 +14,7 @@ pub mod sinker;
 use async_trait::async_trait;
 use check_log::check_log::CheckLog;
 use dt_common::meta::{
-    ddl_meta::ddl_data::DdlData, dt_data::DtItem, row_data::RowData,
+    ddl_meta::ddl_data::DdlData, dt_data::DtItem, position::Position, row_data::RowData,
     struct_meta::struct_data::StructData,
 };
 
@@ -44,6 +44,25 @@ pub trait Sinker {
         Ok(())
     }
 
+    /// Called on each checkpoint before the position is recorded, sinkers with transactions
+    /// commit the data sinked since the last checkpoint together with the position,
+    /// others may store the position in the target to resume from
+    async fn commit_position(&mut self, _position: &Position) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Sinkers committing positions in transactions can not commit a position while rows of an
+    /// unfinished source transaction are sinked after it, the pipeline defers the checkpoint instead
+    fn defers_partial_commit(&self) -> bool {
+        false
+    }
+
+    /// Sinkers reading row_data.position, e.g. to fill the source info of json messages,
+    /// the pipeline sets it for each row only if any sinker needs it
+    fn needs_row_position(&self) -> bool {
+        false
+    }
+
     fn get_id(&self) -> String {
         String::new()
     }
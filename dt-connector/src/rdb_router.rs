@@ -1,10 +1,15 @@
-use anyhow::Ok;
+use anyhow::{bail, Ok};
 use dt_common::{
     config::{
         config_enums::DbType, config_token_parser::ConfigTokenParser, router_config::RouterConfig,
     },
+    error::Error,
     meta::{
         ddl_meta::{ddl_data::DdlData, ddl_statement::DdlStatement},
+        redis::{
+            redis_entry::RedisEntry,
+            redis_object::{RedisObject, RedisString},
+        },
         struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
     },
     utils::sql_util::SqlUtil,
@@ -17,6 +22,7 @@ use serde::{Deserialize, Serialize};
 type SchemaMap = HashMap<String, String>;
 type TbMap = HashMap<(String, String), (String, String)>;
 type TbColMap = HashMap<(String, String), HashMap<String, String>>;
+type KeyMap = Vec<(String, String)>;
 
 const JSON_PREFIX: &str = "json:";
 
@@ -30,6 +36,8 @@ pub struct RdbRouter {
     pub col_map: TbColMap,
     // HashMap<(src_schema, src_tb), String>
     pub topic_map: HashMap<(String, String), String>,
+    // Vec<(src_key_prefix, dst_key_prefix)>, for redis
+    pub key_map: KeyMap,
 }
 
 impl RdbRouter {
@@ -40,16 +48,31 @@ impl RdbRouter {
                 tb_map,
                 col_map,
                 topic_map,
+                key_map,
             } => {
                 let schema_map = Self::parse_schema_map(schema_map, db_type)?;
                 let tb_map = Self::parse_tb_map(tb_map, db_type)?;
                 let col_map = Self::parse_col_map(col_map)?;
                 let topic_map = Self::parse_topic_map(topic_map, db_type)?;
+                let key_map = Self::parse_key_map(key_map)?;
+
+                if *db_type == DbType::Redis {
+                    for (src_db, dst_db) in schema_map.iter() {
+                        if src_db.parse::<i64>().is_err() || dst_db.parse::<i64>().is_err() {
+                            bail! {Error::ConfigError(format!(
+                                "invalid redis db_map: {}:{}, db should be number",
+                                src_db, dst_db
+                            ))}
+                        }
+                    }
+                }
+
                 Ok(Self {
                     schema_map,
                     tb_map,
                     col_map,
                     topic_map,
+                    key_map,
                 })
             }
         }
@@ -110,12 +133,19 @@ impl RdbRouter {
             reverse_schema_map.insert(dst_db.to_owned(), src_schema.to_owned());
         }
 
+        let reverse_key_map = self
+            .key_map
+            .iter()
+            .map(|(src, dst)| (dst.to_owned(), src.to_owned()))
+            .collect();
+
         Self {
             schema_map: reverse_schema_map,
             tb_map: reverse_tb_map,
             col_map: reverse_tb_col_map,
             // topic_map should not be reversed
             topic_map: self.topic_map.clone(),
+            key_map: reverse_key_map,
         }
     }
 
@@ -217,6 +247,77 @@ impl RdbRouter {
         struct_data
     }
 
+    pub fn has_key_map(&self) -> bool {
+        !self.key_map.is_empty()
+    }
+
+    pub fn get_key_map(&self, key: &[u8]) -> Option<Vec<u8>> {
+        // the first matched prefix wins
+        for (src_prefix, dst_prefix) in self.key_map.iter() {
+            if let Some(suffix) = key.strip_prefix(src_prefix.as_bytes()) {
+                return Some([dst_prefix.as_bytes(), suffix].concat());
+            }
+        }
+        None
+    }
+
+    pub fn route_redis_entry(&self, mut entry: RedisEntry) -> RedisEntry {
+        // db map
+        if let Some(dst_db_id) = self.schema_map.get(&entry.db_id.to_string()) {
+            // already validated in from_config
+            entry.db_id = dst_db_id.parse().unwrap();
+        }
+
+        // key map
+        if self.key_map.is_empty() {
+            return entry;
+        }
+
+        if entry.is_base {
+            if let Some(dst_key) = self.get_key_map(entry.key.as_bytes()) {
+                let dst_key = RedisString::from(dst_key);
+                Self::route_redis_object(&mut entry.value, &dst_key);
+                entry.key = dst_key;
+            }
+        } else {
+            // keys of aof command should have been parsed by KeyParser before, key_indexes are 1-based
+            let cmd = &mut entry.cmd;
+            for (i, key_index) in cmd.key_indexes.iter().enumerate() {
+                if let Some(dst_key) = self.get_key_map(&cmd.args[key_index - 1]) {
+                    cmd.keys[i] = String::from_utf8_lossy(&dst_key).to_string();
+                    cmd.args[key_index - 1] = dst_key;
+                }
+            }
+        }
+        entry
+    }
+
+    fn route_redis_object(value: &mut RedisObject, dst_key: &RedisString) {
+        match value {
+            RedisObject::String(obj) => obj.key = dst_key.clone(),
+            RedisObject::List(obj) => obj.key = dst_key.clone(),
+            RedisObject::Hash(obj) => obj.key = dst_key.clone(),
+            RedisObject::Set(obj) => obj.key = dst_key.clone(),
+            RedisObject::Zset(obj) => obj.key = dst_key.clone(),
+            RedisObject::Stream(obj) => {
+                obj.key = dst_key.clone();
+                // XGROUP CREATE key ..., XADD / XSETID / XCLAIM key ...
+                for cmd in obj.cmds.iter_mut() {
+                    let key_index = if cmd.get_name().eq_ignore_ascii_case("xgroup") {
+                        2
+                    } else {
+                        1
+                    };
+                    if cmd.args.len() > key_index {
+                        cmd.args[key_index] = dst_key.as_bytes().to_vec();
+                    }
+                }
+            }
+            RedisObject::Module(obj) => obj.key = dst_key.clone(),
+            RedisObject::Unknown => {}
+        }
+    }
+
     fn parse_schema_map(config_str: &str, db_type: &DbType) -> anyhow::Result<SchemaMap> {
         // db_map=src_db_1:dst_db_1,src_db_2:dst_db_2
         let mut schema_map = HashMap::new();
@@ -262,6 +363,14 @@ impl RdbRouter {
         Ok(topic_map)
     }
 
+    pub fn parse_partition_cols(
+        config_str: &str,
+        db_type: &DbType,
+    ) -> anyhow::Result<HashMap<(String, String), String>> {
+        // partition_cols=test_db_1.tb_1:f_1,test_db_1.*:f_2, same format as topic_map
+        Self::parse_topic_map(config_str, db_type)
+    }
+
     fn parse_col_map(config_str: &str) -> anyhow::Result<TbColMap> {
         let mut results = TbColMap::new();
         if config_str.trim().is_empty() {
@@ -283,6 +392,38 @@ impl RdbRouter {
         Ok(results)
     }
 
+    fn parse_key_map(config_str: &str) -> anyhow::Result<KeyMap> {
+        let mut results = KeyMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct KeyMapType {
+            src: String,
+            dst: String,
+        }
+        // key_map=json:[{"src":"user:*","dst":"tenant1:user:*"}]
+        let config: Vec<KeyMapType> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+        for i in config {
+            // only prefix rewrite supported
+            let (src, dst) = match (i.src.strip_suffix('*'), i.dst.strip_suffix('*')) {
+                (Some(src), Some(dst))
+                    if !src.contains(['*', '?']) && !dst.contains(['*', '?']) =>
+                {
+                    (src.to_string(), dst.to_string())
+                }
+                _ => bail! {Error::ConfigError(format!(
+                    "invalid key_map: {} -> {}, should be like prefix:* -> new_prefix:*",
+                    i.src, i.dst
+                ))},
+            };
+            results.push((src, dst));
+        }
+        Ok(results)
+    }
+
     fn parse_config(config_str: &str, db_type: &DbType) -> anyhow::Result<Vec<String>> {
         let delimiters = vec![',', '.', ':'];
         let tokens = ConfigTokenParser::parse_config(config_str, db_type, &delimiters)?;
@@ -303,7 +444,14 @@ impl RdbRouter {
 mod tests {
     use std::collections::HashMap;
 
-    use dt_common::config::{config_enums::DbType, router_config::RouterConfig};
+    use dt_common::{
+        config::{config_enums::DbType, router_config::RouterConfig},
+        meta::redis::{
+            command::key_parser::KeyParser,
+            redis_entry::RedisEntry,
+            redis_object::{RedisCmd, RedisObject, RedisString, StringObject},
+        },
+    };
 
     use super::{RdbRouter, TbColMap, TbMap};
 
@@ -424,6 +572,7 @@ mod tests {
             tb_map: tb_map_str.into(),
             col_map: col_map_str.into(),
             topic_map: topic_map.into(),
+            key_map: String::new(),
         };
         let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
 
@@ -450,4 +599,50 @@ mod tests {
         assert_eq!(router.get_topic("db:1", "tb:2"), "test2");
         assert_eq!(router.get_topic("db:2", "tb:1"), "test");
     }
+
+    #[test]
+    fn test_route_redis_entry() {
+        let config = RouterConfig::Rdb {
+            schema_map: "0:1".into(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_map: r#"json:[{"src":"user:*","dst":"tenant1:user:*"}]"#.into(),
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Redis).unwrap();
+
+        // rdb entry
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.key = RedisString::from("user:1".to_string());
+        let mut obj = StringObject::new();
+        obj.key = entry.key.clone();
+        entry.value = RedisObject::String(obj);
+        let entry = router.route_redis_entry(entry);
+        assert_eq!(entry.db_id, 1);
+        assert_eq!(entry.key.to_string(), "tenant1:user:1");
+        if let RedisObject::String(obj) = &entry.value {
+            assert_eq!(obj.key.to_string(), "tenant1:user:1");
+        }
+
+        // aof command
+        let mut entry = RedisEntry::new();
+        entry.db_id = 2;
+        entry.cmd = RedisCmd::from_str_args(&["MSET", "user:1", "v1", "order:1", "v2"]);
+        entry.cmd.parse_keys(&KeyParser::new()).unwrap();
+        let entry = router.route_redis_entry(entry);
+        assert_eq!(entry.db_id, 2);
+        assert_eq!(entry.cmd.to_string(), "MSET tenant1:user:1 v1 order:1 v2");
+        assert_eq!(entry.cmd.keys, vec!["tenant1:user:1", "order:1"]);
+
+        // only prefix rewrite supported
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_map: r#"json:[{"src":"user:*:name","dst":"u:*"}]"#.into(),
+        };
+        assert!(RdbRouter::from_config(&config, &DbType::Redis).is_err());
+    }
 }
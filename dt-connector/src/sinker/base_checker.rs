@@ -91,6 +91,12 @@ impl BaseChecker {
         }
     }
 
+    pub fn log_extra(extra: Vec<CheckLog>) {
+        for log in extra {
+            log_extra!("{}", log.to_string());
+        }
+    }
+
     #[inline(always)]
     pub fn compare_struct(
         src_statement: &mut StructStatement,
@@ -215,6 +221,16 @@ impl BaseChecker {
         diff_log
     }
 
+    pub fn build_mongo_extra_log(
+        dst_row_data: RowData,
+        tb_meta: &RdbTbMeta,
+        reverse_router: &RdbRouter,
+    ) -> CheckLog {
+        let mut extra_log = Self::build_mongo_miss_log(dst_row_data, tb_meta, reverse_router);
+        extra_log.log_type = LogType::Extra;
+        extra_log
+    }
+
     fn build_id_col_values(
         row_data: &RowData,
         tb_meta: &RdbTbMeta,
@@ -31,7 +31,9 @@ impl BaseStructSinker {
                     Err(error) => {
                         log_error!("ddl failed, error: {}", error);
                         match conflict_policy {
-                            ConflictPolicyEnum::Interrupt => bail! {error},
+                            ConflictPolicyEnum::Interrupt | ConflictPolicyEnum::Replace => {
+                                bail! {error}
+                            }
                             ConflictPolicyEnum::Ignore => {}
                         }
                     }
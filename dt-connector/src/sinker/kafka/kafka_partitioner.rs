@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use dt_common::{
+    config::config_enums::KafkaPartitionBy,
+    meta::{row_data::RowData, row_type::RowType},
+    utils::kafka_util::KafkaUtil,
+};
+
+use super::kafka_producer::KafkaProducer;
+
+pub struct KafkaPartitioner {
+    pub partition_by: KafkaPartitionBy,
+    // (schema, tb) -> partition col
+    pub partition_cols: HashMap<(String, String), String>,
+    pub partition_counts: HashMap<String, i32>,
+}
+
+impl KafkaPartitioner {
+    /// Partitions are chosen the same way as the default partitioner of the Java client
+    /// (murmur2 of the partition key), rows without partition keys are partitioned by table
+    pub fn get_partition(
+        &mut self,
+        producer: &mut KafkaProducer,
+        topic: &str,
+        row_data: &RowData,
+        key: &str,
+    ) -> anyhow::Result<i32> {
+        let partition_key = match self.partition_by {
+            KafkaPartitionBy::Key => key.to_string(),
+            KafkaPartitionBy::Table => String::new(),
+            KafkaPartitionBy::Column => self.get_partition_col_value(row_data),
+        };
+        let partition_key = if partition_key.is_empty() {
+            format!("{}.{}", row_data.schema, row_data.tb)
+        } else {
+            partition_key
+        };
+
+        let partition_count = self.get_partition_count(producer, topic)?;
+        Ok(KafkaUtil::get_partition(
+            partition_key.as_bytes(),
+            partition_count,
+        ))
+    }
+
+    /// Messages without keys (e.g. redis cmds without keys) are sent to random partitions
+    pub fn get_key_partition(
+        &mut self,
+        producer: &mut KafkaProducer,
+        topic: &str,
+        key: &str,
+    ) -> anyhow::Result<i32> {
+        if key.is_empty() {
+            return Ok(-1);
+        }
+        let partition_count = self.get_partition_count(producer, topic)?;
+        Ok(KafkaUtil::get_partition(key.as_bytes(), partition_count))
+    }
+
+    fn get_partition_col_value(&self, row_data: &RowData) -> String {
+        let col = match self.get_partition_col(row_data) {
+            Some(col) => col,
+            None => return String::new(),
+        };
+
+        let col_values = match row_data.row_type {
+            RowType::Insert => row_data.after.as_ref(),
+            RowType::Update | RowType::Delete => row_data.before.as_ref(),
+        };
+        col_values
+            .and_then(|i| i.get(col))
+            .and_then(|v| v.to_option_string())
+            .unwrap_or_default()
+    }
+
+    pub fn is_partition_col_changed(&self, row_data: &RowData) -> bool {
+        if self.partition_by != KafkaPartitionBy::Column || row_data.row_type != RowType::Update {
+            return false;
+        }
+
+        match self.get_partition_col(row_data) {
+            Some(col) => {
+                let before = row_data.before.as_ref().and_then(|i| i.get(col));
+                let after = row_data.after.as_ref().and_then(|i| i.get(col));
+                before != after
+            }
+            None => false,
+        }
+    }
+
+    fn get_partition_col(&self, row_data: &RowData) -> Option<&String> {
+        let (schema, tb) = (row_data.schema.clone(), row_data.tb.clone());
+        if let Some(col) = self.partition_cols.get(&(schema.clone(), tb)) {
+            return Some(col);
+        }
+        if let Some(col) = self.partition_cols.get(&(schema, "*".into())) {
+            return Some(col);
+        }
+        self.partition_cols.get(&("*".into(), "*".into()))
+    }
+
+    fn get_partition_count(
+        &mut self,
+        producer: &mut KafkaProducer,
+        topic: &str,
+    ) -> anyhow::Result<i32> {
+        if let Some(partition_count) = self.partition_counts.get(topic) {
+            return Ok(*partition_count);
+        }
+
+        let partition_count = producer.get_partition_count(topic)?;
+        self.partition_counts
+            .insert(topic.to_string(), partition_count);
+        Ok(partition_count)
+    }
+}
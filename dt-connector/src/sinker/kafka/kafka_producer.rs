@@ -0,0 +1,159 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{bail, Context};
+use dt_common::error::Error;
+use kafka::{
+    client::KafkaClient,
+    producer::{Producer, Record, RequiredAcks},
+};
+use rdkafka::{
+    producer::{FutureProducer, FutureRecord, Producer as _},
+    ClientConfig,
+};
+
+const METADATA_TIMEOUT_SECS: u64 = 10;
+
+/// Messages are sent by kafka-rust by default, which has better sending performance,
+/// once any client property is configured, they are sent by librdkafka which supports
+/// SASL / SSL, compression, idempotence and all other client properties
+pub enum KafkaProducer {
+    Kafka {
+        producer: Producer,
+        // to get the partition counts of topics
+        client: KafkaClient,
+    },
+    Rdkafka {
+        producer: FutureProducer,
+        queue_timeout_secs: u64,
+    },
+}
+
+impl KafkaProducer {
+    pub fn create(
+        url: &str,
+        ack_timeout_secs: u64,
+        required_acks: &str,
+        properties: &HashMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        if !properties.is_empty() {
+            let producer =
+                Self::create_rdkafka_producer(url, ack_timeout_secs, required_acks, properties)?;
+            return Ok(Self::Rdkafka {
+                producer,
+                queue_timeout_secs: ack_timeout_secs,
+            });
+        }
+
+        let brokers = vec![url.to_string()];
+        let acks = match required_acks {
+            "all" => RequiredAcks::All,
+            "none" => RequiredAcks::None,
+            _ => RequiredAcks::One,
+        };
+        let producer = Producer::from_hosts(brokers.clone())
+            .with_ack_timeout(Duration::from_secs(ack_timeout_secs))
+            .with_required_acks(acks)
+            .create()
+            .with_context(|| format!("failed to create kafka producer, url: [{}]", url))?;
+        let client = KafkaClient::new(brokers);
+        Ok(Self::Kafka { producer, client })
+    }
+
+    /// Properties are applied after the defaults, so they can override acks, message.timeout.ms, etc.
+    pub fn create_rdkafka_producer(
+        url: &str,
+        ack_timeout_secs: u64,
+        required_acks: &str,
+        properties: &HashMap<String, String>,
+    ) -> anyhow::Result<FutureProducer> {
+        let acks = match required_acks {
+            "all" => "all",
+            "none" => "0",
+            _ => "1",
+        };
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", url);
+        config.set("acks", acks);
+        config.set("message.timeout.ms", (ack_timeout_secs * 1000).to_string());
+        // e.g. security.protocol, sasl.mechanism, ssl.ca.location, compression.type, linger.ms
+        for (key, value) in properties.iter() {
+            config.set(key, value);
+        }
+        config
+            .create()
+            .with_context(|| format!("failed to create kafka producer, url: [{}]", url))
+    }
+
+    pub async fn send_all(
+        &mut self,
+        records: &[Record<'_, String, Vec<u8>>],
+    ) -> anyhow::Result<()> {
+        match self {
+            Self::Kafka { producer, .. } => {
+                producer.send_all(records)?;
+            }
+
+            Self::Rdkafka {
+                producer,
+                queue_timeout_secs,
+            } => {
+                let queue_timeout = Duration::from_secs(*queue_timeout_secs);
+                let mut futures = Vec::new();
+                for record in records.iter() {
+                    let mut future_record = FutureRecord::to(record.topic).key(&record.key);
+                    // an empty value is sent as null, which is a tombstone
+                    if !record.value.is_empty() {
+                        future_record = future_record.payload(&record.value);
+                    }
+                    if record.partition >= 0 {
+                        future_record = future_record.partition(record.partition);
+                    }
+                    futures.push(producer.send(future_record, queue_timeout));
+                }
+
+                for future in futures {
+                    if let Err((err, _)) = future.await {
+                        bail! {Error::SinkerError(format!(
+                            "failed in kafka producer, error: {}",
+                            err
+                        ))}
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_partition_count(&mut self, topic: &str) -> anyhow::Result<i32> {
+        let partition_count = match self {
+            Self::Kafka { client, .. } => {
+                client.load_metadata(&[topic])?;
+                client
+                    .topics()
+                    .partitions(topic)
+                    .map(|partitions| partitions.len())
+                    .unwrap_or_default()
+            }
+
+            Self::Rdkafka { producer, .. } => {
+                let metadata = producer
+                    .client()
+                    .fetch_metadata(Some(topic), Duration::from_secs(METADATA_TIMEOUT_SECS))?;
+                metadata
+                    .topics()
+                    .iter()
+                    .find(|i| i.name() == topic)
+                    .map(|i| i.partitions().len())
+                    .unwrap_or_default()
+            }
+        };
+
+        if partition_count == 0 {
+            bail! {Error::SinkerError(format!(
+                "no partitions found for topic: {}",
+                topic
+            ))}
+        }
+        Ok(partition_count as i32)
+    }
+}
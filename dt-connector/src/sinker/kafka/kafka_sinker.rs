@@ -5,30 +5,76 @@ use std::{
 
 use async_trait::async_trait;
 
-use crate::{call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{
+    call_batch_fn,
+    rdb_router::RdbRouter,
+    sinker::{
+        base_sinker::BaseSinker,
+        kafka::{
+            kafka_partitioner::KafkaPartitioner, kafka_producer::KafkaProducer,
+            kafka_transaction::KafkaTransaction, schema_registry::SchemaRegistry,
+        },
+        message_encoder::MessageEncoder,
+        redis::entry_rewriter::EntryRewriter,
+    },
+    Sinker,
+};
 
-use dt_common::{meta::ddl_meta::ddl_data::DdlData, monitor::monitor::Monitor};
+use dt_common::meta::dt_data::{DtData, DtItem};
+use dt_common::{
+    config::config_enums::MessageFormat, meta::ddl_meta::ddl_data::DdlData,
+    monitor::monitor::Monitor,
+};
 
-use dt_common::meta::{avro::avro_converter::AvroConverter, row_data::RowData};
+use dt_common::meta::{position::Position, row_data::RowData, row_type::RowType};
 
-use kafka::producer::{Producer, Record};
+use kafka::producer::Record;
 
 pub struct KafkaSinker {
     pub batch_size: usize,
     pub router: RdbRouter,
-    pub producer: Producer,
-    pub avro_converter: AvroConverter,
+    pub producer: KafkaProducer,
+    pub encoder: MessageEncoder,
+    pub tombstones_on_delete: bool,
+    pub partitioner: KafkaPartitioner,
+    pub schema_registry: SchemaRegistry,
+    // parallel sinkers share the same transactional producer, and only the first one
+    // holds the transaction to commit it on checkpoints
+    pub transaction: Option<KafkaTransaction>,
     pub monitor: Arc<Mutex<Monitor>>,
 }
 
 #[async_trait]
 impl Sinker for KafkaSinker {
-    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+    async fn sink_dml(&mut self, data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let partitioner = &self.partitioner;
+        let mut data = self
+            .encoder
+            .split_key_changed_updates(data, |row_data| {
+                partitioner.is_partition_col_changed(row_data)
+            })
+            .await?;
+        match self.encoder.message_format {
+            MessageFormat::Avro | MessageFormat::ConfluentAvro => {
+                call_batch_fn!(self, data, Self::send_avro);
+            }
+            _ => {
+                call_batch_fn!(self, data, Self::send_json);
+            }
+        }
+        Ok(())
+    }
+
+    async fn sink_raw(&mut self, mut data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
 
-        call_batch_fn!(self, data, Self::send_avro);
+        call_batch_fn!(self, data, Self::send_redis);
         Ok(())
     }
 
@@ -36,7 +82,9 @@ impl Sinker for KafkaSinker {
         let mut messages = Vec::new();
         for ddl_data in data {
             let topic = self.router.get_topic(&ddl_data.default_schema, "");
-            let payload = self.avro_converter.ddl_data_to_avro_value(ddl_data).await?;
+            let Some(payload) = self.encoder.encode_ddl(ddl_data).await? else {
+                continue;
+            };
             messages.push(Record {
                 key: String::new(),
                 value: payload,
@@ -44,12 +92,37 @@ impl Sinker for KafkaSinker {
                 partition: -1,
             });
         }
-        self.producer.send_all(&messages)?;
+        self.producer.send_all(&messages).await?;
         Ok(())
     }
 
     async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
-        self.avro_converter.refresh_meta(&data);
+        self.encoder.refresh_meta(&data);
+        self.schema_registry.refresh_meta(&data);
+        Ok(())
+    }
+
+    async fn commit_position(&mut self, position: &Position) -> anyhow::Result<()> {
+        if let Some(transaction) = &mut self.transaction {
+            transaction.commit(position).await?;
+        }
+        Ok(())
+    }
+
+    fn defers_partial_commit(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    fn needs_row_position(&self) -> bool {
+        self.encoder.needs_row_position()
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        // messages sent after the last checkpoint are discarded, and will be sent again
+        // after the task resumes from the last committed position
+        if let Some(transaction) = &mut self.transaction {
+            transaction.abort()?;
+        }
         Ok(())
     }
 }
@@ -70,20 +143,114 @@ impl KafkaSinker {
 
             row_data.convert_raw_string();
             let topic = self.router.get_topic(&row_data.schema, &row_data.tb);
-            let key = self.avro_converter.row_data_to_avro_key(row_data).await?;
-            let payload = self
-                .avro_converter
-                .row_data_to_avro_value(row_data.clone())
-                .await?;
+            let key = self.encoder.encode_key(row_data).await?;
+            let partition =
+                self.partitioner
+                    .get_partition(&mut self.producer, topic, row_data, &key)?;
+            let payload = if self.encoder.message_format == MessageFormat::ConfluentAvro {
+                self.schema_registry
+                    .row_data_to_avro_value(&mut self.encoder.avro_converter, topic, row_data)
+                    .await?
+            } else {
+                self.encoder.encode_row(row_data).await?
+            };
             messages.push(Record {
                 key,
                 value: payload,
                 topic,
-                partition: -1,
+                partition,
+            });
+        }
+
+        self.producer.send_all(&messages).await?;
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    /// Rows are sent as debezium / canal / maxwell json messages
+    async fn send_json(
+        &mut self,
+        data: &mut [RowData],
+        sinked_count: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+
+        let mut messages = Vec::new();
+        for row_data in data.iter_mut().skip(sinked_count).take(batch_size) {
+            data_size += row_data.data_size;
+
+            row_data.convert_raw_string();
+            let topic = self.router.get_topic(&row_data.schema, &row_data.tb);
+            let key = self.encoder.encode_key(row_data).await?;
+            let partition =
+                self.partitioner
+                    .get_partition(&mut self.producer, topic, row_data, &key)?;
+            let payload = self.encoder.encode_row(row_data).await?;
+
+            messages.push(Record {
+                key: key.clone(),
+                value: payload,
+                topic,
+                partition,
             });
+
+            // a tombstone lets log compaction remove all messages of the deleted key
+            if self.encoder.message_format == MessageFormat::DebeziumJson
+                && self.tombstones_on_delete
+                && row_data.row_type == RowType::Delete
+                && !key.is_empty()
+            {
+                messages.push(Record {
+                    key,
+                    value: Vec::new(),
+                    topic,
+                    partition,
+                });
+            }
+        }
+
+        self.producer.send_all(&messages).await?;
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    /// Each redis cmd is sent as a message keyed by its first key,
+    /// so cmds of the same key are sent to the same partition in order
+    async fn send_redis(
+        &mut self,
+        data: &mut [DtItem],
+        sinked_count: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+
+        let mut messages = Vec::new();
+        for dt_item in data.iter_mut().skip(sinked_count).take(batch_size) {
+            if let DtData::Redis { entry } = &mut dt_item.dt_data {
+                data_size += entry.get_data_malloc_size();
+
+                let position = dt_item.position.to_string();
+                let topic = self.router.get_topic(&entry.db_id.to_string(), "");
+                for cmd_entry in EntryRewriter::rewrite_as_cmd_entries(entry)? {
+                    let key = cmd_entry.cmd.keys.first().cloned().unwrap_or_default();
+                    let partition =
+                        self.partitioner
+                            .get_key_partition(&mut self.producer, topic, &key)?;
+                    let payload = self.encoder.encode_redis_entry(&cmd_entry, &position)?;
+                    messages.push(Record {
+                        key,
+                        value: payload,
+                        topic,
+                        partition,
+                    });
+                }
+            }
         }
 
-        self.producer.send_all(&messages)?;
+        self.producer.send_all(&messages).await?;
 
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
     }
@@ -0,0 +1,212 @@
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use anyhow::bail;
+use dt_common::{error::Error, log_info, log_warn, meta::position::Position};
+use rdkafka::{
+    consumer::{BaseConsumer, Consumer},
+    producer::{FutureProducer, FutureRecord, Producer},
+    ClientConfig, Message, Offset, TopicPartitionList,
+};
+
+use super::kafka_producer::KafkaProducer;
+
+// timeout of each client call, e.g. init_transactions, commit_transaction
+const CLIENT_CALL_TIMEOUT_SECS: u64 = 60;
+const POLL_TIMEOUT_SECS: u64 = 1;
+// default transaction.max.timeout.ms of brokers
+const MAX_TRANSACTION_TIMEOUT_SECS: u64 = 900;
+// time to send the position and commit after the longest interval between two commits
+const TRANSACTION_TIMEOUT_MARGIN_SECS: u64 = 60;
+
+/// Messages sinked between two checkpoints are sent in a kafka transaction, which is committed
+/// together with a message of the checkpoint position, keyed by transactional_id, in the offsets topic.
+/// Consumers with isolation.level=read_committed see each change exactly once, and after restart,
+/// the task resumes from the position committed by the last transaction
+pub struct KafkaTransaction {
+    pub producer: FutureProducer,
+    pub transactional_id: String,
+    pub offsets_topic: String,
+}
+
+impl KafkaTransaction {
+    /// max_commit_interval_secs: the longest time a transaction stays open, including the time
+    /// checkpoints are deferred, the broker aborts transactions open longer than transaction.timeout.ms
+    pub fn create(
+        url: &str,
+        ack_timeout_secs: u64,
+        properties: &HashMap<String, String>,
+        transactional_id: &str,
+        offsets_topic: &str,
+        max_commit_interval_secs: u64,
+    ) -> anyhow::Result<Self> {
+        // transactions require idempotence, which requires acks=all
+        let mut properties = properties.clone();
+        properties.insert("transactional.id".into(), transactional_id.into());
+        if !properties.contains_key("transaction.timeout.ms") {
+            let timeout_secs = Self::get_transaction_timeout_secs(max_commit_interval_secs);
+            properties.insert(
+                "transaction.timeout.ms".into(),
+                (timeout_secs * 1000).to_string(),
+            );
+        }
+        let producer =
+            KafkaProducer::create_rdkafka_producer(url, ack_timeout_secs, "all", &properties)?;
+
+        // the unfinished transaction of the previous run with the same transactional_id is aborted,
+        // and the producer of the previous run (if still alive) is fenced
+        producer.init_transactions(Self::timeout())?;
+        producer.begin_transaction()?;
+        Ok(Self {
+            producer,
+            transactional_id: transactional_id.into(),
+            offsets_topic: offsets_topic.into(),
+        })
+    }
+
+    pub async fn commit(&mut self, position: &Position) -> anyhow::Result<()> {
+        let payload = position.to_string();
+        let record = FutureRecord::to(&self.offsets_topic)
+            .key(&self.transactional_id)
+            .payload(&payload);
+        if let Err((err, _)) = self.producer.send(record, Self::timeout()).await {
+            bail! {Error::SinkerError(format!(
+                "failed to send position to offsets topic: {}, error: {}",
+                self.offsets_topic, err
+            ))}
+        }
+
+        self.producer.commit_transaction(Self::timeout())?;
+        self.producer.begin_transaction()?;
+        Ok(())
+    }
+
+    /// Messages sent after the last commit are discarded
+    pub fn abort(&mut self) -> anyhow::Result<()> {
+        self.producer.abort_transaction(Self::timeout())?;
+        Ok(())
+    }
+
+    /// Read the position committed by the last transaction of transactional_id
+    pub fn read_committed_position(
+        url: &str,
+        properties: &HashMap<String, String>,
+        transactional_id: &str,
+        offsets_topic: &str,
+        max_commit_interval_secs: u64,
+    ) -> anyhow::Result<Position> {
+        // abort the unfinished transaction of the previous run first,
+        // otherwise read_committed consumers are blocked by it until it times out
+        Self::create(
+            url,
+            CLIENT_CALL_TIMEOUT_SECS,
+            properties,
+            transactional_id,
+            offsets_topic,
+            max_commit_interval_secs,
+        )?;
+
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", url);
+        config.set("group.id", format!("{}_offsets_reader", transactional_id));
+        config.set("enable.auto.commit", "false");
+        for (key, value) in properties.iter() {
+            config.set(key, value);
+        }
+        config.set("isolation.level", "read_committed");
+        let consumer: BaseConsumer = config.create()?;
+
+        let metadata = consumer.fetch_metadata(Some(offsets_topic), Self::timeout())?;
+        let partitions: Vec<i32> = metadata
+            .topics()
+            .iter()
+            .filter(|i| i.name() == offsets_topic)
+            .flat_map(|i| i.partitions().iter().map(|p| p.id()))
+            .collect();
+        if partitions.is_empty() {
+            bail! {Error::SinkerError(format!(
+                "offsets topic: {} not found",
+                offsets_topic
+            ))}
+        }
+
+        let mut tpl = TopicPartitionList::new();
+        let mut watermarks = HashMap::new();
+        for partition in partitions {
+            let watermark = consumer.fetch_watermarks(offsets_topic, partition, Self::timeout())?;
+            watermarks.insert(partition, watermark);
+            tpl.add_partition_offset(offsets_topic, partition, Offset::Beginning)?;
+        }
+        consumer.assign(&tpl)?;
+
+        let mut position = Position::None;
+        let poll_timeout = Duration::from_secs(POLL_TIMEOUT_SECS);
+        let start_time = std::time::Instant::now();
+        loop {
+            if let Some(msg) = consumer.poll(poll_timeout) {
+                let msg = msg?;
+                if msg.key() == Some(transactional_id.as_bytes()) {
+                    if let Some(payload) = msg.payload() {
+                        position = Position::from_str(&String::from_utf8_lossy(payload))?;
+                    }
+                }
+            }
+
+            // the consumer position passes the commit / abort markers of transactions,
+            // the high watermark is reached once all committed messages are consumed
+            let reached = consumer.position()?.elements().iter().all(|i| {
+                match (i.offset(), watermarks.get(&i.partition())) {
+                    (Offset::Offset(offset), Some((_, high))) => offset >= *high,
+                    (_, Some((low, high))) => low >= high,
+                    _ => true,
+                }
+            });
+            if reached {
+                break;
+            }
+
+            if start_time.elapsed().as_secs() > CLIENT_CALL_TIMEOUT_SECS {
+                bail! {Error::SinkerError(format!(
+                    "timeout reading offsets topic: {}",
+                    offsets_topic
+                ))}
+            }
+        }
+
+        log_info!(
+            "position committed by transactional_id: {}, position: {}",
+            transactional_id,
+            position
+        );
+        Ok(position)
+    }
+
+    fn get_transaction_timeout_secs(max_commit_interval_secs: u64) -> u64 {
+        let timeout_secs = max_commit_interval_secs + TRANSACTION_TIMEOUT_MARGIN_SECS;
+        if timeout_secs > MAX_TRANSACTION_TIMEOUT_SECS {
+            log_warn!(
+                "transactions may stay open for {} secs, longer than transaction.max.timeout.ms of brokers by default, set transaction.timeout.ms in properties if the brokers allow it, or decrease checkpoint_interval_secs",
+                timeout_secs
+            );
+            return MAX_TRANSACTION_TIMEOUT_SECS;
+        }
+        timeout_secs
+    }
+
+    fn timeout() -> Duration {
+        Duration::from_secs(CLIENT_CALL_TIMEOUT_SECS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_transaction_timeout_secs() {
+        assert_eq!(KafkaTransaction::get_transaction_timeout_secs(100), 160);
+        assert_eq!(
+            KafkaTransaction::get_transaction_timeout_secs(3000),
+            MAX_TRANSACTION_TIMEOUT_SECS
+        );
+    }
+}
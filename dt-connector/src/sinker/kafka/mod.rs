@@ -1,2 +1,6 @@
+pub mod kafka_partitioner;
+pub mod kafka_producer;
 pub mod kafka_sinker;
+pub mod kafka_transaction;
 pub mod rdkafka_sinker;
+pub mod schema_registry;
@@ -13,6 +13,8 @@ use dt_common::monitor::monitor::Monitor;
 
 use dt_common::meta::{avro::avro_converter::AvroConverter, row_data::RowData};
 
+/// The producer should be created by KafkaProducer::create_rdkafka_producer,
+/// so the client properties in [sinker] (kafka.xxx) are applied
 pub struct RdkafkaSinker {
     pub batch_size: usize,
     pub router: RdbRouter,
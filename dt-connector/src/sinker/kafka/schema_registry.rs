@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use dt_common::{
+    error::Error,
+    meta::{
+        avro::{avro_converter::AvroConverter, avro_table_schema::AvroTableSchema},
+        ddl_meta::ddl_data::DdlData,
+        row_data::RowData,
+    },
+};
+use reqwest::{header, Client, StatusCode};
+use serde_json::{json, Value};
+
+const CONTENT_TYPE: &str = "application/vnd.schemaregistry.v1+json";
+const MAGIC_BYTE: u8 = 0;
+
+/// A client of confluent compatible schema registry, typed avro schemas of tables are
+/// registered under subjects of TopicRecordNameStrategy: {topic}-{db}.{tb}.Envelope,
+/// so tables routed to the same topic can evolve independently
+#[derive(Clone)]
+pub struct SchemaRegistry {
+    pub url: String,
+    pub http_client: Client,
+    // (schema, tb) -> (schema id, avro schema)
+    cache: HashMap<(String, String), (i32, AvroTableSchema)>,
+}
+
+impl SchemaRegistry {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            http_client: Client::new(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Schemas of tables changed by DDL are generated and registered again,
+    /// the registry rejects them if they are incompatible with the previous versions
+    pub fn refresh_meta(&mut self, data: &[DdlData]) {
+        for ddl_data in data.iter() {
+            let (schema, tb) = ddl_data.get_schema_tb();
+            if !schema.is_empty() && !tb.is_empty() {
+                self.cache.remove(&(schema, tb));
+            } else {
+                self.cache.clear();
+            }
+        }
+    }
+
+    /// Confluent wire format: magic byte 0 + schema id in 4 bytes big-endian + avro datum
+    pub async fn row_data_to_avro_value(
+        &mut self,
+        avro_converter: &mut AvroConverter,
+        topic: &str,
+        row_data: &RowData,
+    ) -> anyhow::Result<Vec<u8>> {
+        let cache_key = (row_data.schema.clone(), row_data.tb.clone());
+        if !self.cache.contains_key(&cache_key) {
+            let table_schema = avro_converter.get_avro_table_schema(row_data).await?;
+            let subject = format!("{}-{}", topic, table_schema.full_name);
+            let schema_id = self.register(&subject, &table_schema.schema_str).await?;
+            self.cache
+                .insert(cache_key.clone(), (schema_id, table_schema));
+        }
+
+        let (schema_id, table_schema) = self.cache.get(&cache_key).unwrap();
+        let mut payload = vec![MAGIC_BYTE];
+        payload.extend_from_slice(&schema_id.to_be_bytes());
+        payload.extend(table_schema.row_data_to_avro_datum(row_data)?);
+        Ok(payload)
+    }
+
+    /// Registering an existing schema returns the existing schema id
+    pub async fn register(&self, subject: &str, schema_str: &str) -> anyhow::Result<i32> {
+        let url = format!("{}/subjects/{}/versions", self.url, subject);
+        let response = self
+            .http_client
+            .post(&url)
+            .header(header::CONTENT_TYPE, CONTENT_TYPE)
+            .body(json!({ "schema": schema_str }).to_string())
+            .send()
+            .await?;
+
+        let status_code = response.status();
+        let response_text = response.text().await?;
+        // 409 if the schema is incompatible with the previous versions
+        if status_code != StatusCode::OK {
+            bail! {Error::HttpError(format!(
+                "failed to register schema, subject: {}, status_code: {}, response_text: {:?}",
+                subject, status_code, response_text
+            ))}
+        }
+
+        let response: Value = serde_json::from_str(&response_text)?;
+        match response.get("id").and_then(|i| i.as_i64()) {
+            Some(id) => Ok(id as i32),
+            None => bail! {Error::HttpError(format!(
+                "no schema id in response of schema registry, subject: {}, response_text: {:?}",
+                subject, response_text
+            ))},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// A mock registry serving a single request, returns the raw request received
+    async fn mock_registry(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((headers, content)) = text.split_once("\r\n\r\n") {
+                    let content_length = headers
+                        .lines()
+                        .find_map(|i| {
+                            i.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().to_string())
+                        })
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or_default();
+                    if content.len() >= content_length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                CONTENT_TYPE,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_register() {
+        let (url, handle) = mock_registry("200 OK", r#"{"id":7}"#).await;
+        let registry = SchemaRegistry::new(&url);
+        let schema_str = r#"{"type":"string"}"#;
+        let id = registry
+            .register("test_topic-test_db.tb_1.Envelope", schema_str)
+            .await
+            .unwrap();
+        assert_eq!(id, 7);
+
+        let request = handle.await.unwrap();
+        assert!(request
+            .starts_with("POST /subjects/test_topic-test_db.tb_1.Envelope/versions HTTP/1.1"));
+        assert!(request.contains(CONTENT_TYPE));
+        assert!(request.ends_with(&json!({ "schema": schema_str }).to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_register_incompatible() {
+        let (url, handle) = mock_registry(
+            "409 Conflict",
+            r#"{"error_code":409,"message":"Schema being registered is incompatible"}"#,
+        )
+        .await;
+        let registry = SchemaRegistry::new(&url);
+        let result = registry
+            .register("test_topic-test_db.tb_1.Envelope", r#"{"type":"string"}"#)
+            .await;
+        assert!(result.unwrap_err().to_string().contains("409"));
+        handle.await.unwrap();
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::bail;
+use dt_common::{
+    config::config_enums::{DbType, MessageFormat},
+    error::Error,
+    meta::{
+        avro::avro_converter::AvroConverter,
+        canal::canal_converter::CanalConverter,
+        ddl_meta::ddl_data::DdlData,
+        debezium::debezium_converter::DebeziumConverter,
+        maxwell::maxwell_converter::MaxwellConverter,
+        position::Position,
+        rdb_meta_manager::RdbMetaManager,
+        redis::{redis_entry::RedisEntry, redis_json_converter::RedisJsonConverter},
+        row_data::RowData,
+    },
+};
+
+/// Encodes rows and ddls as messages in the configured message_format,
+/// shared by the sinkers of message queues
+#[derive(Clone)]
+pub struct MessageEncoder {
+    pub message_format: MessageFormat,
+    pub avro_converter: AvroConverter,
+    pub debezium_converter: DebeziumConverter,
+    pub canal_converter: CanalConverter,
+    pub maxwell_converter: MaxwellConverter,
+}
+
+impl MessageEncoder {
+    pub fn new(
+        meta_manager: Option<RdbMetaManager>,
+        db_type: DbType,
+        with_field_defs: bool,
+        message_format: MessageFormat,
+    ) -> Self {
+        Self {
+            message_format,
+            avro_converter: AvroConverter::new(meta_manager.clone(), with_field_defs),
+            debezium_converter: DebeziumConverter::new(
+                meta_manager.clone(),
+                db_type,
+                with_field_defs,
+            ),
+            canal_converter: CanalConverter::new(meta_manager.clone()),
+            maxwell_converter: MaxwellConverter::new(meta_manager),
+        }
+    }
+
+    /// Json messages contain the source info from row_data.position
+    pub fn needs_row_position(&self) -> bool {
+        matches!(
+            self.message_format,
+            MessageFormat::DebeziumJson | MessageFormat::CanalJson | MessageFormat::MaxwellJson
+        )
+    }
+
+    pub fn refresh_meta(&mut self, data: &[DdlData]) {
+        self.avro_converter.refresh_meta(data);
+        self.debezium_converter.refresh_meta(data);
+        self.canal_converter.refresh_meta(data);
+        self.maxwell_converter.refresh_meta(data);
+    }
+
+    pub async fn encode_key(&mut self, row_data: &RowData) -> anyhow::Result<String> {
+        match self.message_format {
+            MessageFormat::DebeziumJson => {
+                self.debezium_converter
+                    .row_data_to_debezium_key(row_data)
+                    .await
+            }
+            _ => self.avro_converter.row_data_to_avro_key(row_data).await,
+        }
+    }
+
+    /// The source info in json messages, e.g. the binlog position, comes from row_data.position
+    pub async fn encode_row(&mut self, row_data: &RowData) -> anyhow::Result<Vec<u8>> {
+        match self.message_format {
+            MessageFormat::Avro => {
+                self.avro_converter
+                    .row_data_to_avro_value(row_data.clone())
+                    .await
+            }
+            MessageFormat::DebeziumJson => {
+                self.debezium_converter
+                    .row_data_to_debezium_value(row_data, &row_data.position)
+                    .await
+            }
+            MessageFormat::CanalJson => {
+                self.canal_converter
+                    .row_data_to_canal_value(row_data, &row_data.position)
+                    .await
+            }
+            MessageFormat::MaxwellJson => {
+                self.maxwell_converter
+                    .row_data_to_maxwell_value(row_data, &row_data.position)
+                    .await
+            }
+            MessageFormat::ConfluentAvro => bail! {Error::SinkerError(
+                "message_format: confluent_avro is only supported by kafka sinker".into()
+            )},
+            MessageFormat::Json => bail! {Error::SinkerError(
+                "message_format: json is only supported for redis sources".into()
+            )},
+        }
+    }
+
+    /// Ddls are not sent in confluent_avro format, since the schemas of tables changed by ddls
+    /// are registered in the schema registry by the following rows
+    pub async fn encode_ddl(&mut self, ddl_data: DdlData) -> anyhow::Result<Option<Vec<u8>>> {
+        let payload = match self.message_format {
+            MessageFormat::Avro => self.avro_converter.ddl_data_to_avro_value(ddl_data).await?,
+            MessageFormat::DebeziumJson => self
+                .debezium_converter
+                .ddl_data_to_debezium_value(&ddl_data, &Position::None),
+            MessageFormat::CanalJson => self
+                .canal_converter
+                .ddl_data_to_canal_value(&ddl_data, &Position::None),
+            MessageFormat::MaxwellJson => self
+                .maxwell_converter
+                .ddl_data_to_maxwell_value(&ddl_data, &Position::None),
+            MessageFormat::ConfluentAvro => return Ok(None),
+            MessageFormat::Json => bail! {Error::SinkerError(
+                "message_format: json is only supported for redis sources".into()
+            )},
+        };
+        Ok(Some(payload))
+    }
+
+    /// Redis entries should be rewritten as cmds first, e.g. by EntryRewriter::rewrite_as_cmd_entries
+    pub fn encode_redis_entry(
+        &self,
+        entry: &RedisEntry,
+        position: &str,
+    ) -> anyhow::Result<Vec<u8>> {
+        match self.message_format {
+            MessageFormat::Avro => self
+                .avro_converter
+                .redis_entry_to_avro_value(entry, position),
+            MessageFormat::Json => Ok(RedisJsonConverter::redis_entry_to_json_value(
+                entry, position,
+            )),
+            _ => bail! {Error::SinkerError(format!(
+                "message_format: {} is not supported for redis sources, use avro or json",
+                self.message_format
+            ))},
+        }
+    }
+
+    /// An update changing the key (or any col deciding the route, e.g. the partition col of kafka)
+    /// is sent as a delete of the old key and an insert of the new key,
+    /// so all messages of a key are routed by the same key
+    pub async fn split_key_changed_updates(
+        &mut self,
+        data: Vec<RowData>,
+        is_route_changed: impl Fn(&RowData) -> bool,
+    ) -> anyhow::Result<Vec<RowData>> {
+        let mut results = Vec::with_capacity(data.len());
+        for row_data in data {
+            if !self.avro_converter.is_key_changed(&row_data).await? && !is_route_changed(&row_data)
+            {
+                results.push(row_data);
+                continue;
+            }
+
+            let (delete, insert) = row_data.split_update_row_data();
+            results.push(delete);
+            results.push(insert);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{
+        col_value::ColValue, ddl_meta::ddl_type::DdlType, redis::redis_object::RedisCmd,
+        row_type::RowType,
+    };
+    use serde_json::Value;
+
+    use super::*;
+
+    fn build_row_data(row_type: RowType, position: Position) -> RowData {
+        let mut after = HashMap::new();
+        after.insert("id".to_string(), ColValue::LongLong(1));
+        let mut row_data = RowData::new("db1".into(), "tb1".into(), row_type, None, Some(after));
+        row_data.position = position;
+        row_data
+    }
+
+    #[tokio::test]
+    async fn test_encode_row_with_position() {
+        let mut encoder =
+            MessageEncoder::new(None, DbType::Mysql, false, MessageFormat::DebeziumJson);
+
+        let snapshot_position = Position::RdbSnapshot {
+            db_type: "mysql".into(),
+            schema: "db1".into(),
+            tb: "tb1".into(),
+            order_col: "id".into(),
+            value: "1".into(),
+        };
+        let payload = encoder
+            .encode_row(&build_row_data(RowType::Insert, snapshot_position))
+            .await
+            .unwrap();
+        let value: Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(value["op"], "r");
+        assert_eq!(value["source"]["snapshot"], "true");
+
+        let cdc_position = Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: "mysql-bin.000001".into(),
+            next_event_position: 1234,
+            gtid_set: String::new(),
+            timestamp: Position::format_timestamp_millis(1700000000123),
+        };
+        let payload = encoder
+            .encode_row(&build_row_data(RowType::Insert, cdc_position.clone()))
+            .await
+            .unwrap();
+        let value: Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(value["op"], "c");
+        assert_eq!(value["source"]["file"], "mysql-bin.000001");
+        assert_eq!(value["source"]["pos"], 1234);
+        assert_eq!(value["source"]["ts_ms"], 1700000000123_i64);
+        assert_eq!(value["source"]["snapshot"], "false");
+
+        // the position is kept when an update changing the key is split
+        let mut before = HashMap::new();
+        before.insert("id".to_string(), ColValue::LongLong(0));
+        let mut update = build_row_data(RowType::Update, cdc_position.clone());
+        update.before = Some(before);
+        let split = encoder
+            .split_key_changed_updates(vec![update], |_| true)
+            .await
+            .unwrap();
+        assert_eq!(split.len(), 2);
+        assert!(split.iter().all(|i| i.position == cdc_position));
+    }
+
+    #[tokio::test]
+    async fn test_encode_ddl() {
+        let ddl_data = DdlData {
+            default_schema: "db1".into(),
+            query: "ALTER TABLE tb1 ADD COLUMN c1 INT".into(),
+            ddl_type: DdlType::AlterTable,
+            db_type: DbType::Mysql,
+            ..Default::default()
+        };
+
+        for message_format in [
+            MessageFormat::Avro,
+            MessageFormat::DebeziumJson,
+            MessageFormat::CanalJson,
+            MessageFormat::MaxwellJson,
+        ] {
+            let mut encoder =
+                MessageEncoder::new(None, DbType::Mysql, true, message_format.clone());
+            let payload = encoder.encode_ddl(ddl_data.clone()).await.unwrap().unwrap();
+            if message_format == MessageFormat::Avro {
+                continue;
+            }
+
+            let value: Value = serde_json::from_slice(&payload).unwrap();
+            match message_format {
+                MessageFormat::DebeziumJson => {
+                    assert_eq!(value["payload"]["ddl"], ddl_data.query);
+                    // schema change events are skipped by consumers of rows
+                    assert!(encoder
+                        .debezium_converter
+                        .debezium_value_to_dt_data(&payload)
+                        .unwrap()
+                        .is_empty());
+                }
+                MessageFormat::CanalJson => {
+                    assert_eq!(value["isDdl"], true);
+                    assert_eq!(value["type"], "ALTER");
+                    assert_eq!(value["sql"], ddl_data.query);
+                }
+                _ => {
+                    assert_eq!(value["type"], "table-alter");
+                    assert_eq!(value["sql"], ddl_data.query);
+                }
+            }
+        }
+
+        let mut encoder =
+            MessageEncoder::new(None, DbType::Mysql, true, MessageFormat::ConfluentAvro);
+        assert!(encoder.encode_ddl(ddl_data).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_encode_redis_entry() {
+        let mut entry = RedisEntry::new();
+        entry.cmd = RedisCmd::from_str_args(&["SET", "k1", "v1"]);
+        entry.cmd.keys = vec!["k1".into()];
+
+        let encoder = MessageEncoder::new(None, DbType::Redis, true, MessageFormat::Json);
+        let payload = encoder.encode_redis_entry(&entry, "").unwrap();
+        let value: Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(value["cmd"], "SET");
+
+        let encoder = MessageEncoder::new(None, DbType::Redis, true, MessageFormat::Avro);
+        assert!(encoder.encode_redis_entry(&entry, "").is_ok());
+
+        // json formats of rows are not for redis cmds
+        let encoder = MessageEncoder::new(None, DbType::Redis, true, MessageFormat::DebeziumJson);
+        assert!(encoder.encode_redis_entry(&entry, "").is_err());
+    }
+}
@@ -5,9 +5,12 @@ pub mod clickhouse;
 pub mod dummy_sinker;
 pub mod foxlake;
 pub mod kafka;
+pub mod message_encoder;
 pub mod mongo;
 pub mod mysql;
 pub mod pg;
+pub mod rabbitmq;
 pub mod redis;
 pub mod sql_sinker;
+pub mod sqs;
 pub mod starrocks;
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use mongodb::bson::{Bson, Document};
+
+use crate::check_log::check_log::DiffColValue;
+
+/// Field level diff of mongo documents, diffs are keyed by dotted paths, e.g. "a.b.0.c".
+/// - key order of (embedded) documents is ignored
+/// - numbers of different types are compared by value, e.g. Int32(1) == Int64(1) == Double(1.0)
+pub struct DocDiffer {}
+
+impl DocDiffer {
+    pub fn diff(src: &Document, dst: &Document) -> HashMap<String, DiffColValue> {
+        let mut diffs = HashMap::new();
+        Self::diff_doc("", src, dst, &mut diffs);
+        diffs
+    }
+
+    fn diff_doc(
+        path: &str,
+        src: &Document,
+        dst: &Document,
+        diffs: &mut HashMap<String, DiffColValue>,
+    ) {
+        for (key, src_value) in src.iter() {
+            let sub_path = Self::sub_path(path, key);
+            Self::diff_bson(&sub_path, Some(src_value), dst.get(key), diffs);
+        }
+        for (key, dst_value) in dst.iter() {
+            if !src.contains_key(key) {
+                let sub_path = Self::sub_path(path, key);
+                Self::diff_bson(&sub_path, None, Some(dst_value), diffs);
+            }
+        }
+    }
+
+    fn diff_bson(
+        path: &str,
+        src: Option<&Bson>,
+        dst: Option<&Bson>,
+        diffs: &mut HashMap<String, DiffColValue>,
+    ) {
+        match (src, dst) {
+            (Some(Bson::Document(src)), Some(Bson::Document(dst))) => {
+                Self::diff_doc(path, src, dst, diffs)
+            }
+
+            (Some(Bson::Array(src)), Some(Bson::Array(dst))) => {
+                for i in 0..src.len().max(dst.len()) {
+                    let sub_path = Self::sub_path(path, &i.to_string());
+                    Self::diff_bson(&sub_path, src.get(i), dst.get(i), diffs);
+                }
+            }
+
+            (Some(src), Some(dst)) if Self::bson_eq(src, dst) => {}
+
+            _ => {
+                let diff_col_value = DiffColValue {
+                    src: src.map(Self::bson_to_string),
+                    dst: dst.map(Self::bson_to_string),
+                };
+                diffs.insert(path.to_string(), diff_col_value);
+            }
+        }
+    }
+
+    fn bson_eq(src: &Bson, dst: &Bson) -> bool {
+        if src == dst {
+            return true;
+        }
+
+        if let (Some(src), Some(dst)) = (Self::to_i64(src), Self::to_i64(dst)) {
+            return src == dst;
+        }
+
+        // Int64 beyond f64 precision can not be compared with Double / Decimal128 by f64
+        if Self::is_big_int64(src) || Self::is_big_int64(dst) {
+            return false;
+        }
+
+        match (Self::to_f64(src), Self::to_f64(dst)) {
+            (Some(src), Some(dst)) => src == dst,
+            _ => false,
+        }
+    }
+
+    fn is_big_int64(value: &Bson) -> bool {
+        matches!(value, Bson::Int64(v) if v.unsigned_abs() > (1u64 << 53))
+    }
+
+    fn to_i64(value: &Bson) -> Option<i64> {
+        match value {
+            Bson::Int32(v) => Some(*v as i64),
+            Bson::Int64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn to_f64(value: &Bson) -> Option<f64> {
+        match value {
+            Bson::Int32(v) => Some(*v as f64),
+            Bson::Int64(v) => Some(*v as f64),
+            Bson::Double(v) => Some(*v),
+            Bson::Decimal128(v) => v.to_string().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn bson_to_string(value: &Bson) -> String {
+        match value {
+            Bson::String(v) => v.clone(),
+            _ => value.clone().into_relaxed_extjson().to_string(),
+        }
+    }
+
+    fn sub_path(path: &str, key: &str) -> String {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let src = doc! {
+            "_id": 1,
+            "name": "a",
+            "age": 1,
+            "score": 1.5,
+            "info": {"city": "x", "tags": ["t1", "t2"]},
+        };
+        // same values with different key order and number types
+        let dst = doc! {
+            "info": {"tags": ["t1", "t2"], "city": "x"},
+            "score": 1.5,
+            "age": 1_i64,
+            "name": "a",
+            "_id": 1.0,
+        };
+        assert!(DocDiffer::diff(&src, &dst).is_empty());
+
+        let dst = doc! {
+            "_id": 1,
+            "name": "b",
+            "age": 1,
+            "info": {"city": "x", "tags": ["t1"], "zip": 100},
+            "extra": true,
+        };
+        let diffs = DocDiffer::diff(&src, &dst);
+        let mut paths: Vec<&String> = diffs.keys().collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec!["extra", "info.tags.1", "info.zip", "name", "score"]
+        );
+
+        let name = &diffs["name"];
+        assert_eq!(name.src.as_deref(), Some("a"));
+        assert_eq!(name.dst.as_deref(), Some("b"));
+
+        let score = &diffs["score"];
+        assert_eq!(score.src.as_deref(), Some("1.5"));
+        assert_eq!(score.dst, None);
+
+        let zip = &diffs["info.zip"];
+        assert_eq!(zip.src, None);
+        assert_eq!(zip.dst.as_deref(), Some("100"));
+    }
+
+    #[test]
+    fn test_bson_eq() {
+        assert!(DocDiffer::bson_eq(&Bson::Int32(1), &Bson::Double(1.0)));
+        assert!(!DocDiffer::bson_eq(&Bson::Int32(1), &Bson::Double(1.1)));
+        assert!(!DocDiffer::bson_eq(
+            &Bson::Int32(1),
+            &Bson::String("1".into())
+        ));
+        assert!(!DocDiffer::bson_eq(
+            &Bson::Int64(9007199254740993),
+            &Bson::Int64(9007199254740992)
+        ));
+    }
+}
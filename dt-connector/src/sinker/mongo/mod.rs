@@ -1,2 +1,4 @@
+pub mod doc_differ;
 pub mod mongo_checker;
 pub mod mongo_sinker;
+pub mod rdb_doc_converter;
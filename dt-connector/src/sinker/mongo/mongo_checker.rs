@@ -1,16 +1,17 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
     time::Instant,
 };
 
 use async_trait::async_trait;
 use mongodb::{
-    bson::{doc, Document},
-    Client,
+    bson::{doc, Bson, Document},
+    options::FindOptions,
+    Client, Collection,
 };
 
-use dt_common::{log_error, monitor::monitor::Monitor};
+use dt_common::{log_error, log_info, monitor::monitor::Monitor, rdb_filter::RdbFilter};
 
 use dt_common::meta::{
     col_value::ColValue,
@@ -22,17 +23,27 @@ use dt_common::meta::{
 
 use crate::{
     call_batch_fn,
+    check_log::check_log::DiffColValue,
     rdb_router::RdbRouter,
     sinker::{base_checker::BaseChecker, base_sinker::BaseSinker},
     Sinker,
 };
 
+use super::doc_differ::DocDiffer;
+
+const SYSTEM_DBS: [&str; 3] = ["admin", "config", "local"];
+
 #[derive(Clone)]
 pub struct MongoChecker {
     pub reverse_router: RdbRouter,
     pub batch_size: usize,
     pub mongo_client: Client,
     pub monitor: Arc<Mutex<Monitor>>,
+    // if set (only for the first of parallel checkers), target collections whose source
+    // collections are included by filter will be scanned reversely when closing to find
+    // documents existing only in target
+    pub src_mongo_client: Option<Client>,
+    pub filter: RdbFilter,
 }
 
 #[async_trait]
@@ -47,6 +58,12 @@ impl Sinker for MongoChecker {
     }
 
     async fn close(&mut self) -> anyhow::Result<()> {
+        if let Some(src_mongo_client) = self.src_mongo_client.take() {
+            for (schema, tb) in self.list_extra_check_tbs().await? {
+                self.check_extra(&src_mongo_client, &schema, &tb).await?;
+            }
+            src_mongo_client.shutdown().await;
+        }
         self.mongo_client.clone().shutdown().await;
         Ok(())
     }
@@ -71,10 +88,24 @@ impl MongoChecker {
 
         let mut ids = Vec::new();
         let mut src_row_data_map = HashMap::new();
+        let mut extra_keys = HashSet::new();
 
         for row_data in data.iter().skip(start_index).take(batch_size) {
-            let after = row_data.after.as_ref().unwrap();
-            if let Some(ColValue::MongoDoc(doc)) = after.get(MongoConstants::DOC) {
+            // delete rows come from revising extra logs, the doc is expected to be missing in dst
+            let (is_delete, col_values) = if row_data.row_type == RowType::Delete {
+                (true, row_data.before.as_ref().unwrap())
+            } else {
+                (false, row_data.after.as_ref().unwrap())
+            };
+            if let Some(ColValue::MongoDoc(doc)) = col_values.get(MongoConstants::DOC) {
+                if is_delete {
+                    if let Some(key) = MongoKey::from_doc(doc) {
+                        extra_keys.insert(key);
+                    }
+                    ids.push(doc.get(MongoConstants::ID).unwrap());
+                    continue;
+                }
+
                 let id = doc.get(MongoConstants::ID).unwrap();
                 if let Some(key) = MongoKey::from_doc(doc) {
                     src_row_data_map.insert(key, row_data.clone());
@@ -106,9 +137,20 @@ impl MongoChecker {
         // batch check
         let mut miss = Vec::new();
         let mut diff = Vec::new();
+        let mut extra = Vec::new();
+        for key in extra_keys {
+            if let Some(dst_row_data) = dst_row_data_map.remove(&key) {
+                extra.push(BaseChecker::build_mongo_extra_log(
+                    dst_row_data,
+                    &tb_meta,
+                    &self.reverse_router,
+                ));
+            }
+        }
+
         for (key, src_row_data) in src_row_data_map {
             if let Some(dst_row_data) = dst_row_data_map.remove(&key) {
-                let diff_col_values = BaseChecker::compare_row_data(&src_row_data, &dst_row_data);
+                let diff_col_values = Self::compare_docs(&src_row_data, &dst_row_data);
                 if !diff_col_values.is_empty() {
                     let diff_log = BaseChecker::build_mongo_diff_log(
                         src_row_data,
@@ -125,10 +167,142 @@ impl MongoChecker {
             };
         }
         BaseChecker::log_dml(miss, diff);
+        BaseChecker::log_extra(extra);
 
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, 0, start_time)
     }
 
+    /// Collections in target mapped back from source collections included by filter,
+    /// collections empty in source are listed too, since they have no docs to be checked
+    async fn list_extra_check_tbs(&mut self) -> anyhow::Result<Vec<(String, String)>> {
+        let mut tbs = Vec::new();
+        for schema in self.mongo_client.list_database_names(None, None).await? {
+            if SYSTEM_DBS.contains(&schema.as_str()) {
+                continue;
+            }
+            let collections = self
+                .mongo_client
+                .database(&schema)
+                .list_collection_names(None)
+                .await?;
+            for tb in collections {
+                if tb.starts_with("system.") {
+                    continue;
+                }
+                let (src_schema, src_tb) = self.reverse_router.get_tb_map(&schema, &tb);
+                if !self.filter.filter_tb(src_schema, src_tb) {
+                    tbs.push((schema.clone(), tb));
+                }
+            }
+        }
+        Ok(tbs)
+    }
+
+    async fn check_extra(
+        &self,
+        src_mongo_client: &Client,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<()> {
+        let (src_schema, src_tb) = self.reverse_router.get_tb_map(schema, tb);
+        log_info!(
+            "start checking extra docs for dst: {}.{}, src: {}.{}",
+            schema,
+            tb,
+            src_schema,
+            src_tb
+        );
+
+        let tb_meta = Self::mock_tb_meta(schema, tb);
+        let dst_collection = self
+            .mongo_client
+            .database(schema)
+            .collection::<Document>(tb);
+        let src_collection = src_mongo_client
+            .database(src_schema)
+            .collection::<Document>(src_tb);
+
+        let find_options = FindOptions::builder()
+            .projection(doc! {MongoConstants::ID: 1})
+            .batch_size(self.batch_size as u32)
+            .build();
+        let mut cursor = dst_collection.find(None, find_options).await?;
+
+        let mut dst_ids = Vec::new();
+        while cursor.advance().await? {
+            let doc = cursor.deserialize_current()?;
+            if let Some(id) = doc.get(MongoConstants::ID) {
+                dst_ids.push(id.clone());
+            }
+            if dst_ids.len() >= self.batch_size {
+                let ids = std::mem::take(&mut dst_ids);
+                self.check_extra_ids(&src_collection, schema, tb, &tb_meta, ids)
+                    .await?;
+            }
+        }
+        if !dst_ids.is_empty() {
+            self.check_extra_ids(&src_collection, schema, tb, &tb_meta, dst_ids)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn check_extra_ids(
+        &self,
+        src_collection: &Collection<Document>,
+        schema: &str,
+        tb: &str,
+        tb_meta: &RdbTbMeta,
+        dst_ids: Vec<Bson>,
+    ) -> anyhow::Result<()> {
+        let filter = doc! {
+            MongoConstants::ID: {
+                "$in": dst_ids.clone()
+            }
+        };
+        let find_options = FindOptions::builder()
+            .projection(doc! {MongoConstants::ID: 1})
+            .build();
+
+        let mut src_keys = HashSet::new();
+        let mut cursor = src_collection.find(filter, find_options).await?;
+        while cursor.advance().await? {
+            let doc = cursor.deserialize_current()?;
+            if let Some(key) = MongoKey::from_doc(&doc) {
+                src_keys.insert(key);
+            }
+        }
+
+        let mut extra = Vec::new();
+        for id in dst_ids {
+            let doc = doc! {MongoConstants::ID: id};
+            if let Some(key) = MongoKey::from_doc(&doc) {
+                if !src_keys.contains(&key) {
+                    let dst_row_data = Self::build_row_data(schema, tb, doc, &key);
+                    extra.push(BaseChecker::build_mongo_extra_log(
+                        dst_row_data,
+                        tb_meta,
+                        &self.reverse_router,
+                    ));
+                }
+            }
+        }
+        BaseChecker::log_extra(extra);
+        Ok(())
+    }
+
+    fn compare_docs(
+        src_row_data: &RowData,
+        dst_row_data: &RowData,
+    ) -> HashMap<String, DiffColValue> {
+        let get_doc =
+            |row_data: &RowData| match row_data.after.as_ref().unwrap().get(MongoConstants::DOC) {
+                Some(ColValue::MongoDoc(doc)) => doc.clone(),
+                _ => Document::new(),
+            };
+        DocDiffer::diff(&get_doc(src_row_data), &get_doc(dst_row_data))
+    }
+
     fn mock_tb_meta(schema: &str, tb: &str) -> RdbTbMeta {
         RdbTbMeta {
             schema: schema.into(),
@@ -1,23 +1,28 @@
 use std::{
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, RwLock},
     time::Instant,
 };
 
 use async_trait::async_trait;
 use mongodb::{
     bson::{doc, Document},
-    options::UpdateOptions,
-    Client, Collection,
+    options::{ReplaceOptions, UpdateOptions},
+    Client, ClientSession, Collection,
 };
 
 use dt_common::{log_error, monitor::monitor::Monitor};
 
 use dt_common::meta::{
-    col_value::ColValue, mongo::mongo_constant::MongoConstants, row_data::RowData,
-    row_type::RowType,
+    col_value::ColValue, ddl_meta::ddl_data::DdlData, mongo::mongo_constant::MongoConstants,
+    row_data::RowData, row_type::RowType,
 };
 
-use crate::{call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{
+    call_batch_fn, data_marker::DataMarker, rdb_router::RdbRouter,
+    sinker::base_sinker::BaseSinker, Sinker,
+};
+
+use super::rdb_doc_converter::RdbDocConverter;
 
 #[derive(Clone)]
 pub struct MongoSinker {
@@ -25,15 +30,36 @@ pub struct MongoSinker {
     pub batch_size: usize,
     pub mongo_client: Client,
     pub monitor: Arc<Mutex<Monitor>>,
+    // only for mysql / pg extractors
+    pub rdb_doc_converter: Option<RdbDocConverter>,
+    pub data_marker: Option<Arc<RwLock<DataMarker>>>,
 }
 
 #[async_trait]
 impl Sinker for MongoSinker {
-    async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, mut batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
 
+        if let Some(converter) = self.rdb_doc_converter.as_mut() {
+            let count = data.len();
+            let mut docs = Vec::with_capacity(count);
+            for row_data in data {
+                docs.extend(converter.convert(row_data).await?);
+            }
+            // rows of nested tables become updates of parent documents, and primary key
+            // changes become delete + insert, batch only if all rows kept the same shape
+            batch = batch
+                && docs.len() == count
+                && docs.iter().all(|i| {
+                    i.row_type == docs[0].row_type
+                        && i.schema == docs[0].schema
+                        && i.tb == docs[0].tb
+                });
+            data = docs;
+        }
+
         if !batch {
             self.serial_sink(data).await?;
         } else {
@@ -50,7 +76,19 @@ impl Sinker for MongoSinker {
         Ok(())
     }
 
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        if let Some(converter) = self.rdb_doc_converter.as_mut() {
+            converter.refresh_meta(&data);
+        }
+        Ok(())
+    }
+
     async fn close(&mut self) -> anyhow::Result<()> {
+        // the meta manager is shared by parallel sinkers, closing its conn pool again is a no-op,
+        // the same as the extractor meta managers of checkers
+        if let Some(converter) = &self.rdb_doc_converter {
+            converter.meta_manager.close().await?;
+        }
         self.mongo_client.clone().shutdown().await;
         Ok(())
     }
@@ -61,6 +99,7 @@ impl MongoSinker {
         let start_time = Instant::now();
         let mut data_size = 0;
 
+        let mut session = self.start_transaction().await?;
         for row_data in data.iter_mut() {
             data_size += row_data.data_size;
 
@@ -76,7 +115,7 @@ impl MongoSinker {
                         let query_doc =
                             doc! {MongoConstants::ID: doc.get(MongoConstants::ID).unwrap()};
                         let update_doc = doc! {MongoConstants::SET: doc};
-                        self.upsert(&collection, query_doc, update_doc).await?;
+                        Self::upsert(&collection, query_doc, update_doc, &mut session).await?;
                     }
                 }
 
@@ -85,7 +124,14 @@ impl MongoSinker {
                     if let Some(ColValue::MongoDoc(doc)) = before.remove(MongoConstants::DOC) {
                         let query_doc =
                             doc! {MongoConstants::ID: doc.get(MongoConstants::ID).unwrap()};
-                        collection.delete_one(query_doc, None).await?;
+                        match session.as_mut() {
+                            Some(session) => {
+                                collection
+                                    .delete_one_with_session(query_doc, None, session)
+                                    .await?
+                            }
+                            None => collection.delete_one(query_doc, None).await?,
+                        };
                     }
                 }
 
@@ -100,25 +146,56 @@ impl MongoSinker {
                             None
                         };
 
-                    let update_doc =
+                    if let Some(query_doc) = query_doc {
                         if let Some(ColValue::MongoDoc(doc)) = after.remove(MongoConstants::DOC) {
-                            Some(doc)
-                        } else if let Some(ColValue::MongoDoc(doc)) =
+                            // full document (post-image) from change stream
+                            let options = ReplaceOptions::builder().upsert(true).build();
+                            match session.as_mut() {
+                                Some(session) => {
+                                    collection
+                                        .replace_one_with_session(
+                                            query_doc,
+                                            doc,
+                                            Some(options),
+                                            session,
+                                        )
+                                        .await?
+                                }
+                                None => {
+                                    collection
+                                        .replace_one(query_doc, doc, Some(options))
+                                        .await?
+                                }
+                            };
+                        } else if let Some(ColValue::MongoDoc(update_doc)) =
                             after.remove(MongoConstants::DIFF_DOC)
                         {
-                            // for Update row_data from oplog (NOT change stream), after contains diff_doc instead of doc
-                            Some(doc)
-                        } else {
-                            None
-                        };
-
-                    if query_doc.is_some() && update_doc.is_some() {
-                        self.upsert(&collection, query_doc.unwrap(), update_doc.unwrap())
-                            .await?;
+                            // for Update row_data from oplog, or from change stream without post-image,
+                            // after contains diff_doc instead of doc
+                            if update_doc.contains_key(MongoConstants::PULL) {
+                                // $pull from a missing document should not create it
+                                match session.as_mut() {
+                                    Some(session) => {
+                                        collection
+                                            .update_one_with_session(
+                                                query_doc, update_doc, None, session,
+                                            )
+                                            .await?
+                                    }
+                                    None => {
+                                        collection.update_one(query_doc, update_doc, None).await?
+                                    }
+                                };
+                            } else {
+                                Self::upsert(&collection, query_doc, update_doc, &mut session)
+                                    .await?;
+                            }
+                        }
                     }
                 }
             }
         }
+        Self::commit_transaction(session).await?;
 
         BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), data_size, start_time)
     }
@@ -152,7 +229,17 @@ impl MongoSinker {
                 "$in": ids
             }
         };
-        collection.delete_many(query, None).await?;
+        match self.start_transaction().await? {
+            Some(mut session) => {
+                collection
+                    .delete_many_with_session(query, None, &mut session)
+                    .await?;
+                Self::commit_transaction(Some(session)).await?;
+            }
+            None => {
+                collection.delete_many(query, None).await?;
+            }
+        }
 
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
     }
@@ -180,7 +267,25 @@ impl MongoSinker {
             }
         }
 
-        if let Err(error) = collection.insert_many(docs, None).await {
+        let result = match self.start_transaction().await? {
+            Some(mut session) => {
+                match collection
+                    .insert_many_with_session(docs, None, &mut session)
+                    .await
+                {
+                    Ok(_) => Self::commit_transaction(Some(session)).await,
+                    // the transaction is aborted when session dropped
+                    Err(error) => Err(error.into()),
+                }
+            }
+            None => collection
+                .insert_many(docs, None)
+                .await
+                .map(|_| ())
+                .map_err(|error| error.into()),
+        };
+
+        if let Err(error) = result {
             log_error!(
                 "batch insert failed, will insert one by one, schema: {}, tb: {}, error: {}",
                 db,
@@ -195,15 +300,61 @@ impl MongoSinker {
     }
 
     async fn upsert(
-        &mut self,
         collection: &Collection<Document>,
         query_doc: Document,
         update_doc: Document,
+        session: &mut Option<ClientSession>,
     ) -> anyhow::Result<()> {
         let options = UpdateOptions::builder().upsert(true).build();
-        collection
-            .update_one(query_doc, update_doc, Some(options))
-            .await?;
+        match session.as_mut() {
+            Some(session) => {
+                collection
+                    .update_one_with_session(query_doc, update_doc, Some(options), session)
+                    .await?
+            }
+            None => {
+                collection
+                    .update_one(query_doc, update_doc, Some(options))
+                    .await?
+            }
+        };
+        Ok(())
+    }
+
+    /// If data_marker is set, start a transaction and write the marker doc first,
+    /// so the extractor of the reverse task can recognize data synced by this task.
+    async fn start_transaction(&self) -> anyhow::Result<Option<ClientSession>> {
+        let (marker_schema, marker_tb, marker_id) = match &self.data_marker {
+            Some(data_marker) => {
+                let data_marker = data_marker.read().unwrap();
+                (
+                    data_marker.marker_schema.clone(),
+                    data_marker.marker_tb.clone(),
+                    data_marker.get_mongo_marker_id(),
+                )
+            }
+            None => return Ok(None),
+        };
+
+        let mut session = self.mongo_client.start_session(None).await?;
+        session.start_transaction(None).await?;
+
+        let collection = self
+            .mongo_client
+            .database(&marker_schema)
+            .collection::<Document>(&marker_tb);
+        let query_doc = doc! {MongoConstants::ID: marker_id};
+        // $inc makes sure every transaction generates an oplog entry for the marker doc
+        let update_doc = doc! {"$inc": {"n": 1}};
+        let mut session = Some(session);
+        Self::upsert(&collection, query_doc, update_doc, &mut session).await?;
+        Ok(session)
+    }
+
+    async fn commit_transaction(session: Option<ClientSession>) -> anyhow::Result<()> {
+        if let Some(mut session) = session {
+            session.commit_transaction().await?;
+        }
         Ok(())
     }
 }
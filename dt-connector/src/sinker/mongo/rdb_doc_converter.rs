@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use dt_common::{
+    error::Error,
+    meta::{
+        adaptor::mongo_col_value_convertor::MongoColValueConvertor,
+        col_value::ColValue,
+        ddl_meta::ddl_data::DdlData,
+        mongo::mongo_constant::MongoConstants,
+        rdb_meta_manager::RdbMetaManager,
+        rdb_tb_meta::RdbTbMeta,
+        row_data::RowData,
+        row_type::RowType,
+    },
+};
+use mongodb::bson::{doc, Bson, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::rdb_router::RdbRouter;
+
+const JSON_PREFIX: &str = "json:";
+
+/// Embeds rows of a child table into an array field of the parent document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NestSpec {
+    pub db: String,
+    pub tb: String,
+    pub parent_tb: String,
+    // HashMap<child_col, parent_col>
+    pub parent_cols: HashMap<String, String>,
+    pub field: String,
+}
+
+/// Converts RowData extracted from mysql / pg into the MongoDoc based RowData accepted by MongoSinker.
+#[derive(Clone)]
+pub struct RdbDocConverter {
+    pub meta_manager: RdbMetaManager,
+    pub router: RdbRouter,
+    pub reverse_router: RdbRouter,
+    // HashMap<(src_db, src_tb), NestSpec>
+    pub nest_specs: HashMap<(String, String), NestSpec>,
+}
+
+impl RdbDocConverter {
+    pub fn new(
+        meta_manager: RdbMetaManager,
+        router: RdbRouter,
+        nest_map: &str,
+    ) -> anyhow::Result<Self> {
+        let reverse_router = router.reverse();
+        Ok(Self {
+            meta_manager,
+            router,
+            reverse_router,
+            nest_specs: Self::parse_nest_map(nest_map)?,
+        })
+    }
+
+    pub fn refresh_meta(&mut self, data: &[DdlData]) {
+        for ddl_data in data.iter() {
+            self.meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+        }
+    }
+
+    pub async fn convert(&mut self, row_data: RowData) -> anyhow::Result<Vec<RowData>> {
+        // row_data has been routed by extractor, get the src schema / tb to fetch meta
+        let (src_schema, src_tb) = self.get_src_schema_tb(&row_data);
+        let tb_meta = self
+            .meta_manager
+            .get_tb_meta(&src_schema, &src_tb)
+            .await?
+            .clone();
+        let id_cols = self.get_dst_cols(&tb_meta, &tb_meta.id_cols);
+        let cols = self.get_dst_cols(&tb_meta, &tb_meta.cols);
+
+        if let Some(spec) = self.nest_specs.get(&(src_schema.clone(), src_tb.clone())) {
+            let spec = spec.clone();
+            return self
+                .convert_nested(row_data, &spec, &src_schema, &tb_meta, &cols, &id_cols)
+                .await;
+        }
+
+        let (schema, tb) = (row_data.schema.clone(), row_data.tb.clone());
+        let data = match row_data.row_type {
+            RowType::Insert => {
+                let after = Self::build_doc(row_data.after.as_ref().unwrap(), &cols, &id_cols)?;
+                vec![Self::build_insert(schema, tb, after)]
+            }
+
+            RowType::Delete => {
+                let id = Self::build_id(row_data.before.as_ref().unwrap(), &id_cols)?;
+                vec![Self::build_delete(schema, tb, id)]
+            }
+
+            RowType::Update => {
+                let before_id = Self::build_id(row_data.before.as_ref().unwrap(), &id_cols)?;
+                let after = Self::build_doc(row_data.after.as_ref().unwrap(), &cols, &id_cols)?;
+                let after_id = after.get(MongoConstants::ID).unwrap().clone();
+                if before_id != after_id {
+                    // _id is immutable in mongo, primary key changes become delete + insert
+                    vec![
+                        Self::build_delete(schema.clone(), tb.clone(), before_id),
+                        Self::build_insert(schema, tb, after),
+                    ]
+                } else {
+                    let update_doc = doc! {MongoConstants::SET: after};
+                    vec![Self::build_update(schema, tb, after_id, update_doc)]
+                }
+            }
+        };
+        Ok(data)
+    }
+
+    async fn convert_nested(
+        &mut self,
+        row_data: RowData,
+        spec: &NestSpec,
+        src_schema: &str,
+        tb_meta: &RdbTbMeta,
+        cols: &[String],
+        id_cols: &[String],
+    ) -> anyhow::Result<Vec<RowData>> {
+        let parent_tb_meta = self
+            .meta_manager
+            .get_tb_meta(src_schema, &spec.parent_tb)
+            .await?
+            .clone();
+        let (parent_schema, parent_tb) = self.router.get_tb_map(src_schema, &spec.parent_tb);
+        let (parent_schema, parent_tb) = (parent_schema.to_string(), parent_tb.to_string());
+
+        // Vec<(child_dst_col, parent_dst_col)> in the order of parent id cols
+        let mut parent_id_cols = Vec::new();
+        for parent_col in parent_tb_meta.id_cols.iter() {
+            let child_col = spec.parent_cols.iter().find(|(_, v)| *v == parent_col);
+            if child_col.is_none() {
+                bail! {Error::ConfigError(format!(
+                    "nest_map of {}.{} does not cover parent id col: {}",
+                    spec.db, spec.tb, parent_col
+                ))}
+            }
+            let child_col = self.get_dst_col(tb_meta, child_col.unwrap().0);
+            let parent_col = self.get_dst_col(&parent_tb_meta, parent_col);
+            parent_id_cols.push((child_col, parent_col));
+        }
+
+        let field = &spec.field;
+        let build_pull = |col_values: &HashMap<String, ColValue>| -> anyhow::Result<Document> {
+            let id = Self::build_id(col_values, id_cols)?;
+            Ok(doc! {MongoConstants::PULL: {field: {MongoConstants::ID: id}}})
+        };
+        let build_push = |col_values: &HashMap<String, ColValue>| -> anyhow::Result<Document> {
+            let child_doc = Self::build_doc(col_values, cols, id_cols)?;
+            Ok(doc! {MongoConstants::PUSH: {field: child_doc}})
+        };
+
+        let mut data = Vec::new();
+        if let Some(before) = &row_data.before {
+            let parent_id = Self::build_parent_id(before, &parent_id_cols)?;
+            data.push(Self::build_update(
+                parent_schema.clone(),
+                parent_tb.clone(),
+                parent_id,
+                build_pull(before)?,
+            ));
+        }
+        if let Some(after) = &row_data.after {
+            let parent_id = Self::build_parent_id(after, &parent_id_cols)?;
+            data.push(Self::build_update(
+                parent_schema,
+                parent_tb,
+                parent_id,
+                build_push(after)?,
+            ));
+        }
+        Ok(data)
+    }
+
+    fn build_doc(
+        col_values: &HashMap<String, ColValue>,
+        cols: &[String],
+        id_cols: &[String],
+    ) -> anyhow::Result<Document> {
+        let mut doc = doc! {MongoConstants::ID: Self::build_id(col_values, id_cols)?};
+        for col in cols.iter() {
+            if let Some(col_value) = col_values.get(col) {
+                doc.insert(col, MongoColValueConvertor::to_bson(col_value));
+            }
+        }
+        Ok(doc)
+    }
+
+    fn build_id(col_values: &HashMap<String, ColValue>, id_cols: &[String]) -> anyhow::Result<Bson> {
+        let id_cols: Vec<(String, String)> =
+            id_cols.iter().map(|i| (i.clone(), i.clone())).collect();
+        Self::build_parent_id(col_values, &id_cols)
+    }
+
+    fn build_parent_id(
+        col_values: &HashMap<String, ColValue>,
+        // Vec<(col in col_values, field in _id)>
+        id_cols: &[(String, String)],
+    ) -> anyhow::Result<Bson> {
+        let mut id = Document::new();
+        for (col, field) in id_cols.iter() {
+            if let Some(col_value) = col_values.get(col) {
+                id.insert(field, MongoColValueConvertor::to_bson(col_value));
+            } else {
+                bail! {Error::SinkerError(format!(
+                    "id col: {} not found when building mongo _id",
+                    col
+                ))}
+            }
+        }
+
+        // single key: _id is the value itself, compound key: _id is a document
+        if id.len() == 1 {
+            return Ok(id.into_iter().next().unwrap().1);
+        }
+        Ok(Bson::Document(id))
+    }
+
+    fn build_insert(schema: String, tb: String, doc: Document) -> RowData {
+        let after = HashMap::from([(MongoConstants::DOC.to_string(), ColValue::MongoDoc(doc))]);
+        RowData::new(schema, tb, RowType::Insert, None, Some(after))
+    }
+
+    fn build_delete(schema: String, tb: String, id: Bson) -> RowData {
+        let before = HashMap::from([(
+            MongoConstants::DOC.to_string(),
+            ColValue::MongoDoc(doc! {MongoConstants::ID: id}),
+        )]);
+        RowData::new(schema, tb, RowType::Delete, Some(before), None)
+    }
+
+    fn build_update(schema: String, tb: String, id: Bson, update_doc: Document) -> RowData {
+        let before = HashMap::from([(
+            MongoConstants::DOC.to_string(),
+            ColValue::MongoDoc(doc! {MongoConstants::ID: id}),
+        )]);
+        let after = HashMap::from([(
+            MongoConstants::DIFF_DOC.to_string(),
+            ColValue::MongoDoc(update_doc),
+        )]);
+        RowData::new(schema, tb, RowType::Update, Some(before), Some(after))
+    }
+
+    fn get_src_schema_tb(&self, row_data: &RowData) -> (String, String) {
+        let (schema, tb) = self
+            .reverse_router
+            .get_tb_map(&row_data.schema, &row_data.tb);
+        (schema.to_string(), tb.to_string())
+    }
+
+    fn get_dst_cols(&self, tb_meta: &RdbTbMeta, src_cols: &[String]) -> Vec<String> {
+        src_cols
+            .iter()
+            .map(|col| self.get_dst_col(tb_meta, col))
+            .collect()
+    }
+
+    fn get_dst_col(&self, tb_meta: &RdbTbMeta, src_col: &str) -> String {
+        if let Some(col_map) = self.router.get_col_map(&tb_meta.schema, &tb_meta.tb) {
+            if let Some(dst_col) = col_map.get(src_col) {
+                return dst_col.clone();
+            }
+        }
+        src_col.to_string()
+    }
+
+    fn parse_nest_map(config_str: &str) -> anyhow::Result<HashMap<(String, String), NestSpec>> {
+        let mut results = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        // nest_map=json:[{"db":"test_db","tb":"order_item","parent_tb":"order","parent_cols":{"order_id":"id"},"field":"items"}]
+        let specs: Vec<NestSpec> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+        for spec in specs {
+            results.insert((spec.db.clone(), spec.tb.clone()), spec);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nest_map() {
+        let config_str = r#"json:[{"db":"test_db_1","tb":"order_item","parent_tb":"orders","parent_cols":{"order_id":"id"},"field":"items"}]"#;
+        let specs = RdbDocConverter::parse_nest_map(config_str).unwrap();
+        let spec = specs
+            .get(&("test_db_1".to_string(), "order_item".to_string()))
+            .unwrap();
+        assert_eq!(spec.parent_tb, "orders");
+        assert_eq!(spec.parent_cols.get("order_id").unwrap(), "id");
+        assert_eq!(spec.field, "items");
+        assert!(RdbDocConverter::parse_nest_map("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_build_id() {
+        let col_values = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("name".to_string(), ColValue::String("a".into())),
+        ]);
+
+        let id = RdbDocConverter::build_id(&col_values, &["id".to_string()]).unwrap();
+        assert_eq!(id, Bson::Int32(1));
+
+        let id = RdbDocConverter::build_id(&col_values, &["id".to_string(), "name".to_string()])
+            .unwrap();
+        assert_eq!(id, Bson::Document(doc! {"id": 1, "name": "a"}));
+
+        assert!(RdbDocConverter::build_id(&col_values, &["f_1".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_build_doc() {
+        let col_values = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("price".to_string(), ColValue::Decimal("1.50".into())),
+            ("tags".to_string(), ColValue::Json2(r#"["a","b"]"#.into())),
+            ("note".to_string(), ColValue::None),
+        ]);
+        let cols = vec![
+            "id".to_string(),
+            "price".to_string(),
+            "tags".to_string(),
+            "note".to_string(),
+        ];
+        let doc = RdbDocConverter::build_doc(&col_values, &cols, &["id".to_string()]).unwrap();
+        assert_eq!(doc.get(MongoConstants::ID).unwrap(), &Bson::Int32(1));
+        assert_eq!(doc.get("id").unwrap(), &Bson::Int32(1));
+        assert!(matches!(doc.get("price").unwrap(), Bson::Decimal128(_)));
+        assert_eq!(
+            doc.get("tags").unwrap(),
+            &Bson::Array(vec![Bson::String("a".into()), Bson::String("b".into())])
+        );
+        assert_eq!(doc.get("note").unwrap(), &Bson::Null);
+    }
+}
@@ -0,0 +1 @@
+pub mod rabbitmq_sinker;
@@ -0,0 +1,183 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    config::config_enums::MessageFormat,
+    error::Error,
+    meta::{ddl_meta::ddl_data::DdlData, position::Position, row_data::RowData},
+    monitor::monitor::Monitor,
+};
+use lapin::{
+    options::{BasicPublishOptions, ConfirmSelectOptions},
+    publisher_confirm::{Confirmation, PublisherConfirm},
+    BasicProperties, Channel, Connection, ConnectionProperties,
+};
+
+use crate::{
+    call_batch_fn,
+    sinker::{base_sinker::BaseSinker, message_encoder::MessageEncoder},
+    Sinker,
+};
+
+const PERSISTENT_DELIVERY_MODE: u8 = 2;
+const DDL_OPERATION: &str = "ddl";
+const MAX_PENDING_CONFIRMS: usize = 10000;
+
+/// Messages are published as mandatory with publisher confirms, the confirms are waited on
+/// checkpoints (or once MAX_PENDING_CONFIRMS are pending), so a position is recorded only after
+/// all messages before it are confirmed by the broker and routed to at least one queue
+pub struct RabbitMqSinker {
+    pub batch_size: usize,
+    pub conn: Connection,
+    pub channel: Channel,
+    pub exchange: String,
+    pub routing_key: String,
+    pub encoder: MessageEncoder,
+    pub pending_confirms: Vec<PublisherConfirm>,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+#[async_trait]
+impl Sinker for RabbitMqSinker {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        call_batch_fn!(self, data, Self::publish_dml);
+        Ok(())
+    }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        for ddl_data in data {
+            let routing_key = self.get_routing_key(&ddl_data.default_schema, "", DDL_OPERATION);
+            if let Some(payload) = self.encoder.encode_ddl(ddl_data).await? {
+                self.publish(&routing_key, &payload).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        self.encoder.refresh_meta(&data);
+        Ok(())
+    }
+
+    async fn commit_position(&mut self, _position: &Position) -> anyhow::Result<()> {
+        self.wait_confirms().await
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.wait_confirms().await?;
+        self.conn.close(200, "OK").await?;
+        Ok(())
+    }
+
+    fn needs_row_position(&self) -> bool {
+        self.encoder.needs_row_position()
+    }
+}
+
+impl RabbitMqSinker {
+    pub async fn create_channel(url: &str) -> anyhow::Result<(Connection, Channel)> {
+        let conn = Connection::connect(url, ConnectionProperties::default()).await?;
+        let channel = conn.create_channel().await?;
+        channel
+            .confirm_select(ConfirmSelectOptions::default())
+            .await?;
+        Ok((conn, channel))
+    }
+
+    async fn publish_dml(
+        &mut self,
+        data: &mut [RowData],
+        sinked_count: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+
+        for row_data in data.iter_mut().skip(sinked_count).take(batch_size) {
+            data_size += row_data.data_size;
+
+            row_data.convert_raw_string();
+            let routing_key = self.get_routing_key(
+                &row_data.schema,
+                &row_data.tb,
+                &row_data.row_type.to_string(),
+            );
+            let payload = self.encoder.encode_row(row_data).await?;
+            self.publish(&routing_key, &payload).await?;
+        }
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    async fn publish(&mut self, routing_key: &str, payload: &[u8]) -> anyhow::Result<()> {
+        let content_type = if self.encoder.message_format == MessageFormat::Avro {
+            "application/octet-stream"
+        } else {
+            "application/json"
+        };
+        let properties = BasicProperties::default()
+            .with_delivery_mode(PERSISTENT_DELIVERY_MODE)
+            .with_content_type(content_type.into());
+
+        let confirm = self
+            .channel
+            .basic_publish(
+                &self.exchange,
+                routing_key,
+                BasicPublishOptions {
+                    mandatory: true,
+                    ..Default::default()
+                },
+                payload,
+                properties,
+            )
+            .await?;
+        self.pending_confirms.push(confirm);
+
+        if self.pending_confirms.len() >= MAX_PENDING_CONFIRMS {
+            self.wait_confirms().await?;
+        }
+        Ok(())
+    }
+
+    async fn wait_confirms(&mut self) -> anyhow::Result<()> {
+        let pending_confirms = std::mem::take(&mut self.pending_confirms);
+        for confirm in pending_confirms {
+            match confirm.await? {
+                Confirmation::Nack(_) => {
+                    bail! {Error::SinkerError(format!(
+                        "message nacked by rabbitmq, exchange: {}",
+                        self.exchange
+                    ))}
+                }
+                // mandatory messages not routed to any queue are returned by basic.return
+                Confirmation::Ack(Some(returned)) => {
+                    bail! {Error::SinkerError(format!(
+                        "message returned by rabbitmq, exchange: {}, routing_key: {}, reply_code: {}, reply_text: {}",
+                        self.exchange,
+                        returned.delivery.routing_key,
+                        returned.reply_code,
+                        returned.reply_text
+                    ))}
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn get_routing_key(&self, schema: &str, tb: &str, operation: &str) -> String {
+        self.routing_key
+            .replace("{schema}", schema)
+            .replace("{tb}", tb)
+            .replace("{operation}", operation)
+    }
+}
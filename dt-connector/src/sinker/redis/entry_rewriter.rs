@@ -3,10 +3,15 @@ use dt_common::error::Error;
 use dt_common::meta::redis::{
     redis_entry::RedisEntry,
     redis_object::{
-        HashObject, ListObject, ModuleObject, RedisCmd, SetObject, StringObject, ZsetObject,
+        HashObject, ListObject, RedisCmd, RedisObject, RedisString, SetObject, StringObject,
+        ZsetObject,
     },
 };
 
+use crate::extractor::redis::rdb::rdb_parser::K_FLAG_FUNCTION2;
+
+use super::module_rewriter::ModuleRewriter;
+
 const CRC64_TABLE: [u64; 256] = [
     0x0000000000000000,
     0x7ad870c830358979,
@@ -266,81 +271,211 @@ const CRC64_TABLE: [u64; 256] = [
     0x29b7d047efec8728,
 ];
 
+// functions were introduced in rdb 10 (redis 7.0)
+const FUNCTION_RDB_VERSION: u16 = 10;
+// max bytes of members in a rewritten command
+const REWRITE_CHUNK_BYTES: usize = 1024 * 1024;
+// big keys sent to consumers are split into cmds of at most 1000 members,
+// rewritten cmds (e.g. hset with multiple fields) require redis 4.0+ as target
+const CONSUMER_REWRITE_CHUNK_SIZE: usize = 1000;
+const CONSUMER_TARGET_VERSION: f32 = 4.0;
+
 pub struct EntryRewriter {}
 
 impl EntryRewriter {
-    pub fn rewrite_hash(obj: &mut HashObject) -> anyhow::Result<Vec<RedisCmd>> {
+    /// Big collections are split into commands of at most chunk_size members,
+    /// and each command carries no more than REWRITE_CHUNK_BYTES bytes unless a single member is bigger
+    pub fn rewrite_hash(
+        obj: &HashObject,
+        chunk_size: usize,
+        version: f32,
+    ) -> anyhow::Result<Vec<RedisCmd>> {
+        // hset with multiple fields is supported since redis 4.0
+        let name = if version >= 4.0 { "hset" } else { "hmset" };
+        let members = obj.value.iter().map(|(k, v)| vec![k, v]);
+        Ok(Self::rewrite_in_chunks(name, &obj.key, members, chunk_size))
+    }
+
+    pub fn rewrite_list(obj: &ListObject, chunk_size: usize) -> anyhow::Result<Vec<RedisCmd>> {
+        let members = obj.elements.iter().map(|ele| vec![ele]);
+        Ok(Self::rewrite_in_chunks(
+            "rpush", &obj.key, members, chunk_size,
+        ))
+    }
+
+    pub fn rewrite_set(obj: &SetObject, chunk_size: usize) -> anyhow::Result<Vec<RedisCmd>> {
+        let members = obj.elements.iter().map(|ele| vec![ele]);
+        Ok(Self::rewrite_in_chunks(
+            "sadd", &obj.key, members, chunk_size,
+        ))
+    }
+
+    pub fn rewrite_string(obj: &StringObject) -> anyhow::Result<Vec<RedisCmd>> {
+        // big strings are written by set + append
         let mut cmds = vec![];
-        for (k, v) in &obj.value {
+        for (i, chunk) in obj.value.bytes.chunks(REWRITE_CHUNK_BYTES).enumerate() {
             let mut cmd = RedisCmd::new();
-            cmd.add_str_arg("hset");
+            cmd.add_str_arg(if i == 0 { "set" } else { "append" });
             cmd.add_redis_arg(&obj.key);
-            cmd.add_redis_arg(k);
-            cmd.add_redis_arg(v);
+            cmd.add_arg(chunk.to_vec());
             cmds.push(cmd);
         }
-        Ok(cmds)
-    }
 
-    pub fn rewrite_list(obj: &mut ListObject) -> anyhow::Result<Vec<RedisCmd>> {
-        let mut cmds = vec![];
-        for ele in &obj.elements {
+        if cmds.is_empty() {
             let mut cmd = RedisCmd::new();
-            cmd.add_str_arg("rpush");
+            cmd.add_str_arg("set");
             cmd.add_redis_arg(&obj.key);
-            cmd.add_redis_arg(ele);
+            cmd.add_redis_arg(&obj.value);
             cmds.push(cmd);
         }
         Ok(cmds)
     }
 
-    pub fn rewrite_module(_obj: &mut ModuleObject) -> anyhow::Result<Vec<RedisCmd>> {
-        bail! {Error::RedisRdbError(
-            "module rewrite not implemented".into(),
-        )}
+    pub fn rewrite_zset(obj: &ZsetObject, chunk_size: usize) -> anyhow::Result<Vec<RedisCmd>> {
+        let members = obj.elements.iter().map(|ele| vec![&ele.score, &ele.member]);
+        Ok(Self::rewrite_in_chunks(
+            "zadd", &obj.key, members, chunk_size,
+        ))
     }
 
-    pub fn rewrite_set(obj: &mut SetObject) -> anyhow::Result<Vec<RedisCmd>> {
-        let mut cmds = vec![];
-        for ele in &obj.elements {
+    fn rewrite_in_chunks<'a>(
+        name: &str,
+        key: &RedisString,
+        members: impl Iterator<Item = Vec<&'a RedisString>>,
+        chunk_size: usize,
+    ) -> Vec<RedisCmd> {
+        let new_cmd = || {
             let mut cmd = RedisCmd::new();
-            cmd.add_str_arg("sadd");
-            cmd.add_redis_arg(&obj.key);
-            cmd.add_redis_arg(ele);
+            cmd.add_str_arg(name);
+            cmd.add_redis_arg(key);
+            cmd
+        };
+
+        // same as RESTORE with REPLACE, the key in target will be overwritten
+        let mut cmds = vec![RedisCmd::from_str_args(&["del"])];
+        cmds[0].add_redis_arg(key);
+
+        let mut cmd = new_cmd();
+        let (mut count, mut bytes) = (0, 0);
+        for member in members {
+            for arg in member {
+                bytes += arg.bytes.len();
+                cmd.add_redis_arg(arg);
+            }
+            count += 1;
+
+            if count >= chunk_size || bytes >= REWRITE_CHUNK_BYTES {
+                cmds.push(std::mem::replace(&mut cmd, new_cmd()));
+                count = 0;
+                bytes = 0;
+            }
+        }
+
+        if count > 0 {
             cmds.push(cmd);
         }
-        Ok(cmds)
+        cmds
     }
 
-    pub fn rewrite_string(obj: &mut StringObject) -> anyhow::Result<Vec<RedisCmd>> {
-        let mut cmd = RedisCmd::new();
-        cmd.add_str_arg("set");
-        cmd.add_redis_arg(&obj.key);
-        cmd.add_redis_arg(&obj.value);
-        Ok(vec![cmd])
+    /// Rewrites the value of a snapshot entry by cmds, the ttl is applied after the last chunk.
+    /// The existing key in target is deleted only if replace, and for an entry holding a chunk
+    /// of a big key, only before its first chunk.
+    /// Returns None if the value can not be rewritten
+    pub fn rewrite_value(
+        entry: &mut RedisEntry,
+        chunk_size: usize,
+        version: f32,
+        replace: bool,
+    ) -> anyhow::Result<Option<Vec<RedisCmd>>> {
+        let mut cmds = match entry.value {
+            RedisObject::String(ref obj) => Self::rewrite_string(obj)?,
+            RedisObject::List(ref obj) => Self::rewrite_list(obj, chunk_size)?,
+            RedisObject::Set(ref obj) => Self::rewrite_set(obj, chunk_size)?,
+            RedisObject::Hash(ref obj) => Self::rewrite_hash(obj, chunk_size, version)?,
+            RedisObject::Zset(ref obj) => Self::rewrite_zset(obj, chunk_size)?,
+            RedisObject::Stream(ref mut obj) => obj.cmds.drain(..).collect(),
+            RedisObject::Module(ref obj) => match ModuleRewriter::rewrite(obj)? {
+                Some(cmds) => cmds,
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+
+        let is_first_chunk = entry.chunk.as_ref().map_or(true, |chunk| chunk.offset == 0);
+        if (!replace || !is_first_chunk)
+            && cmds
+                .first()
+                .is_some_and(|cmd| cmd.get_name().eq_ignore_ascii_case("del"))
+        {
+            cmds.remove(0);
+        }
+
+        if entry.chunk.as_ref().is_some_and(|chunk| !chunk.is_last) {
+            return Ok(Some(cmds));
+        }
+
+        if let Some(expire_cmd) = Self::rewrite_expire(entry)? {
+            cmds.push(expire_cmd)
+        }
+        Ok(Some(cmds))
     }
 
-    pub fn rewrite_zset(obj: &mut ZsetObject) -> anyhow::Result<Vec<RedisCmd>> {
-        let mut cmds = vec![];
-        for ele in obj.elements.iter() {
-            let mut cmd = RedisCmd::new();
-            cmd.add_str_arg("zadd");
-            cmd.add_redis_arg(&obj.key);
-            cmd.add_redis_arg(&ele.score);
-            cmd.add_redis_arg(&ele.member);
-            cmds.push(cmd);
+    /// Converts an entry into cmd entries for consumers (e.g. kafka), snapshot entries are rewritten,
+    /// or converted to RESTORE if they can not be rewritten
+    pub fn rewrite_as_cmd_entries(entry: &mut RedisEntry) -> anyhow::Result<Vec<RedisEntry>> {
+        if !entry.is_raw() {
+            let mut cmd_entry = Self::new_cmd_entry(entry);
+            cmd_entry.cmd = entry.cmd.clone();
+            return Ok(vec![cmd_entry]);
         }
-        Ok(cmds)
+
+        let cmds = match Self::rewrite_value(
+            entry,
+            CONSUMER_REWRITE_CHUNK_SIZE,
+            CONSUMER_TARGET_VERSION,
+            true,
+        )? {
+            Some(cmds) => cmds,
+            None => vec![Self::rewrite_as_restore(
+                entry,
+                CONSUMER_TARGET_VERSION,
+                true,
+            )?],
+        };
+
+        let key = entry.key.to_string();
+        let mut cmd_entries = Vec::with_capacity(cmds.len());
+        for mut cmd in cmds {
+            cmd.keys = vec![key.clone()];
+            let mut cmd_entry = Self::new_cmd_entry(entry);
+            cmd_entry.data_size = cmd.get_malloc_size();
+            cmd_entry.cmd = cmd;
+            cmd_entries.push(cmd_entry);
+        }
+        Ok(cmd_entries)
+    }
+
+    fn new_cmd_entry(entry: &RedisEntry) -> RedisEntry {
+        let mut cmd_entry = RedisEntry::new();
+        cmd_entry.db_id = entry.db_id;
+        cmd_entry.timestamp_ms = entry.timestamp_ms;
+        cmd_entry.expire_ms = entry.expire_ms;
+        cmd_entry.data_size = entry.data_size;
+        cmd_entry
     }
 
-    pub fn rewrite_as_restore(entry: &RedisEntry, version: f32) -> anyhow::Result<RedisCmd> {
+    pub fn rewrite_as_restore(
+        entry: &RedisEntry,
+        version: f32,
+        replace: bool,
+    ) -> anyhow::Result<RedisCmd> {
         let value = Self::create_value_dump(entry.value_type_byte, &entry.raw_bytes);
         let mut cmd = RedisCmd::new();
         cmd.add_str_arg("restore");
         cmd.add_redis_arg(&entry.key);
         cmd.add_str_arg(&entry.expire_ms.to_string());
         cmd.add_arg(value);
-        if version >= 3.0 {
+        if replace && version >= 3.0 {
             cmd.add_str_arg("replace");
         }
         Ok(cmd)
@@ -357,6 +492,51 @@ impl EntryRewriter {
         Ok(Some(cmd))
     }
 
+    /// Function libraries from rdb are like: function load replace <code>
+    pub fn is_function_load(cmd: &RedisCmd) -> bool {
+        cmd.get_name().eq_ignore_ascii_case("function")
+            && cmd.get_str_arg(1).eq_ignore_ascii_case("load")
+    }
+
+    pub fn rewrite_function_as_restore(cmd: &RedisCmd) -> anyhow::Result<RedisCmd> {
+        let code = match cmd.args.last() {
+            Some(code) if cmd.args.len() > 2 => code,
+            _ => bail! {Error::SinkerError(format!("invalid function load cmd: {}", cmd))},
+        };
+
+        // same as the payload of FUNCTION DUMP
+        let mut buf = vec![K_FLAG_FUNCTION2];
+        buf.extend_from_slice(&Self::encode_length(code.len() as u64));
+        buf.extend_from_slice(code);
+        buf.extend_from_slice(&FUNCTION_RDB_VERSION.to_le_bytes());
+        let sum64 = Self::calc_crc64(&buf);
+        buf.extend_from_slice(&sum64.to_le_bytes());
+
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg("function");
+        cmd.add_str_arg("restore");
+        cmd.add_arg(buf);
+        cmd.add_str_arg("replace");
+        Ok(cmd)
+    }
+
+    /// Length encoding of rdb
+    pub fn encode_length(len: u64) -> Vec<u8> {
+        if len < 1 << 6 {
+            vec![len as u8]
+        } else if len < 1 << 14 {
+            vec![((len >> 8) as u8) | 0x40, len as u8]
+        } else if len <= u32::MAX as u64 {
+            let mut buf = vec![0x80];
+            buf.extend_from_slice(&(len as u32).to_be_bytes());
+            buf
+        } else {
+            let mut buf = vec![0x81];
+            buf.extend_from_slice(&len.to_be_bytes());
+            buf
+        }
+    }
+
     fn create_value_dump(type_byte: u8, val: &[u8]) -> Vec<u8> {
         let mut buf: Vec<u8> = Vec::new();
         buf.push(type_byte);
@@ -368,7 +548,11 @@ impl EntryRewriter {
     }
 
     fn calc_crc64(p: &[u8]) -> u64 {
-        let mut crc: u64 = 0;
+        Self::update_crc64(0, p)
+    }
+
+    /// crc64 (Jones) used by both DUMP payloads and rdb files
+    pub fn update_crc64(mut crc: u64, p: &[u8]) -> u64 {
         for b in p {
             let inx = (crc as u8) ^ *b;
             crc = CRC64_TABLE[inx as usize] ^ (crc >> 8);
@@ -376,3 +560,89 @@ impl EntryRewriter {
         crc
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::redis::redis_entry::RedisEntryChunk;
+
+    use super::*;
+
+    #[test]
+    fn test_rewrite_in_chunks() {
+        let mut list = ListObject::new();
+        list.key = RedisString::from("l".to_string());
+        list.elements = (0..5).map(|i| RedisString::from(i.to_string())).collect();
+        let cmds = EntryRewriter::rewrite_list(&list, 2).unwrap();
+        let cmds: Vec<String> = cmds.iter().map(|i| i.to_string()).collect();
+        assert_eq!(
+            cmds,
+            vec!["del l", "rpush l 0 1", "rpush l 2 3", "rpush l 4"]
+        );
+
+        let mut hash = HashObject::new();
+        hash.key = RedisString::from("h".to_string());
+        hash.value = HashMap::from([("f".to_string().into(), "v".to_string().into())]);
+        let cmds = EntryRewriter::rewrite_hash(&hash, 2, 3.2).unwrap();
+        assert_eq!(cmds[1].to_string(), "hmset h f v");
+
+        // big members are not merged
+        let mut set = SetObject::new();
+        set.key = RedisString::from("s".to_string());
+        set.elements = vec![
+            RedisString::from(vec![b'a'; REWRITE_CHUNK_BYTES]),
+            RedisString::from(vec![b'b'; 10]),
+        ];
+        let cmds = EntryRewriter::rewrite_set(&set, 100).unwrap();
+        assert_eq!(cmds.len(), 3);
+        assert_eq!(cmds[2].args.len(), 3);
+
+        // big strings are written by set + append
+        let mut string = StringObject::new();
+        string.key = RedisString::from("s".to_string());
+        string.value = RedisString::from(vec![b'a'; REWRITE_CHUNK_BYTES * 2 + 1]);
+        let cmds = EntryRewriter::rewrite_string(&string).unwrap();
+        let names: Vec<String> = cmds.iter().map(|i| i.get_name()).collect();
+        assert_eq!(names, vec!["set", "append", "append"]);
+        assert_eq!(cmds[2].args[2].len(), 1);
+    }
+
+    #[test]
+    fn test_rewrite_chunk_value() {
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.key = RedisString::from("l".to_string());
+        entry.expire_ms = 1000;
+        let mut list = ListObject::new();
+        list.key = entry.key.clone();
+        list.elements = vec![RedisString::from("a".to_string())];
+        entry.value = RedisObject::List(list);
+
+        let rewrite = |entry: &mut RedisEntry, offset: usize, is_last: bool| {
+            entry.chunk = Some(RedisEntryChunk {
+                offset,
+                total_len: 3,
+                is_last,
+            });
+            let cmds = EntryRewriter::rewrite_value(entry, 10, 7.0, true)
+                .unwrap()
+                .unwrap();
+            cmds.iter().map(|i| i.to_string()).collect::<Vec<String>>()
+        };
+
+        assert_eq!(rewrite(&mut entry, 0, false), vec!["del l", "rpush l a"]);
+        assert_eq!(rewrite(&mut entry, 1, false), vec!["rpush l a"]);
+        assert_eq!(
+            rewrite(&mut entry, 2, true),
+            vec!["rpush l a", "pexpire l 1000"]
+        );
+
+        // the key is never deleted if not replace
+        entry.chunk = None;
+        let cmds = EntryRewriter::rewrite_value(&mut entry, 10, 7.0, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cmds[0].to_string(), "rpush l a");
+    }
+}
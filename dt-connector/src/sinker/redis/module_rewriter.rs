@@ -0,0 +1,258 @@
+use anyhow::bail;
+use dt_common::error::Error;
+use dt_common::meta::redis::redis_object::{ModuleObject, ModuleValue, RedisCmd, RedisString};
+
+const MODULE_REJSON: &str = "ReJSON-RL";
+const MODULE_BLOOM: &str = "MBbloom--";
+
+// RedisJSON 2.x saves the whole document as a json string since encver 2
+const REJSON_MIN_STRING_ENCVER: u64 = 2;
+// RedisBloom saves options since encver 2 and growth since encver 4
+const BLOOM_MIN_OPTIONS_ENCVER: u64 = 2;
+const BLOOM_MIN_GROWTH_ENCVER: u64 = 4;
+const BLOOM_DEFAULT_GROWTH: u64 = 2;
+// same as MAX_SCANDUMP_SIZE of RedisBloom
+const BLOOM_MAX_CHUNK_SIZE: usize = 10 * 1024 * 1024;
+
+/// Rewrites module values by commands of the module, so they can be moved between
+/// incompatible module versions. Returns None for unknown module types or encodings,
+/// which should be migrated by RESTORE.
+pub struct ModuleRewriter {}
+
+struct BloomLink {
+    entries: u64,
+    error: f64,
+    hashes: u64,
+    bpe: f64,
+    bits: u64,
+    n2: u64,
+    data: Vec<u8>,
+    size: u64,
+}
+
+impl ModuleRewriter {
+    pub fn rewrite(obj: &ModuleObject) -> anyhow::Result<Option<Vec<RedisCmd>>> {
+        match obj.module_name.as_str() {
+            MODULE_REJSON if obj.encver >= REJSON_MIN_STRING_ENCVER => {
+                Self::rewrite_json(obj).map(Some)
+            }
+            MODULE_BLOOM => Self::rewrite_bloom(obj).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn rewrite_json(obj: &ModuleObject) -> anyhow::Result<Vec<RedisCmd>> {
+        let mut reader = ValueReader::new(obj);
+        let json = reader.read_string()?;
+        // "." is the root path of both RedisJSON 1.x and 2.x
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg("JSON.SET");
+        cmd.add_redis_arg(&obj.key);
+        cmd.add_str_arg(".");
+        cmd.add_redis_arg(&json);
+        Ok(vec![cmd])
+    }
+
+    /// Rebuilds the scalable bloom filter by BF.LOADCHUNK, the first chunk is the header
+    /// (same as BF.SCANDUMP with iterator 0) followed by the bit arrays of all links.
+    /// refer: https://github.com/RedisBloom/RedisBloom/blob/master/src/sb.c
+    fn rewrite_bloom(obj: &ModuleObject) -> anyhow::Result<Vec<RedisCmd>> {
+        let mut reader = ValueReader::new(obj);
+        let size = reader.read_uint()?;
+        let nfilters = reader.read_uint()?;
+        let options = if obj.encver >= BLOOM_MIN_OPTIONS_ENCVER {
+            reader.read_uint()?
+        } else {
+            0
+        };
+        let growth = if obj.encver >= BLOOM_MIN_GROWTH_ENCVER {
+            reader.read_uint()?
+        } else {
+            BLOOM_DEFAULT_GROWTH
+        };
+
+        let mut links = Vec::new();
+        for _ in 0..nfilters {
+            let entries = reader.read_uint()?;
+            let error = reader.read_double()?;
+            let hashes = reader.read_uint()?;
+            let bpe = reader.read_double()?;
+            let (bits, n2) = if obj.encver == 0 {
+                ((entries as f64 * bpe) as u64, 0)
+            } else {
+                (reader.read_uint()?, reader.read_uint()?)
+            };
+            let data = reader.read_string()?.bytes;
+            let size = reader.read_uint()?;
+            links.push(BloomLink {
+                entries,
+                error,
+                hashes,
+                bpe,
+                bits,
+                n2,
+                data,
+                size,
+            });
+        }
+
+        // dumpedChainHeader, packed and little endian
+        let mut header = Vec::new();
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&(nfilters as u32).to_le_bytes());
+        header.extend_from_slice(&(options as u32).to_le_bytes());
+        header.extend_from_slice(&(growth as u32).to_le_bytes());
+        for link in links.iter() {
+            // dumpedChainLink
+            header.extend_from_slice(&(link.data.len() as u64).to_le_bytes());
+            header.extend_from_slice(&link.bits.to_le_bytes());
+            header.extend_from_slice(&link.size.to_le_bytes());
+            header.extend_from_slice(&link.error.to_le_bytes());
+            header.extend_from_slice(&link.bpe.to_le_bytes());
+            header.extend_from_slice(&(link.hashes as u32).to_le_bytes());
+            header.extend_from_slice(&link.entries.to_le_bytes());
+            header.push(link.n2 as u8);
+        }
+
+        // BF.LOADCHUNK fails if the key exists
+        let mut cmds = vec![Self::new_cmd("DEL", &obj.key)];
+        let mut cmd = Self::new_cmd("BF.LOADCHUNK", &obj.key);
+        cmd.add_str_arg("1");
+        cmd.add_arg(header);
+        cmds.push(cmd);
+
+        // the iterator of a chunk is: offset in all bit arrays + chunk length + 1,
+        // and a chunk should never cross links
+        let mut offset = 0;
+        for link in links.iter() {
+            for chunk in link.data.chunks(BLOOM_MAX_CHUNK_SIZE) {
+                offset += chunk.len();
+                let mut cmd = Self::new_cmd("BF.LOADCHUNK", &obj.key);
+                cmd.add_str_arg(&(offset + 1).to_string());
+                cmd.add_arg(chunk.to_vec());
+                cmds.push(cmd);
+            }
+        }
+        Ok(cmds)
+    }
+
+    fn new_cmd(name: &str, key: &RedisString) -> RedisCmd {
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg(name);
+        cmd.add_redis_arg(key);
+        cmd
+    }
+}
+
+struct ValueReader<'a> {
+    obj: &'a ModuleObject,
+    index: usize,
+}
+
+impl<'a> ValueReader<'a> {
+    fn new(obj: &'a ModuleObject) -> Self {
+        Self { obj, index: 0 }
+    }
+
+    fn next(&mut self) -> anyhow::Result<&'a ModuleValue> {
+        if let Some(value) = self.obj.values.get(self.index) {
+            self.index += 1;
+            Ok(value)
+        } else {
+            bail! {Error::SinkerError(format!(
+                "unexpected end of module value, module: {}, encver: {}, key: {}",
+                self.obj.module_name, self.obj.encver, self.obj.key
+            ))}
+        }
+    }
+
+    fn read_uint(&mut self) -> anyhow::Result<u64> {
+        match self.next()? {
+            ModuleValue::Uint(v) => Ok(*v),
+            ModuleValue::Int(v) => Ok(*v as u64),
+            v => self.unexpected(v),
+        }
+    }
+
+    fn read_double(&mut self) -> anyhow::Result<f64> {
+        match self.next()? {
+            ModuleValue::Double(v) => Ok(*v),
+            v => self.unexpected(v),
+        }
+    }
+
+    fn read_string(&mut self) -> anyhow::Result<RedisString> {
+        match self.next()? {
+            ModuleValue::String(v) => Ok(v.clone()),
+            v => self.unexpected(v),
+        }
+    }
+
+    fn unexpected<T>(&self, value: &ModuleValue) -> anyhow::Result<T> {
+        bail! {Error::SinkerError(format!(
+            "unexpected module value: {:?}, module: {}, encver: {}, key: {}",
+            value, self.obj.module_name, self.obj.encver, self.obj.key
+        ))}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module_obj(module_name: &str, encver: u64, values: Vec<ModuleValue>) -> ModuleObject {
+        let mut obj = ModuleObject::new();
+        obj.key = RedisString::from("k".to_string());
+        obj.module_name = module_name.into();
+        obj.encver = encver;
+        obj.values = values;
+        obj
+    }
+
+    #[test]
+    fn test_rewrite_json() {
+        let json = r#"{"a":1}"#.to_string();
+        let obj = module_obj(MODULE_REJSON, 3, vec![ModuleValue::String(json.into())]);
+        let cmds = ModuleRewriter::rewrite(&obj).unwrap().unwrap();
+        assert_eq!(cmds[0].to_string(), r#"JSON.SET k . {"a":1}"#);
+
+        // RedisJSON 1.x saves documents as node trees
+        let obj = module_obj(MODULE_REJSON, 0, vec![]);
+        assert!(ModuleRewriter::rewrite(&obj).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rewrite_bloom() {
+        let values = vec![
+            ModuleValue::Uint(2),   // size
+            ModuleValue::Uint(1),   // nfilters
+            ModuleValue::Uint(0),   // options
+            ModuleValue::Uint(2),   // growth
+            ModuleValue::Uint(100), // entries
+            ModuleValue::Double(0.01),
+            ModuleValue::Uint(7), // hashes
+            ModuleValue::Double(9.585),
+            ModuleValue::Uint(1024), // bits
+            ModuleValue::Uint(10),   // n2
+            ModuleValue::String(RedisString::from(vec![1u8; 128])),
+            ModuleValue::Uint(2), // link size
+        ];
+        let obj = module_obj(MODULE_BLOOM, 4, values);
+        let cmds = ModuleRewriter::rewrite(&obj).unwrap().unwrap();
+        assert_eq!(cmds.len(), 3);
+        assert_eq!(cmds[0].to_string(), "DEL k");
+        assert_eq!(cmds[1].get_str_arg(2), "1");
+        // header: 8 + 4 * 3, link: 8 * 5 + 4 + 8 + 1
+        assert_eq!(cmds[1].args[3].len(), 20 + 53);
+        assert_eq!(cmds[2].get_str_arg(2), "129");
+        assert_eq!(cmds[2].args[3], vec![1u8; 128]);
+
+        // missing values
+        let obj = module_obj(MODULE_BLOOM, 4, vec![ModuleValue::Uint(2)]);
+        assert!(ModuleRewriter::rewrite(&obj).is_err());
+
+        // unknown modules
+        let obj = module_obj("MBbloomCF", 1, vec![]);
+        assert!(ModuleRewriter::rewrite(&obj).unwrap().is_none());
+    }
+}
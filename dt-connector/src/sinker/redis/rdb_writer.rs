@@ -0,0 +1,402 @@
+use std::io::Write;
+
+use anyhow::bail;
+use chrono::Utc;
+use dt_common::{
+    error::Error,
+    log_warn,
+    meta::redis::{
+        redis_entry::RedisEntry,
+        redis_object::{RedisObject, RedisString},
+    },
+};
+
+use crate::extractor::redis::rdb::{entry_parser::*, rdb_parser::*};
+
+use super::entry_rewriter::EntryRewriter;
+
+pub const MIN_RDB_VERSION: u32 = 6;
+pub const MAX_RDB_VERSION: u32 = 11;
+
+/// Serializes RedisEntry into a rdb file of the given version.
+/// - raw entries are written as they are if the target version supports their encodings,
+///   otherwise they are re-encoded with the plain encodings supported by all versions
+/// - expirations are written as absolute unix time in milliseconds
+pub struct RdbWriter<W: Write> {
+    writer: W,
+    rdb_version: u32,
+    crc: u64,
+    now_db_id: i64,
+}
+
+impl<W: Write> RdbWriter<W> {
+    pub fn new(writer: W, rdb_version: u32) -> anyhow::Result<Self> {
+        if !(MIN_RDB_VERSION..=MAX_RDB_VERSION).contains(&rdb_version) {
+            bail! {Error::ConfigError(format!(
+                "unsupported rdb_version: {}, should be in [{}, {}]",
+                rdb_version, MIN_RDB_VERSION, MAX_RDB_VERSION
+            ))}
+        }
+
+        let mut me = Self {
+            writer,
+            rdb_version,
+            crc: 0,
+            now_db_id: -1,
+        };
+        me.write_bytes(format!("REDIS{:04}", rdb_version).as_bytes())?;
+        // aux fields were introduced in rdb 7 (redis 3.2)
+        if rdb_version >= 7 {
+            me.write_aux("redis-bits", b"64")?;
+            me.write_aux("ctime", Utc::now().timestamp().to_string().as_bytes())?;
+        }
+        Ok(me)
+    }
+
+    /// Returns false if the entry is not written
+    pub fn write_entry(&mut self, entry: &RedisEntry) -> anyhow::Result<bool> {
+        if !entry.is_base {
+            log_warn!("rdb file sinker ignores cmd: {}", entry.cmd);
+            return Ok(false);
+        }
+
+        // lua scripts from rdb aux fields
+        if entry.cmd.get_name().eq_ignore_ascii_case("script") {
+            if self.rdb_version >= 7 && entry.cmd.args.len() > 2 {
+                let script = entry.cmd.args[2].clone();
+                self.write_aux("lua", &script)?;
+                return Ok(true);
+            }
+            return Ok(false);
+        }
+
+        // function libraries were introduced in rdb 10 (redis 7.0)
+        if EntryRewriter::is_function_load(&entry.cmd) {
+            if self.rdb_version >= 10 && entry.cmd.args.len() > 2 {
+                let code = entry.cmd.args.last().unwrap().clone();
+                self.write_bytes(&[K_FLAG_FUNCTION2])?;
+                self.write_string(&code)?;
+                return Ok(true);
+            }
+            return Ok(false);
+        }
+
+        if entry.db_id != self.now_db_id {
+            self.write_bytes(&[K_FLAG_SELECT])?;
+            self.write_length(entry.db_id as u64)?;
+            self.now_db_id = entry.db_id;
+        }
+
+        if entry.expire_ms > 0 {
+            let expire_at = Utc::now().timestamp_millis() + entry.expire_ms;
+            self.write_bytes(&[K_FLAG_EXPIRE_MS])?;
+            self.write_bytes(&(expire_at as u64).to_le_bytes())?;
+        }
+
+        if entry.is_raw() && Self::min_rdb_version(entry.value_type_byte) <= self.rdb_version {
+            self.write_bytes(&[entry.value_type_byte])?;
+            self.write_string(entry.key.as_bytes())?;
+            self.write_bytes(&entry.raw_bytes)?;
+        } else {
+            self.write_object(entry)?;
+        }
+        Ok(true)
+    }
+
+    pub fn finish(&mut self) -> anyhow::Result<()> {
+        self.write_bytes(&[K_EOF])?;
+        // checksum was introduced in rdb 5
+        let crc = self.crc;
+        self.write_bytes(&crc.to_le_bytes())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn write_object(&mut self, entry: &RedisEntry) -> anyhow::Result<()> {
+        match &entry.value {
+            RedisObject::String(obj) => {
+                self.write_type_and_key(RDB_TYPE_STRING, &entry.key)?;
+                self.write_string(obj.value.as_bytes())?;
+            }
+
+            RedisObject::List(obj) => {
+                self.write_type_and_key(RDB_TYPE_LIST, &entry.key)?;
+                self.write_length(obj.elements.len() as u64)?;
+                for element in obj.elements.iter() {
+                    self.write_string(element.as_bytes())?;
+                }
+            }
+
+            RedisObject::Set(obj) => {
+                self.write_type_and_key(RDB_TYPE_SET, &entry.key)?;
+                self.write_length(obj.elements.len() as u64)?;
+                for element in obj.elements.iter() {
+                    self.write_string(element.as_bytes())?;
+                }
+            }
+
+            RedisObject::Hash(obj) => {
+                self.write_type_and_key(RDB_TYPE_HASH, &entry.key)?;
+                self.write_length(obj.value.len() as u64)?;
+                for (field, value) in obj.value.iter() {
+                    self.write_string(field.as_bytes())?;
+                    self.write_string(value.as_bytes())?;
+                }
+            }
+
+            RedisObject::Zset(obj) => {
+                // binary double scores were introduced in rdb 8 (redis 4.0)
+                let binary_score = self.rdb_version >= 8;
+                let type_byte = if binary_score {
+                    RDB_TYPE_ZSET_2
+                } else {
+                    RDB_TYPE_ZSET
+                };
+                self.write_type_and_key(type_byte, &entry.key)?;
+                self.write_length(obj.elements.len() as u64)?;
+                for element in obj.elements.iter() {
+                    self.write_string(element.member.as_bytes())?;
+                    let score: f64 = String::from(element.score.clone()).parse()?;
+                    if binary_score {
+                        self.write_bytes(&score.to_le_bytes())?;
+                    } else {
+                        self.write_float(score)?;
+                    }
+                }
+            }
+
+            _ => {
+                bail! {Error::RedisRdbError(format!(
+                    "can not write {} of type_byte: {} into rdb version: {}, key: {}",
+                    entry.get_type(),
+                    entry.value_type_byte,
+                    self.rdb_version,
+                    entry.key
+                ))}
+            }
+        }
+        Ok(())
+    }
+
+    fn write_type_and_key(&mut self, type_byte: u8, key: &RedisString) -> anyhow::Result<()> {
+        self.write_bytes(&[type_byte])?;
+        self.write_string(key.as_bytes())
+    }
+
+    fn write_aux(&mut self, key: &str, value: &[u8]) -> anyhow::Result<()> {
+        self.write_bytes(&[K_FLAG_AUX])?;
+        self.write_string(key.as_bytes())?;
+        self.write_string(value)
+    }
+
+    fn write_string(&mut self, value: &[u8]) -> anyhow::Result<()> {
+        self.write_length(value.len() as u64)?;
+        self.write_bytes(value)
+    }
+
+    fn write_float(&mut self, value: f64) -> anyhow::Result<()> {
+        if value.is_nan() {
+            self.write_bytes(&[253])
+        } else if value == f64::INFINITY {
+            self.write_bytes(&[254])
+        } else if value == f64::NEG_INFINITY {
+            self.write_bytes(&[255])
+        } else {
+            let value = format!("{}", value);
+            self.write_bytes(&[value.len() as u8])?;
+            self.write_bytes(value.as_bytes())
+        }
+    }
+
+    fn write_length(&mut self, len: u64) -> anyhow::Result<()> {
+        self.write_bytes(&EntryRewriter::encode_length(len))
+    }
+
+    fn write_bytes(&mut self, buf: &[u8]) -> anyhow::Result<()> {
+        self.crc = EntryRewriter::update_crc64(self.crc, buf);
+        self.writer.write_all(buf)?;
+        Ok(())
+    }
+
+    /// The lowest rdb version supporting the type encoding
+    fn min_rdb_version(type_byte: u8) -> u32 {
+        match type_byte {
+            RDB_TYPE_STRING..=RDB_TYPE_HASH => 1,
+            RDB_TYPE_ZSET_2 | RDB_TYPE_MODULE => 8,
+            RDB_TYPE_MODULE_2 => 9,
+            RDB_TYPE_HASH_ZIPMAP..=RDB_TYPE_HASH_ZIPLIST => 2,
+            RDB_TYPE_LIST_QUICKLIST => 7,
+            RDB_TYPE_STREAM_LISTPACKS => 9,
+            RDB_TYPE_HASH_LISTPACK..=RDB_TYPE_STREAM_LISTPACKS_2 => 10,
+            RDB_TYPE_SET_LISTPACK | RDB_TYPE_STREAM_LISTPACKS_3 => 11,
+            _ => u32::MAX,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, io::Cursor};
+
+    use dt_common::meta::redis::redis_object::{
+        HashObject, ListObject, SetObject, StringObject, ZSetEntry, ZsetObject,
+    };
+
+    use crate::extractor::redis::{
+        rdb::rdb_parser::RdbParser, rdb::reader::rdb_reader::RdbReader, StreamReader,
+    };
+
+    use super::*;
+
+    fn base_entry(db_id: i64, key: &str, value: RedisObject) -> RedisEntry {
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.db_id = db_id;
+        entry.key = RedisString::from(key.to_string());
+        entry.value = value;
+        entry
+    }
+
+    fn parse_rdb(buf: &[u8]) -> Vec<RedisEntry> {
+        let mut cursor = Cursor::new(buf);
+        let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(&mut cursor);
+        let mut parser = RdbParser {
+            reader: RdbReader {
+                conn: &mut stream_reader,
+                rdb_length: buf.len(),
+                position: 0,
+                copy_raw: false,
+                raw_bytes: Vec::new(),
+            },
+            repl_stream_db_id: 0,
+            now_db_id: 0,
+            expire_ms: 0,
+            idle: 0,
+            freq: 0,
+            is_end: false,
+        };
+
+        parser.load_meta().unwrap();
+        let mut entries = Vec::new();
+        while !parser.is_end {
+            if let Some(entry) = parser.load_entry().unwrap() {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+
+    fn write_rdb(rdb_version: u32, entries: &[RedisEntry]) -> Vec<u8> {
+        let mut writer = RdbWriter::new(Vec::new(), rdb_version).unwrap();
+        for entry in entries {
+            assert!(writer.write_entry(entry).unwrap());
+        }
+        writer.finish().unwrap();
+        writer.writer
+    }
+
+    #[test]
+    fn test_write_rdb() {
+        let mut string = StringObject::new();
+        string.value = RedisString::from("v".to_string());
+        let mut string_entry = base_entry(0, "s", RedisObject::String(string));
+        string_entry.expire_ms = 100000;
+
+        let mut list = ListObject::new();
+        list.elements = vec!["a".to_string().into(), "b".to_string().into()];
+        let mut set = SetObject::new();
+        set.elements = vec!["c".to_string().into()];
+        let mut zset = ZsetObject::new();
+        zset.elements = vec![ZSetEntry {
+            member: "m".to_string().into(),
+            score: "1.5".to_string().into(),
+        }];
+        let mut hash = HashObject::new();
+        hash.value = HashMap::from([("f".to_string().into(), "v".to_string().into())]);
+
+        let entries = vec![
+            string_entry,
+            base_entry(1, "l", RedisObject::List(list)),
+            base_entry(1, "st", RedisObject::Set(set)),
+            base_entry(2, "z", RedisObject::Zset(zset)),
+            base_entry(2, "h", RedisObject::Hash(hash)),
+        ];
+
+        for rdb_version in MIN_RDB_VERSION..=MAX_RDB_VERSION {
+            let buf = write_rdb(rdb_version, &entries);
+            assert_eq!(&buf[..9], format!("REDIS{:04}", rdb_version).as_bytes());
+            let crc = EntryRewriter::update_crc64(0, &buf[..buf.len() - 8]);
+            assert_eq!(&buf[buf.len() - 8..], &crc.to_le_bytes());
+
+            let parsed = parse_rdb(&buf);
+            assert_eq!(parsed.len(), entries.len());
+            for (src, dst) in entries.iter().zip(parsed.iter()) {
+                assert_eq!(src.db_id, dst.db_id);
+                assert_eq!(src.key, dst.key);
+                assert_eq!(src.get_type(), dst.get_type());
+                assert_eq!(src.expire_ms > 0, dst.expire_ms > 0);
+            }
+
+            if let RedisObject::Zset(zset) = &parsed[3].value {
+                assert_eq!(String::from(zset.elements[0].score.clone()), "1.5");
+            } else {
+                panic!("zset expected");
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_raw_entry() {
+        // listpack encoded hash (type 16) from redis 7.0: {"f": "v"}
+        let raw_bytes = vec![
+            0x0d, 0x0d, 0x00, 0x00, 0x00, 0x02, 0x00, 0x81, 0x66, 0x02, 0x81, 0x76, 0x02, 0xff,
+        ];
+        let mut hash = HashObject::new();
+        hash.value = HashMap::from([("f".to_string().into(), "v".to_string().into())]);
+        let mut entry = base_entry(0, "h", RedisObject::Hash(hash));
+        entry.value_type_byte = RDB_TYPE_HASH_LISTPACK;
+        entry.raw_bytes = raw_bytes.clone();
+
+        // kept as it is
+        let parsed = parse_rdb(&write_rdb(10, &[entry.clone()]));
+        assert_eq!(parsed[0].value_type_byte, RDB_TYPE_HASH_LISTPACK);
+        assert_eq!(parsed[0].get_type(), entry.get_type());
+
+        // re-encoded for older versions
+        let parsed = parse_rdb(&write_rdb(9, &[entry.clone()]));
+        assert_eq!(parsed[0].value_type_byte, RDB_TYPE_HASH);
+        if let RedisObject::Hash(hash) = &parsed[0].value {
+            assert_eq!(
+                hash.value.get(&RedisString::from("f".to_string())),
+                Some(&RedisString::from("v".to_string()))
+            );
+        } else {
+            panic!("hash expected");
+        }
+    }
+
+    #[test]
+    fn test_write_function() {
+        let code = "#!lua name=mylib\nredis.register_function('f', function() return 1 end)";
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        for arg in ["function", "load", "replace", code] {
+            entry.cmd.add_str_arg(arg);
+        }
+
+        let parsed = parse_rdb(&write_rdb(10, &[entry.clone()]));
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].cmd.to_string(), entry.cmd.to_string());
+
+        // function libraries are not supported before redis 7.0
+        let mut writer = RdbWriter::new(Vec::new(), 9).unwrap();
+        assert!(!writer.write_entry(&entry).unwrap());
+
+        let cmd = EntryRewriter::rewrite_function_as_restore(&entry.cmd).unwrap();
+        assert_eq!(cmd.get_str_arg(1), "restore");
+        let payload = &cmd.args[2];
+        assert_eq!(payload[0], K_FLAG_FUNCTION2);
+        let crc = EntryRewriter::update_crc64(0, &payload[..payload.len() - 8]);
+        assert_eq!(&payload[payload.len() - 8..], &crc.to_le_bytes());
+    }
+}
@@ -0,0 +1,552 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    error::Error,
+    meta::{
+        dt_data::{DtData, DtItem},
+        redis::{
+            command::cmd_encoder::CmdEncoder,
+            redis_entry::RedisEntry,
+            redis_object::{RedisCmd, RedisObject, RedisString, StreamObject},
+        },
+    },
+    monitor::monitor::Monitor,
+};
+use redis::{Connection, ConnectionLike, Value};
+
+use crate::{
+    check_log::{
+        check_log::{CheckLog, DiffColValue},
+        log_type::LogType,
+    },
+    rdb_router::RdbRouter,
+    sinker::{base_checker::BaseChecker, base_sinker::BaseSinker},
+    Sinker,
+};
+
+pub const KEY_COL: &str = "key";
+// stop comparing elements of a big key once this many diffs are found
+const MAX_ELEMENT_DIFFS: usize = 10;
+// ttls of src and dst are read at different times, and keep decreasing
+const TTL_TOLERANCE_MS: i64 = 10000;
+
+pub struct RedisChecker {
+    pub id: String,
+    pub conn: Connection,
+    // elements of big keys (hash / set / zset / list / stream) are fetched from dst in chunks of batch_size
+    pub batch_size: usize,
+    pub now_db_id: i64,
+    pub reverse_router: RdbRouter,
+    pub monitor: Arc<Mutex<Monitor>>,
+    // result of the big key being checked in chunks, logged after its last chunk
+    pub chunk_result: Option<(LogType, HashMap<String, DiffColValue>)>,
+}
+
+#[async_trait]
+impl Sinker for RedisChecker {
+    async fn sink_raw(&mut self, data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let start_time = Instant::now();
+        let mut data_size = 0;
+        let mut miss = Vec::new();
+        let mut diff = Vec::new();
+        for dt_item in data.iter() {
+            data_size += dt_item.dt_data.get_data_size();
+            if let DtData::Redis { entry } = &dt_item.dt_data {
+                // only keys from rdb (or revised by check logs) can be checked,
+                // aux entries like lua scripts have no key
+                if !entry.is_base || entry.key.bytes.is_empty() {
+                    continue;
+                }
+
+                if let Some(check_log) = self.check_entry(entry)? {
+                    match check_log.log_type {
+                        LogType::Miss => miss.push(check_log),
+                        _ => diff.push(check_log),
+                    }
+                }
+            }
+        }
+        BaseChecker::log_dml(miss, diff);
+
+        BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), data_size, start_time)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+impl RedisChecker {
+    fn check_entry(&mut self, entry: &RedisEntry) -> anyhow::Result<Option<CheckLog>> {
+        if entry.db_id != self.now_db_id {
+            let db_id = entry.db_id.to_string();
+            self.query(&RedisCmd::from_str_args(&["SELECT", &db_id]))?;
+            self.now_db_id = entry.db_id;
+        }
+
+        // following chunks of a big key go on with the result of the previous chunks
+        let (log_type, mut diffs) = match (&entry.chunk, self.chunk_result.take()) {
+            (Some(chunk), Some(result)) if chunk.offset > 0 => result,
+            _ => self.check_key(entry)?,
+        };
+
+        // elements are compared only if the key exists in dst with the same type
+        if log_type == LogType::Diff && !diffs.contains_key("type") {
+            self.check_elements(entry, &mut diffs)?;
+        }
+
+        if let Some(chunk) = &entry.chunk {
+            if !chunk.is_last {
+                self.chunk_result = Some((log_type, diffs));
+                return Ok(None);
+            }
+        }
+
+        if log_type == LogType::Miss || !diffs.is_empty() {
+            Ok(Some(self.build_check_log(entry, log_type, diffs)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Checks existence, type, length and ttl of the key
+    fn check_key(
+        &mut self,
+        entry: &RedisEntry,
+    ) -> anyhow::Result<(LogType, HashMap<String, DiffColValue>)> {
+        let key = &entry.key;
+        let mut diffs = HashMap::new();
+        let dst_type = Self::to_string(self.query(&Self::key_cmd("TYPE", key))?);
+        if dst_type == "none" {
+            return Ok((LogType::Miss, diffs));
+        }
+
+        let src_type = entry.get_type();
+        // TYPE returns the module type name for module values, e.g. ReJSON-RL
+        if dst_type != src_type && !matches!(entry.value, RedisObject::Module(_)) {
+            Self::add_diff(&mut diffs, "type", Some(src_type), Some(dst_type));
+            return Ok((LogType::Diff, diffs));
+        }
+
+        let src_len = match &entry.value {
+            RedisObject::Hash(obj) => Some(("HLEN", obj.value.len())),
+            RedisObject::Set(obj) => Some(("SCARD", obj.elements.len())),
+            RedisObject::Zset(obj) => Some(("ZCARD", obj.elements.len())),
+            RedisObject::List(obj) => Some(("LLEN", obj.elements.len())),
+            _ => None,
+        };
+        if let Some((cmd_name, src_len)) = src_len {
+            // a chunk only holds part of the elements
+            let src_len = entry
+                .chunk
+                .as_ref()
+                .map_or(src_len, |chunk| chunk.total_len);
+            self.check_length(key, cmd_name, src_len, &mut diffs)?;
+        }
+
+        self.check_ttl(entry, &mut diffs)?;
+        Ok((LogType::Diff, diffs))
+    }
+
+    fn check_elements(
+        &mut self,
+        entry: &RedisEntry,
+        diffs: &mut HashMap<String, DiffColValue>,
+    ) -> anyhow::Result<()> {
+        let key = &entry.key;
+        match &entry.value {
+            RedisObject::String(obj) => {
+                let dst_value = Self::to_bytes(self.query(&Self::key_cmd("GET", key))?);
+                if dst_value.as_deref() != Some(obj.value.as_bytes()) {
+                    Self::add_diff(
+                        diffs,
+                        "value",
+                        Some(obj.value.to_string()),
+                        dst_value.map(Self::bytes_to_string),
+                    );
+                }
+            }
+
+            RedisObject::Hash(obj) => {
+                let fields: Vec<&RedisString> = obj.value.keys().collect();
+                for chunk in fields.chunks(self.batch_size) {
+                    let mut cmd = Self::key_cmd("HMGET", key);
+                    for field in chunk.iter() {
+                        cmd.add_redis_arg(field);
+                    }
+                    let dst_values = Self::to_array(self.query(&cmd)?);
+                    for (field, dst_value) in chunk.iter().zip(dst_values) {
+                        let src_value = &obj.value[*field];
+                        let dst_value = Self::to_bytes(dst_value);
+                        if dst_value.as_deref() != Some(src_value.as_bytes()) {
+                            Self::add_diff(
+                                diffs,
+                                &format!("field:{}", field),
+                                Some(src_value.to_string()),
+                                dst_value.map(Self::bytes_to_string),
+                            );
+                        }
+                    }
+                    if Self::too_many_diffs(diffs) {
+                        break;
+                    }
+                }
+            }
+
+            RedisObject::Set(obj) => {
+                for chunk in obj.elements.chunks(self.batch_size) {
+                    let cmds: Vec<RedisCmd> = chunk
+                        .iter()
+                        .map(|member| {
+                            let mut cmd = Self::key_cmd("SISMEMBER", key);
+                            cmd.add_redis_arg(member);
+                            cmd
+                        })
+                        .collect();
+                    let dst_values = self.query_pipeline(&cmds)?;
+                    for (member, dst_value) in chunk.iter().zip(dst_values) {
+                        if Self::to_i64(dst_value) != 1 {
+                            Self::add_diff(
+                                diffs,
+                                &format!("member:{}", member),
+                                Some(member.to_string()),
+                                None,
+                            );
+                        }
+                    }
+                    if Self::too_many_diffs(diffs) {
+                        break;
+                    }
+                }
+            }
+
+            RedisObject::Zset(obj) => {
+                for chunk in obj.elements.chunks(self.batch_size) {
+                    let cmds: Vec<RedisCmd> = chunk
+                        .iter()
+                        .map(|ele| {
+                            let mut cmd = Self::key_cmd("ZSCORE", key);
+                            cmd.add_redis_arg(&ele.member);
+                            cmd
+                        })
+                        .collect();
+                    let dst_values = self.query_pipeline(&cmds)?;
+                    for (ele, dst_value) in chunk.iter().zip(dst_values) {
+                        let src_score = ele.score.to_string();
+                        let dst_score = Self::to_bytes(dst_value).map(Self::bytes_to_string);
+                        if !Self::score_eq(&src_score, dst_score.as_deref()) {
+                            Self::add_diff(
+                                diffs,
+                                &format!("member:{}", ele.member),
+                                Some(src_score),
+                                dst_score,
+                            );
+                        }
+                    }
+                    if Self::too_many_diffs(diffs) {
+                        break;
+                    }
+                }
+            }
+
+            RedisObject::List(obj) => {
+                // index of the first element in the whole list
+                let offset = entry.chunk.as_ref().map_or(0, |chunk| chunk.offset);
+                for (i, chunk) in obj.elements.chunks(self.batch_size).enumerate() {
+                    let start = offset + i * self.batch_size;
+                    let stop = start + chunk.len() - 1;
+                    let mut cmd = Self::key_cmd("LRANGE", key);
+                    cmd.add_str_arg(&start.to_string());
+                    cmd.add_str_arg(&stop.to_string());
+                    let mut dst_values = Self::to_array(self.query(&cmd)?).into_iter();
+                    for (j, src_value) in chunk.iter().enumerate() {
+                        let dst_value = dst_values.next().and_then(Self::to_bytes);
+                        if dst_value.as_deref() != Some(src_value.as_bytes()) {
+                            Self::add_diff(
+                                diffs,
+                                &format!("index:{}", start + j),
+                                Some(src_value.to_string()),
+                                dst_value.map(Self::bytes_to_string),
+                            );
+                        }
+                    }
+                    if Self::too_many_diffs(diffs) {
+                        break;
+                    }
+                }
+            }
+
+            RedisObject::Stream(obj) => self.check_stream(key, obj, diffs)?,
+
+            // module values are opaque, only the existence and type are checked
+            RedisObject::Module(_) | RedisObject::Unknown => {}
+        }
+        Ok(())
+    }
+
+    fn check_stream(
+        &mut self,
+        key: &RedisString,
+        obj: &StreamObject,
+        diffs: &mut HashMap<String, DiffColValue>,
+    ) -> anyhow::Result<()> {
+        // entries are restored by: xadd key id field value [field value ...]
+        let src_entries: Vec<&RedisCmd> = obj
+            .cmds
+            .iter()
+            .filter(|cmd| {
+                cmd.get_str_arg(0).eq_ignore_ascii_case("xadd")
+                    && !cmd.get_str_arg(2).eq_ignore_ascii_case("maxlen")
+            })
+            .collect();
+        self.check_length(key, "XLEN", src_entries.len(), diffs)?;
+
+        for chunk in src_entries.chunks(self.batch_size) {
+            let mut cmd = Self::key_cmd("XRANGE", key);
+            cmd.add_str_arg(&chunk[0].get_str_arg(2));
+            cmd.add_str_arg(&chunk[chunk.len() - 1].get_str_arg(2));
+
+            let mut dst_entries = HashMap::new();
+            for dst_entry in Self::to_array(self.query(&cmd)?) {
+                let mut id_fields = Self::to_array(dst_entry).into_iter();
+                if let (Some(id), Some(fields)) = (id_fields.next(), id_fields.next()) {
+                    let fields: Vec<Vec<u8>> = Self::to_array(fields)
+                        .into_iter()
+                        .filter_map(Self::to_bytes)
+                        .collect();
+                    dst_entries.insert(Self::to_string(id), fields);
+                }
+            }
+
+            for src_entry in chunk.iter() {
+                let id = src_entry.get_str_arg(2);
+                let src_fields = &src_entry.args[3..];
+                let dst_fields = dst_entries.get(&id);
+                if dst_fields.map(|v| v.as_slice()) != Some(src_fields) {
+                    Self::add_diff(
+                        diffs,
+                        &format!("id:{}", id),
+                        Some(Self::join_fields(src_fields)),
+                        dst_fields.map(|v| Self::join_fields(v)),
+                    );
+                }
+            }
+            if Self::too_many_diffs(diffs) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_length(
+        &mut self,
+        key: &RedisString,
+        cmd_name: &str,
+        src_len: usize,
+        diffs: &mut HashMap<String, DiffColValue>,
+    ) -> anyhow::Result<()> {
+        let dst_len = Self::to_i64(self.query(&Self::key_cmd(cmd_name, key))?);
+        if dst_len != src_len as i64 {
+            Self::add_diff(
+                diffs,
+                "length",
+                Some(src_len.to_string()),
+                Some(dst_len.to_string()),
+            );
+        }
+        Ok(())
+    }
+
+    fn check_ttl(
+        &mut self,
+        entry: &RedisEntry,
+        diffs: &mut HashMap<String, DiffColValue>,
+    ) -> anyhow::Result<()> {
+        let dst_ttl = Self::to_i64(self.query(&Self::key_cmd("PTTL", &entry.key))?);
+        if !Self::ttl_eq(entry.expire_ms, dst_ttl) {
+            Self::add_diff(
+                diffs,
+                "ttl",
+                Some(entry.expire_ms.to_string()),
+                Some(dst_ttl.to_string()),
+            );
+        }
+        Ok(())
+    }
+
+    fn build_check_log(
+        &self,
+        entry: &RedisEntry,
+        log_type: LogType,
+        diff_col_values: HashMap<String, DiffColValue>,
+    ) -> CheckLog {
+        // check logs record the src db and key so that they can be revised from src directly
+        let db_id = entry.db_id.to_string();
+        let schema = self.reverse_router.get_schema_map(&db_id).to_string();
+        let key = self
+            .reverse_router
+            .get_key_map(entry.key.as_bytes())
+            .unwrap_or_else(|| entry.key.bytes.clone());
+
+        let mut id_col_values = HashMap::new();
+        id_col_values.insert(KEY_COL.to_string(), Some(Self::bytes_to_string(key)));
+
+        CheckLog {
+            log_type,
+            schema,
+            tb: String::new(),
+            id_col_values,
+            diff_col_values,
+        }
+    }
+
+    fn query(&mut self, cmd: &RedisCmd) -> anyhow::Result<Value> {
+        match self.conn.req_packed_command(&CmdEncoder::encode(cmd)) {
+            Ok(value) => Ok(value),
+            Err(error) => bail! {Error::SinkerError(format!(
+                "check failed, error: {:?}, cmd: {}",
+                error, cmd
+            ))},
+        }
+    }
+
+    fn query_pipeline(&mut self, cmds: &[RedisCmd]) -> anyhow::Result<Vec<Value>> {
+        let mut packed_cmds = Vec::new();
+        for cmd in cmds.iter() {
+            packed_cmds.extend_from_slice(&CmdEncoder::encode(cmd));
+        }
+        match self.conn.req_packed_commands(&packed_cmds, 0, cmds.len()) {
+            Ok(values) => Ok(values),
+            Err(error) => bail! {Error::SinkerError(format!(
+                "batch check failed, error: {:?}",
+                error
+            ))},
+        }
+    }
+
+    fn key_cmd(name: &str, key: &RedisString) -> RedisCmd {
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg(name);
+        cmd.add_redis_arg(key);
+        cmd
+    }
+
+    fn add_diff(
+        diffs: &mut HashMap<String, DiffColValue>,
+        name: &str,
+        src: Option<String>,
+        dst: Option<String>,
+    ) {
+        if !Self::too_many_diffs(diffs) {
+            diffs.insert(name.to_string(), DiffColValue { src, dst });
+        }
+    }
+
+    fn too_many_diffs(diffs: &HashMap<String, DiffColValue>) -> bool {
+        diffs.len() >= MAX_ELEMENT_DIFFS
+    }
+
+    fn score_eq(src: &str, dst: Option<&str>) -> bool {
+        let dst = match dst {
+            Some(dst) => dst,
+            None => return false,
+        };
+        match (src.parse::<f64>(), dst.parse::<f64>()) {
+            (Ok(src), Ok(dst)) => src == dst,
+            _ => src == dst,
+        }
+    }
+
+    /// src_ttl is 0 and dst_ttl is -1 if the key has no expiration
+    fn ttl_eq(src_ttl: i64, dst_ttl: i64) -> bool {
+        if src_ttl <= 0 || dst_ttl <= 0 {
+            return (src_ttl > 0) == (dst_ttl > 0);
+        }
+        (src_ttl - dst_ttl).abs() <= TTL_TOLERANCE_MS
+    }
+
+    fn join_fields(fields: &[Vec<u8>]) -> String {
+        fields
+            .iter()
+            .map(|i| String::from_utf8_lossy(i).to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn bytes_to_string(bytes: Vec<u8>) -> String {
+        String::from_utf8_lossy(&bytes).to_string()
+    }
+
+    fn to_bytes(value: Value) -> Option<Vec<u8>> {
+        match value {
+            Value::BulkString(v) => Some(v),
+            Value::SimpleString(v) => Some(v.into_bytes()),
+            Value::Okay => Some("OK".into()),
+            Value::Int(v) => Some(v.to_string().into_bytes()),
+            Value::Double(v) => Some(v.to_string().into_bytes()),
+            _ => None,
+        }
+    }
+
+    fn to_string(value: Value) -> String {
+        Self::to_bytes(value)
+            .map(Self::bytes_to_string)
+            .unwrap_or_default()
+    }
+
+    fn to_i64(value: Value) -> i64 {
+        match value {
+            Value::Int(v) => v,
+            Value::Boolean(v) => v as i64,
+            _ => Self::to_string(value).parse().unwrap_or_default(),
+        }
+    }
+
+    fn to_array(value: Value) -> Vec<Value> {
+        match value {
+            Value::Array(v) | Value::Set(v) => v,
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_eq() {
+        assert!(RedisChecker::score_eq("1", Some("1")));
+        assert!(RedisChecker::score_eq("1.5", Some("1.50")));
+        assert!(RedisChecker::score_eq("inf", Some("inf")));
+        assert!(RedisChecker::score_eq("-inf", Some("-inf")));
+        assert!(!RedisChecker::score_eq("1", Some("2")));
+        assert!(!RedisChecker::score_eq("1", None));
+    }
+
+    #[test]
+    fn test_ttl_eq() {
+        // no expiration in both
+        assert!(RedisChecker::ttl_eq(0, -1));
+        // expiration only in one of them
+        assert!(!RedisChecker::ttl_eq(1000, -1));
+        assert!(!RedisChecker::ttl_eq(0, 1000));
+        // within tolerance
+        assert!(RedisChecker::ttl_eq(100000, 100000));
+        assert!(RedisChecker::ttl_eq(100000, 100000 - TTL_TOLERANCE_MS));
+        assert!(RedisChecker::ttl_eq(100000, 100000 + TTL_TOLERANCE_MS));
+        // out of tolerance
+        assert!(!RedisChecker::ttl_eq(100000, 100000 - TTL_TOLERANCE_MS - 1));
+        assert!(!RedisChecker::ttl_eq(1000000, 100000));
+    }
+}
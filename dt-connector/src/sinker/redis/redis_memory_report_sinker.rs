@@ -0,0 +1,482 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use dt_common::{
+    log_info,
+    meta::{
+        dt_data::{DtData, DtItem},
+        redis::{redis_entry::RedisEntry, redis_object::RedisObject},
+    },
+    monitor::monitor::Monitor,
+};
+use serde::Serialize;
+
+use crate::{extractor::redis::rdb::entry_parser::*, sinker::base_sinker::BaseSinker, Sinker};
+
+pub const MEMORY_REPORT_FILE: &str = "memory_report.json";
+pub const MEMORY_KEYS_FILE: &str = "memory_keys.csv";
+const MEMORY_KEYS_HEADER: &str =
+    "db_id,key_type,encoding,key,serialized_size,elements,largest_element,expire_ms";
+
+const HOUR_MS: i64 = 3600 * 1000;
+const DAY_MS: i64 = 24 * HOUR_MS;
+// upper bounds of ttl buckets
+const TTL_BUCKETS: [(&str, i64); 4] = [
+    ("<1h", HOUR_MS),
+    ("<1d", DAY_MS),
+    ("<7d", 7 * DAY_MS),
+    ("<30d", 30 * DAY_MS),
+];
+
+/// Analyzes memory usage of keys from rdb entries (from a rdb file or psync) without SCAN,
+/// outputs in report_dir:
+/// - memory_keys.csv: serialized size of each key bigger than data_size_threshold
+/// - memory_report.json: summary by db, type, encoding, key prefix and ttl, and the top N keys
+///
+/// serialized_size is the size of the key and its rdb payload, it is not the memory used by Redis
+/// (MEMORY USAGE) which can not be got without querying the source for each key
+pub struct RedisMemoryReportSinker {
+    pub report_dir: String,
+    pub data_size_threshold: usize,
+    pub top_n: usize,
+    pub prefix_delimiter: String,
+    pub report_interval_secs: u64,
+    pub monitor: Arc<Mutex<Monitor>>,
+    key_writer: BufWriter<File>,
+    report: MemoryReport,
+    last_report_time: Instant,
+}
+
+#[derive(Serialize, Default)]
+struct MemoryReport {
+    total: MemoryStat,
+    dbs: Vec<NamedMemoryStat>,
+    types: Vec<NamedMemoryStat>,
+    encodings: Vec<NamedMemoryStat>,
+    prefixes: Vec<NamedMemoryStat>,
+    ttls: Vec<NamedMemoryStat>,
+    top_keys: Vec<KeyMemoryInfo>,
+
+    #[serde(skip)]
+    stats: HashMap<StatGroup, HashMap<String, MemoryStat>>,
+}
+
+#[derive(Hash, PartialEq, Eq, Clone, Copy)]
+enum StatGroup {
+    Db,
+    Type,
+    Encoding,
+    Prefix,
+    Ttl,
+}
+
+#[derive(Serialize, Default, Clone)]
+struct MemoryStat {
+    keys: u64,
+    serialized_size: u64,
+}
+
+#[derive(Serialize)]
+struct NamedMemoryStat {
+    name: String,
+    keys: u64,
+    serialized_size: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct KeyMemoryInfo {
+    db_id: i64,
+    key_type: String,
+    encoding: String,
+    key: String,
+    serialized_size: usize,
+    elements: usize,
+    largest_element: usize,
+    expire_ms: i64,
+}
+
+#[async_trait]
+impl Sinker for RedisMemoryReportSinker {
+    async fn sink_raw(&mut self, data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut count = 0;
+        let mut data_size = 0;
+        for dt_item in data.iter() {
+            if let DtData::Redis { entry } = &dt_item.dt_data {
+                // only keys from rdb are analyzed
+                if !entry.is_base || entry.key.bytes.is_empty() {
+                    continue;
+                }
+                self.analyze_entry(entry)?;
+                count += 1;
+                data_size += entry.get_data_malloc_size();
+            }
+        }
+
+        // psync may keep running after the rdb is done, so the report is also written periodically
+        if self.last_report_time.elapsed() >= Duration::from_secs(self.report_interval_secs) {
+            self.write_report()?;
+        }
+        BaseSinker::update_serial_monitor(&mut self.monitor, count, data_size, start_time)
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.write_report()
+    }
+}
+
+impl RedisMemoryReportSinker {
+    pub fn new(
+        report_dir: &str,
+        data_size_threshold: usize,
+        top_n: usize,
+        prefix_delimiter: &str,
+        report_interval_secs: u64,
+        monitor: Arc<Mutex<Monitor>>,
+    ) -> anyhow::Result<Self> {
+        fs::create_dir_all(report_dir)
+            .with_context(|| format!("failed to create report dir: {}", report_dir))?;
+        let file = File::create(Path::new(report_dir).join(MEMORY_KEYS_FILE))?;
+        let mut key_writer = BufWriter::new(file);
+        writeln!(key_writer, "{}", MEMORY_KEYS_HEADER)?;
+
+        Ok(Self {
+            report_dir: report_dir.into(),
+            data_size_threshold,
+            top_n,
+            prefix_delimiter: prefix_delimiter.into(),
+            report_interval_secs,
+            monitor,
+            key_writer,
+            report: MemoryReport::default(),
+            last_report_time: Instant::now(),
+        })
+    }
+
+    fn analyze_entry(&mut self, entry: &RedisEntry) -> anyhow::Result<()> {
+        let key = entry.key.to_string();
+        let (elements, largest_element) = Self::get_element_info(&entry.value);
+        let info = KeyMemoryInfo {
+            db_id: entry.db_id,
+            key_type: entry.get_type(),
+            encoding: Self::get_encoding(entry.value_type_byte).into(),
+            serialized_size: Self::get_serialized_size(entry),
+            elements,
+            largest_element,
+            expire_ms: entry.expire_ms,
+            key,
+        };
+
+        if info.serialized_size >= self.data_size_threshold {
+            writeln!(
+                self.key_writer,
+                "{},{},{},{},{},{},{},{}",
+                info.db_id,
+                info.key_type,
+                info.encoding,
+                Self::escape_csv(&info.key),
+                info.serialized_size,
+                info.elements,
+                info.largest_element,
+                info.expire_ms
+            )?;
+        }
+
+        let prefix = match info.key.split_once(self.prefix_delimiter.as_str()) {
+            Some((prefix, _)) if !self.prefix_delimiter.is_empty() => prefix.to_string(),
+            // keys without delimiter
+            _ => String::new(),
+        };
+        let size = info.serialized_size as u64;
+        self.report.total.add(size);
+        self.report.add(StatGroup::Db, info.db_id.to_string(), size);
+        self.report
+            .add(StatGroup::Type, info.key_type.clone(), size);
+        self.report
+            .add(StatGroup::Encoding, info.encoding.clone(), size);
+        self.report.add(StatGroup::Prefix, prefix, size);
+        self.report.add(
+            StatGroup::Ttl,
+            Self::get_ttl_bucket(entry.expire_ms).into(),
+            size,
+        );
+        self.report.add_top_key(info, self.top_n);
+        Ok(())
+    }
+
+    fn get_serialized_size(entry: &RedisEntry) -> usize {
+        if entry.is_raw() {
+            // raw_bytes holds the rdb payload of the value
+            entry.key.bytes.len() + entry.raw_bytes.len()
+        } else {
+            entry.get_data_malloc_size()
+        }
+    }
+
+    fn get_element_info(value: &RedisObject) -> (usize, usize) {
+        match value {
+            RedisObject::String(v) => (1, v.value.bytes.len()),
+            RedisObject::List(v) => (
+                v.elements.len(),
+                v.elements.iter().map(|i| i.bytes.len()).max().unwrap_or(0),
+            ),
+            RedisObject::Set(v) => (
+                v.elements.len(),
+                v.elements.iter().map(|i| i.bytes.len()).max().unwrap_or(0),
+            ),
+            RedisObject::Hash(v) => (
+                v.value.len(),
+                v.value
+                    .iter()
+                    .map(|(field, value)| field.bytes.len().max(value.bytes.len()))
+                    .max()
+                    .unwrap_or(0),
+            ),
+            RedisObject::Zset(v) => (
+                v.elements.len(),
+                v.elements
+                    .iter()
+                    .map(|i| i.member.bytes.len())
+                    .max()
+                    .unwrap_or(0),
+            ),
+            RedisObject::Stream(v) => (
+                v.cmds
+                    .iter()
+                    .filter(|i| i.get_name().eq_ignore_ascii_case("xadd"))
+                    .count(),
+                0,
+            ),
+            RedisObject::Module(v) => (v.values.len(), 0),
+            RedisObject::Unknown => (0, 0),
+        }
+    }
+
+    fn get_encoding(type_byte: u8) -> &'static str {
+        match type_byte {
+            RDB_TYPE_STRING => "string",
+            RDB_TYPE_LIST => "linkedlist",
+            RDB_TYPE_SET | RDB_TYPE_HASH => "hashtable",
+            RDB_TYPE_ZSET | RDB_TYPE_ZSET_2 => "skiplist",
+            RDB_TYPE_MODULE | RDB_TYPE_MODULE_2 => "module",
+            RDB_TYPE_HASH_ZIPMAP => "zipmap",
+            RDB_TYPE_LIST_ZIPLIST | RDB_TYPE_ZSET_ZIPLIST | RDB_TYPE_HASH_ZIPLIST => "ziplist",
+            RDB_TYPE_SET_INTSET => "intset",
+            RDB_TYPE_LIST_QUICKLIST | RDB_TYPE_LIST_QUICKLIST_2 => "quicklist",
+            RDB_TYPE_HASH_LISTPACK | RDB_TYPE_ZSET_LISTPACK | RDB_TYPE_SET_LISTPACK => "listpack",
+            RDB_TYPE_STREAM_LISTPACKS
+            | RDB_TYPE_STREAM_LISTPACKS_2
+            | RDB_TYPE_STREAM_LISTPACKS_3 => "stream",
+            _ => "unknown",
+        }
+    }
+
+    fn get_ttl_bucket(expire_ms: i64) -> &'static str {
+        if expire_ms <= 0 {
+            return "no_ttl";
+        }
+        for (name, upper) in TTL_BUCKETS {
+            if expire_ms < upper {
+                return name;
+            }
+        }
+        ">=30d"
+    }
+
+    fn write_report(&mut self) -> anyhow::Result<()> {
+        self.last_report_time = Instant::now();
+        self.key_writer.flush()?;
+
+        self.report.finish(self.top_n);
+        let report_file = Path::new(&self.report_dir).join(MEMORY_REPORT_FILE);
+        fs::write(&report_file, serde_json::to_string_pretty(&self.report)?)?;
+        log_info!(
+            "memory report written, keys: {}, serialized_size: {}, report: {}",
+            self.report.total.keys,
+            self.report.total.serialized_size,
+            report_file.to_string_lossy()
+        );
+        Ok(())
+    }
+
+    fn escape_csv(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+impl MemoryStat {
+    fn add(&mut self, size: u64) {
+        self.keys += 1;
+        self.serialized_size += size;
+    }
+}
+
+impl MemoryReport {
+    fn add(&mut self, group: StatGroup, name: String, size: u64) {
+        self.stats
+            .entry(group)
+            .or_default()
+            .entry(name)
+            .or_default()
+            .add(size);
+    }
+
+    fn add_top_key(&mut self, info: KeyMemoryInfo, top_n: usize) {
+        if top_n == 0 {
+            return;
+        }
+        self.top_keys.push(info);
+        // truncate in batches to avoid sorting for each key
+        if self.top_keys.len() >= top_n * 2 {
+            self.truncate_top_keys(top_n);
+        }
+    }
+
+    fn truncate_top_keys(&mut self, top_n: usize) {
+        self.top_keys
+            .sort_by(|a, b| b.serialized_size.cmp(&a.serialized_size));
+        self.top_keys.truncate(top_n);
+    }
+
+    // may be called multiple times, the summaries are rebuilt from stats each time
+    fn finish(&mut self, top_n: usize) {
+        self.truncate_top_keys(top_n);
+        self.dbs = self.get_sorted_stats(StatGroup::Db, usize::MAX);
+        self.types = self.get_sorted_stats(StatGroup::Type, usize::MAX);
+        self.encodings = self.get_sorted_stats(StatGroup::Encoding, usize::MAX);
+        // prefixes may be too many
+        self.prefixes = self.get_sorted_stats(StatGroup::Prefix, top_n);
+        self.ttls = self.get_sorted_stats(StatGroup::Ttl, usize::MAX);
+    }
+
+    fn get_sorted_stats(&self, group: StatGroup, limit: usize) -> Vec<NamedMemoryStat> {
+        let mut stats: Vec<NamedMemoryStat> = self
+            .stats
+            .get(&group)
+            .map(|stats| {
+                stats
+                    .iter()
+                    .map(|(name, stat)| NamedMemoryStat {
+                        name: name.clone(),
+                        keys: stat.keys,
+                        serialized_size: stat.serialized_size,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        stats.sort_by(|a, b| {
+            b.serialized_size
+                .cmp(&a.serialized_size)
+                .then(a.name.cmp(&b.name))
+        });
+        stats.truncate(limit);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::redis::redis_object::{HashObject, RedisString, StringObject};
+
+    use super::*;
+
+    fn string_entry(db_id: i64, key: &str, value: &str, expire_ms: i64) -> RedisEntry {
+        let mut obj = StringObject::new();
+        obj.key = RedisString::from(key.to_string());
+        obj.value = RedisString::from(value.to_string());
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.db_id = db_id;
+        entry.key = obj.key.clone();
+        entry.value = RedisObject::String(obj);
+        entry.value_type_byte = RDB_TYPE_STRING;
+        entry.expire_ms = expire_ms;
+        entry
+    }
+
+    #[test]
+    fn test_memory_report() {
+        let report_dir = std::env::temp_dir().join(format!(
+            "ape_dts_test_memory_report_{}",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let report_dir = report_dir.to_string_lossy().to_string();
+        let monitor = Arc::new(Mutex::new(Monitor::new("sinker", 1, 100, 1)));
+        let mut sinker =
+            RedisMemoryReportSinker::new(&report_dir, 20, 2, ":", 60, monitor).unwrap();
+
+        let mut hash = HashObject::new();
+        hash.key = RedisString::from("user:1".to_string());
+        hash.value = HashMap::from([
+            ("name".to_string().into(), "abcdefgh".to_string().into()),
+            ("last_login_at".to_string().into(), "1".to_string().into()),
+        ]);
+        let mut hash_entry = RedisEntry::new();
+        hash_entry.is_base = true;
+        hash_entry.key = hash.key.clone();
+        hash_entry.value = RedisObject::Hash(hash);
+        hash_entry.value_type_byte = RDB_TYPE_HASH_LISTPACK;
+
+        let entries = vec![
+            string_entry(0, "user:2", "v", 0),
+            string_entry(0, "order,1", "valuevalue", 2 * DAY_MS),
+            string_entry(1, "user:3", "vvvvvvvvvvvvvvvvvvvv", 1000),
+            hash_entry,
+        ];
+        for entry in entries.iter() {
+            sinker.analyze_entry(entry).unwrap();
+        }
+        sinker.write_report().unwrap();
+        // the report can be written again, e.g. periodically with psync
+        sinker.write_report().unwrap();
+
+        let report = &sinker.report;
+        assert_eq!(report.total.keys, 4);
+        assert_eq!(report.prefixes[0].name, "user");
+        assert_eq!(report.prefixes[0].keys, 3);
+        assert_eq!(report.prefixes.len(), 2);
+        assert_eq!(report.encodings[0].name, "string");
+        assert_eq!(report.encodings[1].name, "listpack");
+        assert_eq!(report.top_keys.len(), 2);
+        assert_eq!(report.top_keys[0].key, "user:1");
+        assert_eq!(report.top_keys[0].elements, 2);
+        // the longest field is longer than all values
+        assert_eq!(report.top_keys[0].largest_element, 13);
+        assert_eq!(report.top_keys[1].key, "user:3");
+        let ttls: Vec<&str> = report.ttls.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(ttls, vec!["no_ttl", "<1h", "<7d"]);
+
+        let keys = fs::read_to_string(Path::new(&report_dir).join(MEMORY_KEYS_FILE)).unwrap();
+        let lines: Vec<&str> = keys.lines().collect();
+        assert_eq!(lines[0], MEMORY_KEYS_HEADER);
+        // user:2 is smaller than data_size_threshold
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("0,string,string,\"order,1\","));
+        assert!(fs::metadata(Path::new(&report_dir).join(MEMORY_REPORT_FILE)).is_ok());
+        fs::remove_dir_all(&report_dir).unwrap();
+    }
+
+    #[test]
+    fn test_serialized_size() {
+        let mut entry = string_entry(0, "key", "value", 0);
+        assert_eq!(RedisMemoryReportSinker::get_serialized_size(&entry), 11);
+
+        // the rdb payload is used if the entry is from rdb
+        entry.raw_bytes = vec![0; 100];
+        assert_eq!(RedisMemoryReportSinker::get_serialized_size(&entry), 103);
+    }
+}
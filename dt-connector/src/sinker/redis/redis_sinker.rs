@@ -5,11 +5,17 @@ use std::time::Instant;
 
 use anyhow::bail;
 use async_trait::async_trait;
+use dt_common::config::config_enums::ConflictPolicyEnum;
 use dt_common::error::Error;
+use dt_common::log_info;
+use dt_common::log_warn;
+use dt_common::meta::ddl_meta::ddl_data::DdlData;
 use dt_common::meta::dt_data::DtData;
 use dt_common::meta::dt_data::DtItem;
+use dt_common::meta::position::Position;
 use dt_common::meta::rdb_meta_manager::RdbMetaManager;
 use dt_common::meta::redis::command::cmd_encoder::CmdEncoder;
+use dt_common::meta::redis::redis_entry::RedisEntry;
 use dt_common::meta::redis::redis_object::RedisCmd;
 use dt_common::meta::redis::redis_object::RedisObject;
 use dt_common::meta::redis::redis_write_method::RedisWriteMethod;
@@ -18,6 +24,7 @@ use dt_common::meta::row_type::RowType;
 use dt_common::monitor::monitor::Monitor;
 use redis::Connection;
 use redis::ConnectionLike;
+use redis::Value;
 
 use crate::call_batch_fn;
 use crate::data_marker::DataMarker;
@@ -25,6 +32,7 @@ use crate::sinker::base_sinker::BaseSinker;
 use crate::Sinker;
 
 use super::entry_rewriter::EntryRewriter;
+use super::redis_stream_writer::RedisStreamWriter;
 
 pub struct RedisSinker {
     pub id: String,
@@ -33,9 +41,17 @@ pub struct RedisSinker {
     pub now_db_id: i64,
     pub version: f32,
     pub method: RedisWriteMethod,
+    pub rewrite_chunk_size: usize,
+    pub max_restore_bytes: usize,
+    // keys existing in target are only overwritten by snapshot entries with replace
+    pub conflict_policy: ConflictPolicyEnum,
+    // the last big key skipped for conflict, its following chunks are skipped too
+    pub ignored_key: Option<(i64, String)>,
     pub meta_manager: Option<RdbMetaManager>,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
+    // rows are written to streams instead of hashes, for sink_type=stream
+    pub stream_writer: Option<RedisStreamWriter>,
 }
 
 #[async_trait]
@@ -66,6 +82,42 @@ impl Sinker for RedisSinker {
         Ok(())
     }
 
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        let mut cmds = Vec::new();
+        if let Some(stream_writer) = &mut self.stream_writer {
+            for ddl_data in data {
+                if let Some(cmd) = stream_writer.ddl_data_to_cmd(ddl_data).await? {
+                    cmds.push(cmd);
+                }
+            }
+        }
+        self.batch_sink(&cmds).await
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        if let Some(stream_writer) = &mut self.stream_writer {
+            stream_writer.encoder.refresh_meta(&data);
+        }
+        Ok(())
+    }
+
+    async fn commit_position(&mut self, position: &Position) -> anyhow::Result<()> {
+        let cmd = self
+            .stream_writer
+            .as_ref()
+            .and_then(|i| i.position_to_cmd(position));
+        if let Some(cmd) = cmd {
+            self.conn.req_packed_command(&CmdEncoder::encode(&cmd))?;
+        }
+        Ok(())
+    }
+
+    fn needs_row_position(&self) -> bool {
+        self.stream_writer
+            .as_ref()
+            .is_some_and(|i| i.encoder.needs_row_position())
+    }
+
     fn get_id(&self) -> String {
         self.id.clone()
     }
@@ -82,10 +134,14 @@ impl RedisSinker {
         let start_time = Instant::now();
         let mut data_size = 0;
 
+        let data = &mut data[start_index..start_index + batch_size];
+        let writes = self.check_conflicts(data)?;
         let mut cmds = Vec::new();
-        for dt_item in data.iter_mut().skip(start_index).take(batch_size) {
+        for (dt_item, write) in data.iter_mut().zip(writes) {
             data_size += dt_item.dt_data.get_data_size();
-            cmds.extend_from_slice(&self.rewrite_entry(&mut dt_item.dt_data)?);
+            if write {
+                cmds.extend_from_slice(&self.rewrite_entry(&mut dt_item.dt_data)?);
+            }
         }
 
         self.batch_sink(&cmds).await?;
@@ -97,10 +153,13 @@ impl RedisSinker {
         let start_time = Instant::now();
         let mut data_size = 0;
 
-        for dt_item in data.iter_mut() {
+        let writes = self.check_conflicts(data)?;
+        for (dt_item, write) in data.iter_mut().zip(writes) {
             data_size += dt_item.dt_data.get_data_size();
-            let cmds = self.rewrite_entry(&mut dt_item.dt_data)?;
-            self.serial_sink(cmds).await?;
+            if write {
+                let cmds = self.rewrite_entry(&mut dt_item.dt_data)?;
+                self.serial_sink(cmds).await?;
+            }
         }
 
         BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), data_size, start_time)
@@ -117,39 +176,164 @@ impl RedisSinker {
                 self.now_db_id = entry.db_id;
             }
 
+            let replace = self.conflict_policy == ConflictPolicyEnum::Replace;
+            // chunks of big keys have no dump payload, they can only be rewritten
+            if entry.chunk.is_some() {
+                if let Some(rewrite_cmds) = EntryRewriter::rewrite_value(
+                    entry,
+                    self.rewrite_chunk_size,
+                    self.version,
+                    replace,
+                )? {
+                    cmds.extend(rewrite_cmds);
+                }
+                return Ok(cmds);
+            }
+
             match self.method {
                 RedisWriteMethod::Restore => {
                     if entry.is_raw() {
-                        let cmd = EntryRewriter::rewrite_as_restore(entry, self.version)?;
+                        match self.rewrite_big_key(entry)? {
+                            Some(rewrite_cmds) => cmds.extend(rewrite_cmds),
+                            None => {
+                                let cmd = EntryRewriter::rewrite_as_restore(
+                                    entry,
+                                    self.version,
+                                    replace,
+                                )?;
+                                cmds.push(cmd);
+                            }
+                        }
+                    } else if entry.is_base && EntryRewriter::is_function_load(&entry.cmd) {
+                        let cmd = EntryRewriter::rewrite_function_as_restore(&entry.cmd)?;
                         cmds.push(cmd);
                     } else {
                         cmds.push(entry.cmd.clone());
                     }
                 }
 
-                RedisWriteMethod::Rewrite => {
-                    let mut rewrite_cmds = match entry.value {
-                        RedisObject::String(ref mut obj) => EntryRewriter::rewrite_string(obj),
-                        RedisObject::List(ref mut obj) => EntryRewriter::rewrite_list(obj),
-                        RedisObject::Set(ref mut obj) => EntryRewriter::rewrite_set(obj),
-                        RedisObject::Hash(ref mut obj) => EntryRewriter::rewrite_hash(obj),
-                        RedisObject::Zset(ref mut obj) => EntryRewriter::rewrite_zset(obj),
-                        RedisObject::Stream(ref mut obj) => Ok(obj.cmds.drain(..).collect()),
-                        RedisObject::Module(_) => {
-                            let cmd = EntryRewriter::rewrite_as_restore(entry, self.version)?;
-                            Ok(vec![cmd])
-                        }
-                        _ => bail! {Error::SinkerError("rewrite not implemented".into())},
-                    }?;
-                    if let Some(expire_cmd) = EntryRewriter::rewrite_expire(entry)? {
-                        rewrite_cmds.push(expire_cmd)
-                    }
-                    cmds.extend(rewrite_cmds);
+                // cmds (e.g. lua scripts, function libraries, cdc cmds) are not rewritten
+                RedisWriteMethod::Rewrite if !entry.is_raw() => {
+                    cmds.push(entry.cmd.clone());
                 }
+
+                RedisWriteMethod::Rewrite => match EntryRewriter::rewrite_value(
+                    entry,
+                    self.rewrite_chunk_size,
+                    self.version,
+                    replace,
+                )? {
+                    Some(rewrite_cmds) => cmds.extend(rewrite_cmds),
+                    // modules not supported by ModuleRewriter
+                    None if matches!(entry.value, RedisObject::Module(_)) => {
+                        let cmd = EntryRewriter::rewrite_as_restore(entry, self.version, replace)?;
+                        cmds.push(cmd);
+                    }
+                    None => bail! {Error::SinkerError("rewrite not implemented".into())},
+                },
             }
         }
         Ok(cmds)
     }
+
+    /// Payloads of RESTORE bigger than max_restore_bytes may exceed proto-max-bulk-len
+    /// or block the target for long, rewrite them in chunks instead
+    fn rewrite_big_key(&self, entry: &mut RedisEntry) -> anyhow::Result<Option<Vec<RedisCmd>>> {
+        if self.max_restore_bytes == 0 || entry.raw_bytes.len() <= self.max_restore_bytes {
+            return Ok(None);
+        }
+
+        let rewrite_cmds = EntryRewriter::rewrite_value(
+            entry,
+            self.rewrite_chunk_size,
+            self.version,
+            self.conflict_policy == ConflictPolicyEnum::Replace,
+        )?;
+        if rewrite_cmds.is_some() {
+            log_info!(
+                "rewrite big key instead of restore, key: {}, type: {}, size: {}",
+                entry.key,
+                entry.get_type(),
+                entry.raw_bytes.len()
+            );
+        }
+        Ok(rewrite_cmds)
+    }
+
+    /// Snapshot keys existing in target are skipped with conflict_policy=ignore,
+    /// or fail the task with conflict_policy=interrupt.
+    /// Returns whether each item should be written
+    fn check_conflicts(&mut self, data: &[DtItem]) -> anyhow::Result<Vec<bool>> {
+        let mut writes = vec![true; data.len()];
+        if self.conflict_policy == ConflictPolicyEnum::Replace {
+            return Ok(writes);
+        }
+
+        // check all keys by one request, following chunks of a big key
+        // are written or skipped along with its first chunk
+        let mut entries = Vec::new();
+        let mut packed_cmds = Vec::new();
+        for (i, dt_item) in data.iter().enumerate() {
+            if let DtData::Redis { entry } = &dt_item.dt_data {
+                if !entry.is_raw() && entry.chunk.is_none() {
+                    continue;
+                }
+
+                let is_first_chunk = entry.chunk.as_ref().map_or(true, |chunk| chunk.offset == 0);
+                if is_first_chunk {
+                    let select_cmd = RedisCmd::from_str_args(&["SELECT", &entry.db_id.to_string()]);
+                    let mut exists_cmd = RedisCmd::from_str_args(&["EXISTS"]);
+                    exists_cmd.add_redis_arg(&entry.key);
+                    packed_cmds.extend_from_slice(&CmdEncoder::encode(&select_cmd));
+                    packed_cmds.extend_from_slice(&CmdEncoder::encode(&exists_cmd));
+                    self.now_db_id = entry.db_id;
+                }
+                entries.push((i, entry, is_first_chunk));
+            }
+        }
+
+        let mut results = Vec::new();
+        if !packed_cmds.is_empty() {
+            let cmd_count = entries.iter().filter(|(_, _, i)| *i).count() * 2;
+            results = match self.conn.req_packed_commands(&packed_cmds, 0, cmd_count) {
+                Ok(results) => results,
+                Err(error) => bail! {Error::SinkerError(format!(
+                    "check existing keys failed, error: {:?}",
+                    error
+                ))},
+            };
+        }
+
+        // results of EXISTS
+        let mut results = results.into_iter().skip(1).step_by(2);
+        for (i, entry, is_first_chunk) in entries {
+            let key = (entry.db_id, entry.key.to_string());
+            if !is_first_chunk {
+                writes[i] = self.ignored_key.as_ref() != Some(&key);
+                continue;
+            }
+            if results.next() != Some(Value::Int(1)) {
+                continue;
+            }
+
+            if self.conflict_policy == ConflictPolicyEnum::Interrupt {
+                bail! {Error::SinkerError(format!(
+                    "key already exists in target, db: {}, key: {}",
+                    key.0, key.1
+                ))}
+            }
+            log_warn!(
+                "key already exists in target, ignore it, db: {}, key: {}",
+                key.0,
+                key.1
+            );
+            writes[i] = false;
+            if entry.chunk.as_ref().is_some_and(|chunk| !chunk.is_last) {
+                self.ignored_key = Some(key);
+            }
+        }
+        Ok(writes)
+    }
 }
 
 /// sink dml
@@ -189,7 +373,14 @@ impl RedisSinker {
         BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), data_size, start_time)
     }
 
-    async fn dml_to_redis_cmd(&mut self, row_data: &RowData) -> anyhow::Result<Option<RedisCmd>> {
+    async fn dml_to_redis_cmd(
+        &mut self,
+        row_data: &mut RowData,
+    ) -> anyhow::Result<Option<RedisCmd>> {
+        if let Some(stream_writer) = &mut self.stream_writer {
+            return Ok(Some(stream_writer.row_data_to_cmd(row_data).await?));
+        }
+
         if self.meta_manager.is_none() {
             return Ok(None);
         }
@@ -0,0 +1,43 @@
+use std::{
+    fs::File,
+    io::BufWriter,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use async_trait::async_trait;
+use dt_common::{
+    meta::dt_data::{DtData, DtItem},
+    monitor::monitor::Monitor,
+};
+
+use crate::{sinker::base_sinker::BaseSinker, Sinker};
+
+use super::rdb_writer::RdbWriter;
+
+pub struct RedisSnapshotFileSinker {
+    pub writer: RdbWriter<BufWriter<File>>,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+#[async_trait]
+impl Sinker for RedisSnapshotFileSinker {
+    async fn sink_raw(&mut self, data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut count = 0;
+        let mut data_size = 0;
+        for dt_item in data.iter() {
+            if let DtData::Redis { entry } = &dt_item.dt_data {
+                if self.writer.write_entry(entry)? {
+                    count += 1;
+                    data_size += entry.get_data_malloc_size();
+                }
+            }
+        }
+        BaseSinker::update_serial_monitor(&mut self.monitor, count, data_size, start_time)
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.writer.finish()
+    }
+}
@@ -64,6 +64,9 @@ impl Sinker for RedisStatisticSinker {
                         };
                         log_statistic!("{}", json!(info).to_string());
                     }
+
+                    // handled by RedisMemoryReportSinker
+                    RedisStatisticType::Memory => {}
                 }
             }
         }
@@ -0,0 +1,84 @@
+use std::str::FromStr;
+
+use dt_common::{
+    log_info,
+    meta::{
+        ddl_meta::ddl_data::DdlData, position::Position, redis::redis_object::RedisCmd,
+        row_data::RowData,
+    },
+    utils::redis_util::RedisUtil,
+};
+use redis::{Connection, Value};
+
+use crate::{rdb_router::RdbRouter, sinker::message_encoder::MessageEncoder};
+
+const KEY_FIELD: &str = "key";
+const VALUE_FIELD: &str = "value";
+
+/// Rewrites rows and ddls as XADD cmds to streams named by topic_map, each stream entry
+/// has 2 fields: key (the primary / unique key of the row) and value (the encoded message)
+pub struct RedisStreamWriter {
+    pub router: RdbRouter,
+    pub encoder: MessageEncoder,
+    // approximate max length of streams, 0 for no trimming
+    pub max_len: usize,
+    // the key storing the last checkpoint position, empty for not storing
+    pub position_key: String,
+}
+
+impl RedisStreamWriter {
+    pub async fn row_data_to_cmd(&mut self, row_data: &mut RowData) -> anyhow::Result<RedisCmd> {
+        row_data.convert_raw_string();
+        let key = self.encoder.encode_key(row_data).await?;
+        let payload = self.encoder.encode_row(row_data).await?;
+        let stream = self.router.get_topic(&row_data.schema, &row_data.tb);
+        Ok(Self::build_xadd_cmd(stream, self.max_len, &key, payload))
+    }
+
+    pub async fn ddl_data_to_cmd(&mut self, ddl_data: DdlData) -> anyhow::Result<Option<RedisCmd>> {
+        let stream = self.router.get_topic(&ddl_data.default_schema, "");
+        let payload = self.encoder.encode_ddl(ddl_data).await?;
+        Ok(payload.map(|payload| Self::build_xadd_cmd(stream, self.max_len, "", payload)))
+    }
+
+    /// All data before the position has been sinked when it is committed
+    pub fn position_to_cmd(&self, position: &Position) -> Option<RedisCmd> {
+        if self.position_key.is_empty() {
+            return None;
+        }
+        Some(RedisCmd::from_str_args(&[
+            "SET",
+            &self.position_key,
+            &position.to_string(),
+        ]))
+    }
+
+    pub fn read_position(conn: &mut Connection, position_key: &str) -> anyhow::Result<Position> {
+        let position = match RedisUtil::send_cmd(conn, &["GET", position_key])? {
+            Value::BulkString(v) => Position::from_str(&String::from_utf8(v)?)?,
+            _ => Position::None,
+        };
+        log_info!(
+            "position stored in redis key: {}, position: {}",
+            position_key,
+            position
+        );
+        Ok(position)
+    }
+
+    fn build_xadd_cmd(stream: &str, max_len: usize, key: &str, payload: Vec<u8>) -> RedisCmd {
+        let mut cmd = RedisCmd::from_str_args(&["XADD", stream]);
+        if max_len > 0 {
+            // trimming with ~ only removes whole macro nodes, which is much more efficient
+            cmd.add_str_arg("MAXLEN");
+            cmd.add_str_arg("~");
+            cmd.add_str_arg(&max_len.to_string());
+        }
+        cmd.add_str_arg("*");
+        cmd.add_str_arg(KEY_FIELD);
+        cmd.add_str_arg(key);
+        cmd.add_str_arg(VALUE_FIELD);
+        cmd.add_arg(payload);
+        cmd
+    }
+}
@@ -0,0 +1 @@
+pub mod sqs_sinker;
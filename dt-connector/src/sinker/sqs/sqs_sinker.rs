@@ -0,0 +1,301 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use dt_common::{
+    config::config_enums::{MessageFormat, SqsMessageGroupBy},
+    error::Error,
+    log_warn,
+    meta::{ddl_meta::ddl_data::DdlData, row_data::RowData},
+    monitor::monitor::Monitor,
+    utils::kafka_util::KafkaUtil,
+};
+use rusoto_sqs::{SendMessageBatchRequest, SendMessageBatchRequestEntry, Sqs, SqsClient};
+
+use crate::{
+    call_batch_fn,
+    sinker::{base_sinker::BaseSinker, message_encoder::MessageEncoder},
+    Sinker,
+};
+
+// limits of SendMessageBatch
+const MAX_BATCH_ENTRIES: usize = 10;
+const MAX_BATCH_BYTES: usize = 256 * 1024;
+// limit of MessageGroupId
+const MAX_GROUP_ID_LEN: usize = 128;
+const RETRY_INTERVAL_MILLIS: u64 = 200;
+
+pub struct SqsSinker {
+    pub batch_size: usize,
+    pub queue_url: String,
+    pub client: SqsClient,
+    pub encoder: MessageEncoder,
+    pub message_group_by: SqsMessageGroupBy,
+    pub max_retries: u32,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+struct SqsMessage {
+    body: String,
+    group_id: String,
+}
+
+#[async_trait]
+impl Sinker for SqsSinker {
+    async fn sink_dml(&mut self, data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut data = if self.is_fifo() && self.message_group_by == SqsMessageGroupBy::Key {
+            self.encoder
+                .split_key_changed_updates(data, |_| false)
+                .await?
+        } else {
+            data
+        };
+        call_batch_fn!(self, data, Self::send_dml);
+        Ok(())
+    }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        let mut messages = Vec::new();
+        for ddl_data in data {
+            let group = match ddl_data.get_schema_tb() {
+                (schema, tb) if tb.is_empty() => schema,
+                (schema, tb) => format!("{}.{}", schema, tb),
+            };
+            if let Some(payload) = self.encoder.encode_ddl(ddl_data).await? {
+                messages.push(SqsMessage {
+                    body: self.to_body(payload)?,
+                    group_id: Self::to_group_id(&group),
+                });
+            }
+        }
+        self.send_messages(messages).await
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        self.encoder.refresh_meta(&data);
+        Ok(())
+    }
+
+    fn needs_row_position(&self) -> bool {
+        self.encoder.needs_row_position()
+    }
+}
+
+impl SqsSinker {
+    async fn send_dml(
+        &mut self,
+        data: &mut [RowData],
+        sinked_count: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+
+        let mut messages = Vec::new();
+        for row_data in data.iter_mut().skip(sinked_count).take(batch_size) {
+            data_size += row_data.data_size;
+
+            row_data.convert_raw_string();
+            let group_id = self.get_group_id(row_data).await?;
+            let payload = self.encoder.encode_row(row_data).await?;
+            messages.push(SqsMessage {
+                body: self.to_body(payload)?,
+                group_id,
+            });
+        }
+
+        self.send_messages(messages).await?;
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    async fn send_messages(&self, messages: Vec<SqsMessage>) -> anyhow::Result<()> {
+        let is_fifo = self.is_fifo();
+        let mut entries = Vec::new();
+        for (i, message) in messages.into_iter().enumerate() {
+            let mut entry = SendMessageBatchRequestEntry {
+                id: i.to_string(),
+                message_body: message.body,
+                ..Default::default()
+            };
+            if is_fifo {
+                // the deduplication id is kept in retries, so messages sent by a failed
+                // request are not duplicated when the request is retried
+                entry.message_group_id = Some(message.group_id);
+                entry.message_deduplication_id = Some(uuid::Uuid::new_v4().simple().to_string());
+            }
+            entries.push(entry);
+        }
+
+        let sizes: Vec<usize> = entries.iter().map(|i| i.message_body.len()).collect();
+        let mut remaining = entries.into_iter();
+        for count in Self::split_batches(&sizes)? {
+            let batch: Vec<SendMessageBatchRequestEntry> = remaining.by_ref().take(count).collect();
+            self.send_batch(batch).await?;
+        }
+        Ok(())
+    }
+
+    /// Retries entries failed for server side errors. For FIFO queues, all entries from the first
+    /// failed one are retried to keep the order, the succeeded ones are deduplicated by the queue
+    async fn send_batch(
+        &self,
+        mut entries: Vec<SendMessageBatchRequestEntry>,
+    ) -> anyhow::Result<()> {
+        let mut retry_times = 0;
+        loop {
+            let request = SendMessageBatchRequest {
+                queue_url: self.queue_url.clone(),
+                entries: entries.clone(),
+            };
+
+            let error = match self.client.send_message_batch(request).await {
+                Ok(result) if result.failed.is_empty() => return Ok(()),
+
+                Ok(result) => {
+                    if let Some(failed) = result.failed.iter().find(|i| i.sender_fault) {
+                        bail! {Error::SinkerError(format!(
+                            "failed to send message to sqs queue: {}, code: {}, error: {:?}",
+                            self.queue_url, failed.code, failed.message
+                        ))}
+                    }
+
+                    let is_failed = |entry: &SendMessageBatchRequestEntry| {
+                        result.failed.iter().any(|i| i.id == entry.id)
+                    };
+                    if self.is_fifo() {
+                        let first_failed = entries.iter().position(is_failed).unwrap_or(0);
+                        entries.drain(..first_failed);
+                    } else {
+                        entries.retain(is_failed);
+                    }
+                    format!("{:?}", result.failed)
+                }
+
+                Err(err) => err.to_string(),
+            };
+
+            retry_times += 1;
+            if retry_times > self.max_retries {
+                bail! {Error::SinkerError(format!(
+                    "failed to send messages to sqs queue: {} after {} retries, error: {}",
+                    self.queue_url, self.max_retries, error
+                ))}
+            }
+
+            log_warn!(
+                "failed to send messages to sqs queue: {}, retry times: {}, error: {}",
+                self.queue_url,
+                retry_times,
+                error
+            );
+            let interval = RETRY_INTERVAL_MILLIS * 2u64.pow(retry_times - 1);
+            tokio::time::sleep(Duration::from_millis(interval)).await;
+        }
+    }
+
+    /// Splits messages into batches of at most 10 messages and 256KB, returns message counts
+    fn split_batches(sizes: &[usize]) -> anyhow::Result<Vec<usize>> {
+        let mut batches = Vec::new();
+        let (mut count, mut bytes) = (0, 0);
+        for size in sizes.iter() {
+            if *size > MAX_BATCH_BYTES {
+                bail! {Error::SinkerError(format!(
+                    "message size: {} exceeds the limit of sqs: {}",
+                    size, MAX_BATCH_BYTES
+                ))}
+            }
+
+            if count == MAX_BATCH_ENTRIES || bytes + size > MAX_BATCH_BYTES {
+                batches.push(count);
+                (count, bytes) = (0, 0);
+            }
+            count += 1;
+            bytes += size;
+        }
+
+        if count > 0 {
+            batches.push(count);
+        }
+        Ok(batches)
+    }
+
+    /// Message bodies of sqs are texts, avro messages are base64 encoded
+    fn to_body(&self, payload: Vec<u8>) -> anyhow::Result<String> {
+        if self.encoder.message_format == MessageFormat::Avro {
+            return Ok(STANDARD.encode(payload));
+        }
+        Ok(String::from_utf8(payload)?)
+    }
+
+    /// Rows of tables without primary / unique keys are grouped by table
+    async fn get_group_id(&mut self, row_data: &RowData) -> anyhow::Result<String> {
+        let table = format!("{}.{}", row_data.schema, row_data.tb);
+        if !self.is_fifo() || self.message_group_by == SqsMessageGroupBy::Table {
+            return Ok(Self::to_group_id(&table));
+        }
+
+        let key = self
+            .encoder
+            .avro_converter
+            .row_data_to_avro_key(row_data)
+            .await?;
+        if key.is_empty() {
+            return Ok(Self::to_group_id(&table));
+        }
+        Ok(Self::to_group_id(&format!("{}:{}", table, key)))
+    }
+
+    /// Group ids longer than 128 or with chars other than alphanumerics and punctuations
+    /// are replaced by their murmur2 hashes
+    fn to_group_id(group: &str) -> String {
+        if group.len() <= MAX_GROUP_ID_LEN && group.chars().all(|c| c.is_ascii_graphic()) {
+            return group.to_string();
+        }
+        format!("{:08x}", KafkaUtil::murmur2(group.as_bytes()))
+    }
+
+    fn is_fifo(&self) -> bool {
+        self.queue_url.ends_with(".fifo")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_batches() {
+        assert!(SqsSinker::split_batches(&[]).unwrap().is_empty());
+        assert_eq!(SqsSinker::split_batches(&[1; 25]).unwrap(), vec![10, 10, 5]);
+
+        let half = MAX_BATCH_BYTES / 2;
+        assert_eq!(
+            SqsSinker::split_batches(&[half, half, 1, half - 1, MAX_BATCH_BYTES]).unwrap(),
+            vec![2, 2, 1]
+        );
+        assert!(SqsSinker::split_batches(&[1, MAX_BATCH_BYTES + 1]).is_err());
+    }
+
+    #[test]
+    fn test_to_group_id() {
+        assert_eq!(SqsSinker::to_group_id("test_db.tb_1"), "test_db.tb_1");
+        assert_eq!(
+            SqsSinker::to_group_id(r#"test_db.tb_1:["1","a"]"#),
+            r#"test_db.tb_1:["1","a"]"#
+        );
+
+        let hashed = SqsSinker::to_group_id("test_db.tb_1:a b");
+        assert_eq!(hashed.len(), 8);
+        assert_eq!(hashed, SqsSinker::to_group_id("test_db.tb_1:a b"));
+        assert_eq!(SqsSinker::to_group_id(&"a".repeat(129)).len(), 8);
+    }
+}
@@ -277,7 +277,7 @@ impl StarrocksStructSinker {
             Err(error) => {
                 log_error!("ddl failed, error: {}", error);
                 match self.conflict_policy {
-                    ConflictPolicyEnum::Interrupt => bail! {error},
+                    ConflictPolicyEnum::Interrupt | ConflictPolicyEnum::Replace => bail! {error},
                     ConflictPolicyEnum::Ignore => {}
                 }
             }
@@ -19,7 +19,7 @@ use dt_common::{
     },
 };
 use dt_common::{
-    log_info, log_position,
+    log_info, log_position, log_warn,
     monitor::{counter_type::CounterType, monitor::Monitor},
     utils::time_util::TimeUtil,
 };
@@ -28,6 +28,9 @@ use dt_parallelizer::Parallelizer;
 
 use crate::{lua_processor::LuaProcessor, Pipeline};
 
+// a checkpoint is deferred by an unfinished source transaction for at most this many intervals
+const MAX_DEFERRED_CHECKPOINTS: u64 = 10;
+
 pub struct BasePipeline {
     pub buffer: Arc<DtQueue>,
     pub parallelizer: Box<dyn Parallelizer + Send>,
@@ -70,6 +73,7 @@ impl Pipeline for BasePipeline {
         let mut last_checkpoint_time = Instant::now();
         let mut last_received_position = Position::None;
         let mut last_commit_position = Position::None;
+        let needs_row_position = self.needs_row_position().await;
 
         while !self.shut_down.load(Ordering::Acquire) || !self.buffer.is_empty() {
             // to avoid too many sub counters, only add counter when buffer is not empty
@@ -100,7 +104,7 @@ impl Pipeline for BasePipeline {
             // process all row_datas in buffer at a time
             let (count, last_received, last_commit) = match self.get_sink_method(&data) {
                 SinkMethod::Ddl => self.sink_ddl(data).await?,
-                SinkMethod::Dml => self.sink_dml(data).await?,
+                SinkMethod::Dml => self.sink_dml(data, needs_row_position).await?,
                 SinkMethod::Raw => self.sink_raw(data).await?,
                 SinkMethod::Struct => self.sink_struct(data).await?,
             };
@@ -113,11 +117,13 @@ impl Pipeline for BasePipeline {
                 last_commit_position = position.to_owned();
             }
 
-            last_checkpoint_time = self.record_checkpoint(
-                Some(last_checkpoint_time),
-                &last_received_position,
-                &last_commit_position,
-            );
+            last_checkpoint_time = self
+                .record_checkpoint(
+                    Some(last_checkpoint_time),
+                    &last_received_position,
+                    &last_commit_position,
+                )
+                .await?;
 
             self.monitor
                 .lock()
@@ -128,7 +134,8 @@ impl Pipeline for BasePipeline {
             TimeUtil::sleep_millis(1).await;
         }
 
-        self.record_checkpoint(None, &last_received_position, &last_commit_position);
+        self.record_checkpoint(None, &last_received_position, &last_commit_position)
+            .await?;
         Ok(())
     }
 }
@@ -164,8 +171,10 @@ impl BasePipeline {
     async fn sink_dml(
         &mut self,
         all_data: Vec<DtItem>,
+        needs_row_position: bool,
     ) -> anyhow::Result<(usize, Option<Position>, Option<Position>)> {
-        let (mut data, last_received_position, last_commit_position) = Self::fetch_dml(all_data);
+        let (mut data, last_received_position, last_commit_position) =
+            Self::fetch_dml(all_data, needs_row_position);
         let count = data.len();
         if count > 0 {
             // execute lua processor
@@ -228,19 +237,36 @@ impl BasePipeline {
         (last_received_position, last_commit_position)
     }
 
-    fn fetch_dml(mut data: Vec<DtItem>) -> (Vec<RowData>, Option<Position>, Option<Position>) {
+    fn fetch_dml(
+        mut data: Vec<DtItem>,
+        needs_row_position: bool,
+    ) -> (Vec<RowData>, Option<Position>, Option<Position>) {
         let mut dml_data = Vec::new();
         let mut last_received_position = Option::None;
         let mut last_commit_position = Option::None;
+        // index of the first row of the current source transaction in dml_data
+        let mut tx_start = 0;
         for i in data.drain(..) {
             match i.dt_data {
-                DtData::Commit { .. } => {
+                DtData::Commit { xid } => {
+                    // rows of the transaction sinked in previous batches are not updated
+                    if tx_start < dml_data.len() {
+                        for row_data in dml_data[tx_start..].iter_mut() {
+                            row_data.xid = xid.clone();
+                        }
+                        dml_data.last_mut().unwrap().is_last_in_tx = true;
+                    }
+                    tx_start = dml_data.len();
+
                     last_commit_position = Some(i.position);
                     last_received_position = last_commit_position.clone();
                     continue;
                 }
 
-                DtData::Dml { row_data } => {
+                DtData::Dml { mut row_data } => {
+                    if needs_row_position {
+                        row_data.position = i.position.clone();
+                    }
                     last_received_position = Some(i.position);
                     dml_data.push(row_data);
                 }
@@ -297,15 +323,45 @@ impl BasePipeline {
         SinkMethod::Raw
     }
 
-    fn record_checkpoint(
+    async fn record_checkpoint(
         &self,
         last_checkpoint_time: Option<Instant>,
         last_received_position: &Position,
         last_commit_position: &Position,
-    ) -> Instant {
+    ) -> anyhow::Result<Instant> {
         if let Some(last) = last_checkpoint_time {
             if last.elapsed().as_secs() < self.checkpoint_interval_secs {
-                return last;
+                return Ok(last);
+            }
+        }
+
+        if !matches!(last_commit_position, Position::None) {
+            // rows sinked after the last commit position belong to an unfinished source transaction,
+            // committing them with the position would make them duplicated after resuming,
+            // so wait until the source transaction is completely sinked, but not forever
+            if last_received_position != last_commit_position && self.defers_partial_commit().await
+            {
+                let max_defer_secs = Self::get_max_defer_secs(self.checkpoint_interval_secs);
+                match last_checkpoint_time {
+                    Some(last) if last.elapsed().as_secs() < max_defer_secs => {
+                        return Ok(last);
+                    }
+                    None => return Ok(Instant::now()),
+                    Some(_) => {
+                        log_warn!(
+                            "checkpoint deferred for over {} secs by an unfinished source transaction, commit with position: {}, rows after it may be duplicated after resuming",
+                            max_defer_secs,
+                            last_commit_position
+                        );
+                    }
+                }
+            }
+            for sinker in self.sinkers.iter() {
+                sinker
+                    .lock()
+                    .await
+                    .commit_position(last_commit_position)
+                    .await?;
             }
         }
 
@@ -315,6 +371,29 @@ impl BasePipeline {
         if !matches!(last_commit_position, Position::None) {
             self.syncer.lock().unwrap().committed_position = last_commit_position.to_owned();
         }
-        Instant::now()
+        Ok(Instant::now())
+    }
+
+    /// The longest time a checkpoint may be deferred, positions may not be committed within it
+    pub fn get_max_defer_secs(checkpoint_interval_secs: u64) -> u64 {
+        checkpoint_interval_secs.max(1) * MAX_DEFERRED_CHECKPOINTS
+    }
+
+    async fn needs_row_position(&self) -> bool {
+        for sinker in self.sinkers.iter() {
+            if sinker.lock().await.needs_row_position() {
+                return true;
+            }
+        }
+        false
+    }
+
+    async fn defers_partial_commit(&self) -> bool {
+        for sinker in self.sinkers.iter() {
+            if sinker.lock().await.defers_partial_commit() {
+                return true;
+            }
+        }
+        false
     }
 }
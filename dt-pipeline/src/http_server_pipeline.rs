@@ -9,13 +9,22 @@ use std::{
 use anyhow::bail;
 use async_trait::async_trait;
 use dt_common::{
+    config::config_enums::MessageFormat,
     log_position,
     meta::{
-        avro::avro_converter::AvroConverter, dt_data::DtData, dt_queue::DtQueue,
-        position::Position, syncer::Syncer,
+        avro::avro_converter::AvroConverter,
+        canal::canal_converter::CanalConverter,
+        debezium::debezium_converter::DebeziumConverter,
+        dt_data::DtData,
+        dt_queue::DtQueue,
+        maxwell::maxwell_converter::MaxwellConverter,
+        position::Position,
+        redis::{redis_entry::RedisEntry, redis_json_converter::RedisJsonConverter},
+        syncer::Syncer,
     },
     monitor::{counter_type::CounterType, monitor::Monitor},
 };
+use dt_connector::sinker::redis::entry_rewriter::EntryRewriter;
 use dt_parallelizer::base_parallelizer::BaseParallelizer;
 use futures::executor::block_on;
 
@@ -32,6 +41,10 @@ pub struct HttpServerPipeline {
     pub syncer: Arc<Mutex<Syncer>>,
     pub monitor: Arc<Mutex<Monitor>>,
     pub avro_converter: AvroConverter,
+    pub debezium_converter: DebeziumConverter,
+    pub canal_converter: CanalConverter,
+    pub maxwell_converter: MaxwellConverter,
+    pub message_format: MessageFormat,
     pub checkpoint_interval_secs: u64,
     pub batch_sink_interval_secs: u64,
     pub http_host: String,
@@ -83,6 +96,10 @@ impl HttpServerPipeline {
         syncer: Arc<Mutex<Syncer>>,
         monitor: Arc<Mutex<Monitor>>,
         avro_converter: AvroConverter,
+        debezium_converter: DebeziumConverter,
+        canal_converter: CanalConverter,
+        maxwell_converter: MaxwellConverter,
+        message_format: MessageFormat,
         checkpoint_interval_secs: u64,
         batch_sink_interval_secs: u64,
         http_host: &str,
@@ -93,6 +110,10 @@ impl HttpServerPipeline {
             syncer,
             monitor,
             avro_converter,
+            debezium_converter,
+            canal_converter,
+            maxwell_converter,
+            message_format,
             checkpoint_interval_secs,
             batch_sink_interval_secs,
             http_host: http_host.into(),
@@ -170,8 +191,50 @@ async fn fetch_new(
     };
 
     let mut avro_converter = pipeline.avro_converter.clone();
+    let mut debezium_converter = pipeline.debezium_converter.clone();
+    let mut canal_converter = pipeline.canal_converter.clone();
+    let mut maxwell_converter = pipeline.maxwell_converter.clone();
     for i in data {
         match i.dt_data {
+            DtData::Dml { row_data } if pipeline.message_format == MessageFormat::DebeziumJson => {
+                let payload = debezium_converter
+                    .row_data_to_debezium_value(&row_data, &i.position)
+                    .await
+                    .unwrap();
+                response.data.push(payload);
+            }
+
+            DtData::Dml { row_data } if pipeline.message_format == MessageFormat::CanalJson => {
+                let payload = canal_converter
+                    .row_data_to_canal_value(&row_data, &i.position)
+                    .await
+                    .unwrap();
+                response.data.push(payload);
+            }
+
+            DtData::Dml { row_data } if pipeline.message_format == MessageFormat::MaxwellJson => {
+                let payload = maxwell_converter
+                    .row_data_to_maxwell_value(&row_data, &i.position)
+                    .await
+                    .unwrap();
+                response.data.push(payload);
+            }
+
+            DtData::Ddl { ddl_data } if pipeline.message_format == MessageFormat::DebeziumJson => {
+                let payload = debezium_converter.ddl_data_to_debezium_value(&ddl_data, &i.position);
+                response.data.push(payload);
+            }
+
+            DtData::Ddl { ddl_data } if pipeline.message_format == MessageFormat::CanalJson => {
+                let payload = canal_converter.ddl_data_to_canal_value(&ddl_data, &i.position);
+                response.data.push(payload);
+            }
+
+            DtData::Ddl { ddl_data } if pipeline.message_format == MessageFormat::MaxwellJson => {
+                let payload = maxwell_converter.ddl_data_to_maxwell_value(&ddl_data, &i.position);
+                response.data.push(payload);
+            }
+
             DtData::Dml { row_data } => {
                 let payload = avro_converter
                     .row_data_to_avro_value(row_data)
@@ -188,6 +251,13 @@ async fn fetch_new(
                 response.data.push(payload);
             }
 
+            DtData::Redis { mut entry } => {
+                match encode_redis_entry(&pipeline, &mut entry, &i.position) {
+                    Ok(payloads) => response.data.extend(payloads),
+                    Err(err) => return HttpResponse::InternalServerError().body(err.to_string()),
+                }
+            }
+
             _ => {}
         }
     }
@@ -248,6 +318,27 @@ async fn ack(data: web::Json<AckReq>, pipeline: web::Data<HttpServerPipeline>) -
     })
 }
 
+fn encode_redis_entry(
+    pipeline: &HttpServerPipeline,
+    entry: &mut RedisEntry,
+    position: &Position,
+) -> anyhow::Result<Vec<Vec<u8>>> {
+    let position = position.to_string();
+    let mut payloads = Vec::new();
+    for cmd_entry in EntryRewriter::rewrite_as_cmd_entries(entry)? {
+        let payload = match pipeline.message_format {
+            MessageFormat::Json => {
+                RedisJsonConverter::redis_entry_to_json_value(&cmd_entry, &position)
+            }
+            _ => pipeline
+                .avro_converter
+                .redis_entry_to_avro_value(&cmd_entry, &position)?,
+        };
+        payloads.push(payload);
+    }
+    Ok(payloads)
+}
+
 fn send_response<T: Serialize>(response: &T) -> HttpResponse {
     match serde_json::to_string(response) {
         Ok(json) => HttpResponse::Ok()
@@ -16,6 +16,8 @@ impl LuaProcessor {
         let lua = Lua::new();
 
         for row_data in data {
+            let (position, xid, is_last_in_tx) =
+                (row_data.position, row_data.xid, row_data.is_last_in_tx);
             // to lua
             let (lua_before, blob_before) = self.col_values_to_lua_table(row_data.before, &lua)?;
             let (lua_after, blob_after) = self.col_values_to_lua_table(row_data.after, &lua)?;
@@ -45,7 +47,10 @@ impl LuaProcessor {
             let schema = lua.globals().get("schema")?;
             let tb = lua.globals().get("tb")?;
             let row_type = RowType::from_str(&row_type)?;
-            let new_row_data = RowData::new(schema, tb, row_type, before, after);
+            let mut new_row_data = RowData::new(schema, tb, row_type, before, after);
+            new_row_data.position = position;
+            new_row_data.xid = xid;
+            new_row_data.is_last_in_tx = is_last_in_tx;
             new_data.push(new_row_data);
         }
 
@@ -1,5 +1,6 @@
 use std::{
     cmp,
+    collections::HashMap,
     str::FromStr,
     sync::{atomic::AtomicBool, Arc, Mutex},
 };
@@ -16,7 +17,9 @@ use dt_common::{
 };
 use dt_common::{
     meta::{
-        avro::avro_converter::AvroConverter, mongo::mongo_cdc_source::MongoCdcSource,
+        avro::avro_converter::AvroConverter, canal::canal_converter::CanalConverter,
+        debezium::debezium_converter::DebeziumConverter,
+        maxwell::maxwell_converter::MaxwellConverter, mongo::mongo_cdc_source::MongoCdcSource,
         pg::pg_meta_manager::PgMetaManager, redis::redis_statistic_type::RedisStatisticType,
         syncer::Syncer,
     },
@@ -43,8 +46,9 @@ use dt_connector::{
             pg_snapshot_extractor::PgSnapshotExtractor, pg_struct_extractor::PgStructExtractor,
         },
         redis::{
-            redis_cdc_extractor::RedisCdcExtractor, redis_client::RedisClient,
-            redis_reshard_extractor::RedisReshardExtractor,
+            redis_aof_file_extractor::RedisAofFileExtractor,
+            redis_cdc_extractor::RedisCdcExtractor, redis_check_extractor::RedisCheckExtractor,
+            redis_client::RedisClient, redis_reshard_extractor::RedisReshardExtractor,
             redis_scan_extractor::RedisScanExtractor,
             redis_snapshot_extractor::RedisSnapshotExtractor,
             redis_snapshot_file_extractor::RedisSnapshotFileExtractor,
@@ -284,15 +288,20 @@ impl ExtractorUtil {
                 resume_token,
                 start_timestamp,
                 source,
+                full_document,
+                full_document_before_change,
                 heartbeat_interval_secs,
                 heartbeat_tb,
             } => {
                 let mongo_client = TaskUtil::create_mongo_client(&url, &app_name).await?;
                 let extractor = MongoCdcExtractor {
                     filter,
+                    url,
                     resume_token,
                     start_timestamp,
                     source: MongoCdcSource::from_str(&source)?,
+                    full_document,
+                    full_document_before_change,
                     mongo_client,
                     app_name,
                     base_extractor,
@@ -344,11 +353,16 @@ impl ExtractorUtil {
                 Box::new(extractor)
             }
 
-            ExtractorConfig::RedisSnapshot { url, repl_port } => {
+            ExtractorConfig::RedisSnapshot {
+                url,
+                repl_port,
+                is_cluster,
+            } => {
                 let conn = RedisClient::new(&url).await?;
                 let extractor = RedisSnapshotExtractor {
                     conn,
                     repl_port,
+                    is_cluster,
                     filter,
                     base_extractor,
                 };
@@ -364,6 +378,16 @@ impl ExtractorUtil {
                 Box::new(extractor)
             }
 
+            ExtractorConfig::RedisAofFile { aof_dir } => {
+                let extractor = RedisAofFileExtractor {
+                    aof_dir,
+                    filter,
+                    base_extractor,
+                    now_db_id: 0,
+                };
+                Box::new(extractor)
+            }
+
             ExtractorConfig::RedisScan {
                 url,
                 scan_count,
@@ -390,6 +414,7 @@ impl ExtractorUtil {
                 keepalive_interval_secs,
                 heartbeat_interval_secs,
                 heartbeat_key,
+                is_cluster,
             } => {
                 let conn = RedisClient::new(&url).await?;
                 let extractor = RedisCdcExtractor {
@@ -404,15 +429,44 @@ impl ExtractorUtil {
                     now_db_id,
                     filter,
                     resumer: cdc_resumer,
+                    is_cluster,
+                    base_extractor,
+                };
+                Box::new(extractor)
+            }
+
+            ExtractorConfig::RedisCheck {
+                url,
+                check_log_dir,
+                batch_size,
+                is_cluster,
+            } => {
+                let extractor = RedisCheckExtractor {
                     base_extractor,
+                    url,
+                    check_log_dir,
+                    batch_size,
+                    is_cluster,
+                    conns: HashMap::new(),
+                    slot_node_map: HashMap::new(),
                 };
                 Box::new(extractor)
             }
 
-            ExtractorConfig::RedisReshard { url } => {
+            ExtractorConfig::RedisReshard {
+                url,
+                node_weights,
+                target_slot_map,
+                plan_file,
+                dry_run,
+            } => {
                 let extractor = RedisReshardExtractor {
                     base_extractor,
                     url,
+                    node_weights,
+                    target_slot_map,
+                    plan_file,
+                    dry_run,
                 };
                 Box::new(extractor)
             }
@@ -423,18 +477,34 @@ impl ExtractorUtil {
                 topic,
                 partition,
                 offset,
+                assignment,
                 ack_interval_secs,
+                message_format,
+                properties,
             } => {
                 let meta_manager = TaskUtil::create_rdb_meta_manager(config).await?;
-                let avro_converter = AvroConverter::new(meta_manager, false);
+                let avro_converter = AvroConverter::new(meta_manager.clone(), false);
+                let debezium_converter = DebeziumConverter::new(
+                    meta_manager.clone(),
+                    config.sinker_basic.db_type.clone(),
+                    false,
+                );
+                let canal_converter = CanalConverter::new(meta_manager.clone());
+                let maxwell_converter = MaxwellConverter::new(meta_manager);
                 let extractor = KafkaExtractor {
                     url,
                     group,
                     topic,
                     partition,
                     offset,
+                    assignment,
                     ack_interval_secs,
+                    message_format,
+                    properties,
                     avro_converter,
+                    debezium_converter,
+                    canal_converter,
+                    maxwell_converter,
                     syncer,
                     resumer: cdc_resumer,
                     base_extractor,
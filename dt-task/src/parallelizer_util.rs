@@ -22,7 +22,7 @@ use dt_parallelizer::{
 };
 use ratelimit::Ratelimiter;
 
-use super::task_util::TaskUtil;
+use super::{extractor_util::ExtractorUtil, task_util::TaskUtil};
 
 pub struct ParallelizerUtil {}
 
@@ -99,7 +99,9 @@ impl ParallelizerUtil {
 
             ParallelType::Redis => {
                 let mut slot_node_map = HashMap::new();
-                if let SinkerConfig::Redis { is_cluster, .. } = config.sinker {
+                if let SinkerConfig::Redis { is_cluster, .. }
+                | SinkerConfig::RedisCheck { is_cluster, .. } = config.sinker
+                {
                     let mut conn = RedisUtil::create_redis_conn(&config.sinker_basic.url).await?;
                     if is_cluster {
                         let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
@@ -144,7 +146,17 @@ impl ParallelizerUtil {
     }
 
     async fn create_rdb_partitioner(config: &TaskConfig) -> anyhow::Result<RdbPartitioner> {
-        let meta_manager = TaskUtil::create_rdb_meta_manager(config).await?.unwrap();
+        let meta_manager = match config.sinker {
+            // rows are partitioned by the meta data of the source tables,
+            // so changes of the same key are sent by the same message queue sinker in order
+            SinkerConfig::Kafka { .. }
+            | SinkerConfig::Sqs { .. }
+            | SinkerConfig::RedisStream { .. }
+            | SinkerConfig::RabbitMq { .. } => ExtractorUtil::get_extractor_meta_manager(config)
+                .await?
+                .unwrap(),
+            _ => TaskUtil::create_rdb_meta_manager(config).await?.unwrap(),
+        };
         Ok(RdbPartitioner { meta_manager })
     }
 }
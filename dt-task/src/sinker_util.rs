@@ -1,4 +1,7 @@
 use std::{
+    collections::HashMap,
+    fs::File,
+    io::BufWriter,
     str::FromStr,
     sync::{Arc, Mutex, RwLock},
 };
@@ -6,7 +9,9 @@ use std::{
 use anyhow::Context;
 use dt_common::{
     config::{
-        config_enums::DbType, extractor_config::ExtractorConfig, sinker_config::SinkerConfig,
+        config_enums::{ConflictPolicyEnum, DbType},
+        extractor_config::ExtractorConfig,
+        sinker_config::SinkerConfig,
         task_config::TaskConfig,
     },
     monitor::monitor::Monitor,
@@ -15,7 +20,6 @@ use dt_common::{
 };
 use dt_common::{
     meta::{
-        avro::avro_converter::AvroConverter,
         mysql::mysql_meta_manager::MysqlMetaManager,
         pg::pg_meta_manager::PgMetaManager,
         redis::{redis_statistic_type::RedisStatisticType, redis_write_method::RedisWriteMethod},
@@ -36,22 +40,37 @@ use dt_connector::{
             foxlake_sinker::FoxlakeSinker, foxlake_struct_sinker::FoxlakeStructSinker,
             orc_sequencer::OrcSequencer,
         },
-        kafka::kafka_sinker::KafkaSinker,
-        mongo::{mongo_checker::MongoChecker, mongo_sinker::MongoSinker},
+        kafka::{
+            kafka_partitioner::KafkaPartitioner, kafka_producer::KafkaProducer,
+            kafka_sinker::KafkaSinker, kafka_transaction::KafkaTransaction,
+            schema_registry::SchemaRegistry,
+        },
+        message_encoder::MessageEncoder,
+        mongo::{
+            mongo_checker::MongoChecker, mongo_sinker::MongoSinker,
+            rdb_doc_converter::RdbDocConverter,
+        },
         mysql::{
             mysql_checker::MysqlChecker, mysql_sinker::MysqlSinker,
             mysql_struct_sinker::MysqlStructSinker,
         },
         pg::{pg_checker::PgChecker, pg_sinker::PgSinker, pg_struct_sinker::PgStructSinker},
-        redis::{redis_sinker::RedisSinker, redis_statistic_sinker::RedisStatisticSinker},
+        rabbitmq::rabbitmq_sinker::RabbitMqSinker,
+        redis::{
+            rdb_writer::RdbWriter, redis_checker::RedisChecker,
+            redis_memory_report_sinker::RedisMemoryReportSinker, redis_sinker::RedisSinker,
+            redis_snapshot_file_sinker::RedisSnapshotFileSinker,
+            redis_statistic_sinker::RedisStatisticSinker, redis_stream_writer::RedisStreamWriter,
+        },
         sql_sinker::SqlSinker,
+        sqs::sqs_sinker::SqsSinker,
         starrocks::{
             starrocks_sinker::StarRocksSinker, starrocks_struct_sinker::StarrocksStructSinker,
         },
     },
     Sinker,
 };
-use kafka::producer::{Producer, RequiredAcks};
+use dt_pipeline::base_pipeline::BasePipeline;
 use reqwest::{redirect::Policy, Url};
 use rusoto_s3::S3Client;
 use sqlx::types::chrono::Utc;
@@ -190,8 +209,22 @@ impl SinkerUtil {
                 url,
                 app_name,
                 batch_size,
+                nest_map,
             } => {
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Mongo)?;
+                // rows from RDB extractor need to be converted into documents
+                let rdb_doc_converter =
+                    match ExtractorUtil::get_extractor_meta_manager(task_config).await? {
+                        Some(meta_manager) => {
+                            let router = RdbRouter::from_config(
+                                &task_config.router,
+                                &task_config.extractor_basic.db_type,
+                            )?;
+                            Some(RdbDocConverter::new(meta_manager, router, &nest_map)?)
+                        }
+                        None => None,
+                    };
+
                 for _ in 0..parallel_size {
                     let mongo_client = TaskUtil::create_mongo_client(&url, &app_name).await?;
                     let sinker = MongoSinker {
@@ -199,6 +232,8 @@ impl SinkerUtil {
                         router: router.clone(),
                         mongo_client,
                         monitor: monitor.clone(),
+                        rdb_doc_converter: rdb_doc_converter.clone(),
+                        data_marker: data_marker.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -208,17 +243,37 @@ impl SinkerUtil {
                 url,
                 app_name,
                 batch_size,
+                check_extra,
                 ..
             } => {
                 let reverse_router =
                     RdbRouter::from_config(&task_config.router, &DbType::Mongo)?.reverse();
-                for _ in 0..parallel_size {
+                let filter = RdbFilter::from_config(&task_config.filter, &DbType::Mongo)?;
+                for i in 0..parallel_size {
                     let mongo_client = TaskUtil::create_mongo_client(&url, &app_name).await?;
+                    // extra docs are only checked after a full snapshot check,
+                    // and only by the first checker when closing
+                    let src_mongo_client = if i == 0
+                        && check_extra
+                        && matches!(extractor_config, ExtractorConfig::MongoSnapshot { .. })
+                    {
+                        Some(
+                            TaskUtil::create_mongo_client(
+                                &task_config.extractor_basic.url,
+                                &app_name,
+                            )
+                            .await?,
+                        )
+                    } else {
+                        None
+                    };
                     let sinker = MongoChecker {
                         batch_size,
                         reverse_router: reverse_router.clone(),
                         mongo_client,
                         monitor: monitor.clone(),
+                        src_mongo_client,
+                        filter: filter.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -230,6 +285,14 @@ impl SinkerUtil {
                 ack_timeout_secs,
                 required_acks,
                 with_field_defs,
+                message_format,
+                tombstones_on_delete,
+                partition_by,
+                partition_cols,
+                properties,
+                schema_registry_url,
+                transactional_id,
+                offsets_topic,
             } => {
                 let router = RdbRouter::from_config(
                     &task_config.router,
@@ -238,30 +301,133 @@ impl SinkerUtil {
                 )?;
                 // kafka sinker may need meta data from RDB extractor
                 let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
-                let avro_converter = AvroConverter::new(meta_manager, with_field_defs);
+                let encoder = MessageEncoder::new(
+                    meta_manager,
+                    task_config.extractor_basic.db_type.clone(),
+                    with_field_defs,
+                    message_format,
+                );
+                let partition_cols = RdbRouter::parse_partition_cols(
+                    &partition_cols,
+                    &task_config.extractor_basic.db_type,
+                )?;
 
-                let brokers = vec![url.to_string()];
-                let acks = match required_acks.as_str() {
-                    "all" => RequiredAcks::All,
-                    "none" => RequiredAcks::None,
-                    _ => RequiredAcks::One,
+                // all sinkers share one transactional producer, so a single transaction
+                // covers the messages sent by all of them between two checkpoints
+                let mut transaction = if transactional_id.is_empty() {
+                    None
+                } else {
+                    Some(KafkaTransaction::create(
+                        &url,
+                        ack_timeout_secs,
+                        &properties,
+                        &transactional_id,
+                        &offsets_topic,
+                        BasePipeline::get_max_defer_secs(
+                            task_config.pipeline.checkpoint_interval_secs,
+                        ),
+                    )?)
                 };
+                let transactional_producer = transaction.as_ref().map(|i| i.producer.clone());
 
                 for _ in 0..parallel_size {
-                    // TODO, authentication, https://github.com/kafka-rust/kafka-rust/blob/master/examples/example-ssl.rs
-                    let producer = Producer::from_hosts(brokers.clone())
-                        .with_ack_timeout(std::time::Duration::from_secs(ack_timeout_secs))
-                        .with_required_acks(acks)
-                        .create()
-                        .with_context(|| {
-                            format!("failed to create kafka producer, url: [{}]", url)
-                        })?;
+                    let producer = match &transactional_producer {
+                        Some(producer) => KafkaProducer::Rdkafka {
+                            producer: producer.clone(),
+                            queue_timeout_secs: ack_timeout_secs,
+                        },
+                        None => KafkaProducer::create(
+                            &url,
+                            ack_timeout_secs,
+                            &required_acks,
+                            &properties,
+                        )?,
+                    };
+                    let partitioner = KafkaPartitioner {
+                        partition_by: partition_by.clone(),
+                        partition_cols: partition_cols.clone(),
+                        partition_counts: HashMap::new(),
+                    };
                     // the sending performance of RdkafkaSinker is much worse than KafkaSinker
                     let sinker = KafkaSinker {
                         batch_size,
                         router: router.clone(),
                         producer,
-                        avro_converter: avro_converter.clone(),
+                        encoder: encoder.clone(),
+                        tombstones_on_delete,
+                        partitioner,
+                        schema_registry: SchemaRegistry::new(&schema_registry_url),
+                        transaction: transaction.take(),
+                        monitor: monitor.clone(),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
+            SinkerConfig::Sqs {
+                url,
+                batch_size,
+                with_field_defs,
+                message_format,
+                message_group_by,
+                max_retries,
+                region,
+                endpoint,
+                access_key,
+                secret_key,
+            } => {
+                // sqs sinker may need meta data from RDB extractor
+                let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
+                let encoder = MessageEncoder::new(
+                    meta_manager,
+                    task_config.extractor_basic.db_type.clone(),
+                    with_field_defs,
+                    message_format,
+                );
+                let client =
+                    TaskUtil::create_sqs_client(&region, &endpoint, &access_key, &secret_key)?;
+                for _ in 0..parallel_size {
+                    let sinker = SqsSinker {
+                        batch_size,
+                        queue_url: url.clone(),
+                        client: client.clone(),
+                        encoder: encoder.clone(),
+                        message_group_by: message_group_by.clone(),
+                        max_retries,
+                        monitor: monitor.clone(),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
+            SinkerConfig::RabbitMq {
+                url,
+                batch_size,
+                with_field_defs,
+                message_format,
+                exchange,
+                routing_key,
+            } => {
+                // rabbitmq sinker may need meta data from RDB extractor
+                let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
+                let encoder = MessageEncoder::new(
+                    meta_manager,
+                    task_config.extractor_basic.db_type.clone(),
+                    with_field_defs,
+                    message_format,
+                );
+                for _ in 0..parallel_size {
+                    // each sinker publishes in its own channel, so messages of the same key
+                    // dispatched to the same sinker are kept in order
+                    let (conn, channel) = RabbitMqSinker::create_channel(&url).await?;
+                    let sinker = RabbitMqSinker {
+                        batch_size,
+                        conn,
+                        channel,
+                        exchange: exchange.clone(),
+                        routing_key: routing_key.clone(),
+                        encoder: encoder.clone(),
+                        pending_confirms: Vec::new(),
                         monitor: monitor.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
@@ -308,6 +474,9 @@ impl SinkerUtil {
                 batch_size,
                 method,
                 is_cluster,
+                rewrite_chunk_size,
+                max_restore_bytes,
+                conflict_policy,
             } => {
                 // redis sinker may need meta data from RDB extractor
                 let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
@@ -335,9 +504,14 @@ impl SinkerUtil {
                             now_db_id: -1,
                             version,
                             method: method.clone(),
+                            rewrite_chunk_size,
+                            max_restore_bytes,
+                            conflict_policy: conflict_policy.clone(),
+                            ignored_key: None,
                             meta_manager: meta_manager.clone(),
                             monitor: monitor.clone(),
                             data_marker: data_marker.clone(),
+                            stream_writer: None,
                         };
                         sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                     }
@@ -351,33 +525,162 @@ impl SinkerUtil {
                             now_db_id: -1,
                             version,
                             method: method.clone(),
+                            rewrite_chunk_size,
+                            max_restore_bytes,
+                            conflict_policy: conflict_policy.clone(),
+                            ignored_key: None,
                             meta_manager: meta_manager.clone(),
                             monitor: monitor.clone(),
                             data_marker: data_marker.clone(),
+                            stream_writer: None,
                         };
                         sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                     }
                 }
             }
 
+            SinkerConfig::RedisStream {
+                url,
+                batch_size,
+                with_field_defs,
+                message_format,
+                max_len,
+                position_key,
+            } => {
+                let router = RdbRouter::from_config(
+                    &task_config.router,
+                    &task_config.extractor_basic.db_type,
+                )?;
+                let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
+                let encoder = MessageEncoder::new(
+                    meta_manager,
+                    task_config.extractor_basic.db_type.clone(),
+                    with_field_defs,
+                    message_format,
+                );
+                for i in 0..parallel_size {
+                    let mut conn = RedisUtil::create_redis_conn(&url).await?;
+                    let version = RedisUtil::get_redis_version(&mut conn)?;
+                    let stream_writer = RedisStreamWriter {
+                        router: router.clone(),
+                        encoder: encoder.clone(),
+                        max_len,
+                        // the position is committed by all sinkers, only the first one stores it
+                        position_key: if i == 0 {
+                            position_key.clone()
+                        } else {
+                            String::new()
+                        },
+                    };
+                    let sinker = RedisSinker {
+                        id: url.to_string(),
+                        conn,
+                        batch_size,
+                        now_db_id: -1,
+                        version,
+                        method: RedisWriteMethod::Rewrite,
+                        rewrite_chunk_size: 0,
+                        max_restore_bytes: 0,
+                        conflict_policy: ConflictPolicyEnum::Replace,
+                        ignored_key: None,
+                        meta_manager: None,
+                        monitor: monitor.clone(),
+                        data_marker: data_marker.clone(),
+                        stream_writer: Some(stream_writer),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
+            SinkerConfig::RedisCheck {
+                url,
+                batch_size,
+                is_cluster,
+                ..
+            } => {
+                let reverse_router =
+                    RdbRouter::from_config(&task_config.router, &DbType::Redis)?.reverse();
+                let mut urls = Vec::new();
+                if is_cluster {
+                    let mut conn = RedisUtil::create_redis_conn(&url).await?;
+                    // one checker for each master, ids are node addresses
+                    for node in RedisUtil::get_cluster_master_nodes(&mut conn)? {
+                        let node_url = RedisUtil::get_cluster_node_url(&url, &node.address)?;
+                        urls.push((node.address, node_url));
+                    }
+                } else {
+                    urls.push((url.clone(), url.clone()));
+                }
+
+                for (id, url) in urls {
+                    let conn = RedisUtil::create_redis_conn(&url).await?;
+                    let sinker = RedisChecker {
+                        id,
+                        conn,
+                        batch_size,
+                        now_db_id: -1,
+                        reverse_router: reverse_router.clone(),
+                        monitor: monitor.clone(),
+                        chunk_result: None,
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
             SinkerConfig::RedisStatistic {
                 statistic_type,
                 data_size_threshold,
                 freq_threshold,
-                ..
+                statistic_log_dir,
+                top_n,
+                prefix_delimiter,
+                report_interval_secs,
             } => {
                 let statistic_type = RedisStatisticType::from_str(&statistic_type)?;
-                for _ in 0..parallel_size {
-                    let sinker = RedisStatisticSinker {
-                        statistic_type: statistic_type.clone(),
-                        data_size_threshold,
-                        freq_threshold,
-                        monitor: monitor.clone(),
+                if let RedisStatisticType::Memory = statistic_type {
+                    // the report is aggregated from all keys by a single sinker
+                    let report_dir = if statistic_log_dir.is_empty() {
+                        format!("{}/statistic", task_config.runtime.log_dir)
+                    } else {
+                        statistic_log_dir
                     };
+                    let sinker = RedisMemoryReportSinker::new(
+                        &report_dir,
+                        data_size_threshold,
+                        top_n,
+                        &prefix_delimiter,
+                        report_interval_secs,
+                        monitor.clone(),
+                    )?;
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                } else {
+                    for _ in 0..parallel_size {
+                        let sinker = RedisStatisticSinker {
+                            statistic_type: statistic_type.clone(),
+                            data_size_threshold,
+                            freq_threshold,
+                            monitor: monitor.clone(),
+                        };
+                        sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                    }
                 }
             }
 
+            SinkerConfig::RedisSnapshotFile {
+                file_path,
+                rdb_version,
+            } => {
+                // entries are written into a single file sequentially
+                let file = File::create(&file_path)
+                    .with_context(|| format!("failed to create rdb file: {}", file_path))?;
+                let writer = RdbWriter::new(BufWriter::new(file), rdb_version)?;
+                let sinker = RedisSnapshotFileSinker {
+                    writer,
+                    monitor: monitor.clone(),
+                };
+                sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+            }
+
             SinkerConfig::StarRocks {
                 url,
                 batch_size,
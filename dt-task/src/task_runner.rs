@@ -20,11 +20,15 @@ use dt_common::{
         task_config::TaskConfig,
     },
     error::Error,
-    log_finished, log_info,
-    meta::{avro::avro_converter::AvroConverter, dt_queue::DtQueue},
+    log_finished, log_info, log_warn,
+    meta::{
+        avro::avro_converter::AvroConverter, canal::canal_converter::CanalConverter,
+        debezium::debezium_converter::DebeziumConverter, dt_queue::DtQueue,
+        maxwell::maxwell_converter::MaxwellConverter,
+    },
     monitor::monitor::Monitor,
     rdb_filter::RdbFilter,
-    utils::{sql_util::SqlUtil, time_util::TimeUtil},
+    utils::{redis_util::RedisUtil, sql_util::SqlUtil, time_util::TimeUtil},
 };
 use dt_common::{
     log_error,
@@ -34,6 +38,9 @@ use dt_connector::{
     data_marker::DataMarker,
     extractor::resumer::{cdc_resumer::CdcResumer, snapshot_resumer::SnapshotResumer},
     rdb_router::RdbRouter,
+    sinker::{
+        kafka::kafka_transaction::KafkaTransaction, redis::redis_stream_writer::RedisStreamWriter,
+    },
     Sinker,
 };
 use dt_pipeline::{
@@ -82,7 +89,57 @@ impl TaskRunner {
         let db_type = &self.config.extractor_basic.db_type;
         let router = RdbRouter::from_config(&self.config.router, db_type)?;
         let snapshot_resumer = SnapshotResumer::from_config(&self.config.resumer)?;
-        let cdc_resumer = CdcResumer::from_config(&self.config.resumer)?;
+        let mut cdc_resumer = CdcResumer::from_config(&self.config.resumer)?;
+        if let SinkerConfig::Kafka {
+            url,
+            properties,
+            transactional_id,
+            offsets_topic,
+            ..
+        } = &self.config.sinker
+        {
+            if !transactional_id.is_empty() {
+                // messages sent after the checkpoint_position in position.log may be committed
+                // to kafka, resume from the position committed with them instead
+                let position = KafkaTransaction::read_committed_position(
+                    url,
+                    properties,
+                    transactional_id,
+                    offsets_topic,
+                    BasePipeline::get_max_defer_secs(self.config.pipeline.checkpoint_interval_secs),
+                )?;
+                if position != Position::None {
+                    cdc_resumer.checkpoint_position = position.clone();
+                    cdc_resumer.current_position = position;
+                }
+            }
+        }
+
+        if let SinkerConfig::RedisStream {
+            url, position_key, ..
+        } = &self.config.sinker
+        {
+            if !position_key.is_empty() {
+                // the position stored with the data in redis wins over the resumer, since
+                // position.log may lag behind the messages already added to streams
+                let mut conn = RedisUtil::create_redis_conn(url).await?;
+                let position = RedisStreamWriter::read_position(&mut conn, position_key)?;
+                if position != Position::None {
+                    if cdc_resumer.checkpoint_position != Position::None
+                        && cdc_resumer.checkpoint_position != position
+                    {
+                        log_warn!(
+                            "resumer position: {} overridden by position in redis key: {}, position: {}",
+                            cdc_resumer.checkpoint_position,
+                            position_key,
+                            position
+                        );
+                    }
+                    cdc_resumer.checkpoint_position = position.clone();
+                    cdc_resumer.current_position = position;
+                }
+            }
+        }
 
         match &self.config.extractor {
             ExtractorConfig::MysqlStruct { url, .. }
@@ -421,12 +478,23 @@ impl TaskRunner {
             PipelineType::HttpServer => {
                 let meta_manager = ExtractorUtil::get_extractor_meta_manager(&self.config).await?;
                 let avro_converter =
-                    AvroConverter::new(meta_manager, self.config.pipeline.with_field_defs);
+                    AvroConverter::new(meta_manager.clone(), self.config.pipeline.with_field_defs);
+                let debezium_converter = DebeziumConverter::new(
+                    meta_manager.clone(),
+                    self.config.extractor_basic.db_type.clone(),
+                    self.config.pipeline.with_field_defs,
+                );
+                let canal_converter = CanalConverter::new(meta_manager.clone());
+                let maxwell_converter = MaxwellConverter::new(meta_manager);
                 let pipeline = HttpServerPipeline::new(
                     buffer,
                     syncer,
                     monitor,
                     avro_converter,
+                    debezium_converter,
+                    canal_converter,
+                    maxwell_converter,
+                    self.config.pipeline.message_format.clone(),
                     self.config.pipeline.checkpoint_interval_secs,
                     self.config.pipeline.batch_sink_interval_secs,
                     &self.config.pipeline.http_host,
@@ -448,7 +516,8 @@ impl TaskRunner {
 
         match &self.config.sinker {
             SinkerConfig::MysqlCheck { check_log_dir, .. }
-            | SinkerConfig::PgCheck { check_log_dir, .. } => {
+            | SinkerConfig::PgCheck { check_log_dir, .. }
+            | SinkerConfig::RedisCheck { check_log_dir, .. } => {
                 if !check_log_dir.is_empty() {
                     config_str = config_str.replace(CHECK_LOG_DIR_PLACEHODLER, check_log_dir);
                 }
@@ -1,10 +1,12 @@
 use std::{str::FromStr, time::Duration};
 
+use anyhow::bail;
 use dt_common::config::s3_config::S3Config;
 use dt_common::config::{
     config_enums::DbType, meta_center_config::MetaCenterConfig, sinker_config::SinkerConfig,
     task_config::TaskConfig,
 };
+use dt_common::error::Error;
 use dt_common::log_info;
 use dt_common::meta::mysql::mysql_dbengine_meta_center::MysqlDbEngineMetaCenter;
 use dt_common::meta::{
@@ -15,6 +17,7 @@ use futures::TryStreamExt;
 use mongodb::options::ClientOptions;
 use rusoto_core::Region;
 use rusoto_s3::S3Client;
+use rusoto_sqs::SqsClient;
 use sqlx::{
     mysql::{MySqlConnectOptions, MySqlPoolOptions},
     postgres::{PgConnectOptions, PgPoolOptions},
@@ -335,4 +338,41 @@ impl TaskUtil {
 
         S3Client::new_with(rusoto_core::HttpClient::new().unwrap(), credentials, region)
     }
+
+    /// Credentials are from the default provider chain (env vars, profile, instance role)
+    /// if access_key is not configured
+    pub fn create_sqs_client(
+        region: &str,
+        endpoint: &str,
+        access_key: &str,
+        secret_key: &str,
+    ) -> anyhow::Result<SqsClient> {
+        let region = if endpoint.is_empty() {
+            match Region::from_str(region) {
+                Ok(region) => region,
+                Err(_) => {
+                    bail! {Error::ConfigError(format!("invalid sqs_region: [{}]", region))}
+                }
+            }
+        } else {
+            Region::Custom {
+                name: region.to_string(),
+                endpoint: endpoint.to_string(),
+            }
+        };
+
+        if access_key.is_empty() {
+            return Ok(SqsClient::new(region));
+        }
+
+        let credentials = rusoto_credential::StaticProvider::new_minimal(
+            access_key.to_owned(),
+            secret_key.to_owned(),
+        );
+        Ok(SqsClient::new_with(
+            rusoto_core::HttpClient::new()?,
+            credentials,
+            region,
+        ))
+    }
 }
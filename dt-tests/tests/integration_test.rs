@@ -3,10 +3,13 @@ mod mongo_to_mongo;
 mod mysql_to_clickhouse;
 mod mysql_to_foxlake;
 mod mysql_to_kafka_to_mysql;
+mod mysql_to_mongo;
 mod mysql_to_mysql;
 mod mysql_to_mysql_case_sensitive;
 mod mysql_to_mysql_lua;
+mod mysql_to_rabbitmq;
 mod mysql_to_redis;
+mod mysql_to_sqs;
 mod mysql_to_starrocks;
 mod mysql_to_tidb;
 mod pg_to_clickhouse;
@@ -14,6 +17,7 @@ mod pg_to_kafka_to_pg;
 mod pg_to_pg;
 mod pg_to_pg_lua;
 mod pg_to_starrocks;
+mod redis_to_kafka_to_redis;
 mod redis_to_redis;
 mod test_config_util;
 mod test_runner;
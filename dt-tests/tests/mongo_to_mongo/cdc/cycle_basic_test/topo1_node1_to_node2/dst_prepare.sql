@@ -0,0 +1,9 @@
+use ape_trans_mongo
+
+db.dropDatabase();
+
+use twoway_test_db_1
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
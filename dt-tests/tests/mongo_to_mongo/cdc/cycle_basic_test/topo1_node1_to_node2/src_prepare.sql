@@ -0,0 +1,5 @@
+use twoway_test_db_1
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
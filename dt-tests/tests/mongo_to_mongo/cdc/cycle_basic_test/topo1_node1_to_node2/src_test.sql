@@ -0,0 +1,14 @@
+use twoway_test_db_1
+
+db.tb_1.insertOne({ "name": "a", "age": "1", "_id": "1" });
+db.tb_1.insertOne({ "name": "b", "age": "2", "_id": "2" });
+db.tb_1.insertOne({ "name": "c", "age": "3", "_id": "3" });
+db.tb_1.insertOne({ "name": "d", "age": "4", "_id": "4" });
+db.tb_1.insertOne({ "name": "e", "age": "5", "_id": "5" });
+
+db.tb_1.updateOne({ "_id" : "1" }, { "$set": { "name" : "a_1" } });
+db.tb_1.updateOne({ "_id" : "2" }, { "$set": { "salary" : 100 } });
+db.tb_1.updateOne({ "_id" : "3" }, { "$unset": { "age" : "" } });
+
+db.tb_1.deleteOne({ "_id": "4" });
+db.tb_1.deleteOne({ "_id": "5" });
@@ -0,0 +1,14 @@
+use twoway_test_db_1
+
+db.tb_1.insertOne({ "name": "f", "age": "6", "_id": "6" });
+db.tb_1.insertOne({ "name": "g", "age": "7", "_id": "7" });
+db.tb_1.insertOne({ "name": "h", "age": "8", "_id": "8" });
+db.tb_1.insertOne({ "name": "i", "age": "9", "_id": "9" });
+db.tb_1.insertOne({ "name": "j", "age": "10", "_id": "10" });
+
+db.tb_1.updateOne({ "_id" : "6" }, { "$set": { "name" : "f_1" } });
+db.tb_1.updateOne({ "_id" : "7" }, { "$set": { "salary" : 100 } });
+db.tb_1.updateOne({ "_id" : "8" }, { "$unset": { "age" : "" } });
+
+db.tb_1.deleteOne({ "_id": "9" });
+db.tb_1.deleteOne({ "_id": "10" });
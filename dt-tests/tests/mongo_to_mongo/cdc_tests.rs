@@ -2,7 +2,7 @@
 mod test {
     use serial_test::serial;
 
-    use crate::test_runner::test_base::TestBase;
+    use crate::test_runner::{mongo_cycle_test_runner::MongoCycleTestRunner, test_base::TestBase};
 
     #[tokio::test]
     #[serial]
@@ -16,6 +16,13 @@ mod test {
         TestBase::run_mongo_cdc_test("mongo_to_mongo/cdc/change_stream_test", 3000, 3000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_change_stream_diff_test() {
+        TestBase::run_mongo_cdc_test("mongo_to_mongo/cdc/change_stream_diff_test", 3000, 3000)
+            .await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_resume_test() {
@@ -45,4 +52,21 @@ mod test {
     async fn cdc_heartbeat_test() {
         TestBase::run_mongo_heartbeat_test("mongo_to_mongo/cdc/heartbeat_test", 3000, 3000).await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn cycle_basic_test() {
+        let tx_check_data = vec![
+            ("node1", "node2", "node1", "10"),
+            ("node2", "node1", "node2", "10"),
+        ];
+
+        MongoCycleTestRunner::run_cycle_cdc_test(
+            "mongo_to_mongo/cdc/cycle_basic_test",
+            3000,
+            3000,
+            &tx_check_data,
+        )
+        .await;
+    }
 }
@@ -0,0 +1,9 @@
+use test_db_1
+
+db.tb_1.insertOne({ "name": "a", "age": "1" , "_id": "65733a82fb2ce9836745de4a" });
+db.tb_1.insertOne({ "name": "b", "age": "2" , "_id": "65733a82fb2ce9836745de4b" });
+db.tb_1.insertOne({ "name": "p", "age": "16" , "_id": "65733a82fb2ce9836745de4p" });
+db.tb_1.insertOne({ "name": "r", "age": "18" , "_id": "65733a82fb2ce9836745de4r" });
+
+db.tb_2.insertOne({ "name": "f", "age": "6" , "_id": "65733a82fb2ce9836745de4f" });
+db.tb_2.insertOne({ "name": "q", "age": "17" , "_id": "65733a82fb2ce9836745de4q" });
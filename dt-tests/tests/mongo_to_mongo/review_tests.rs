@@ -16,4 +16,10 @@ mod test {
     async fn review_route_test() {
         TestBase::run_mongo_review_test("mongo_to_mongo/review/route_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn review_extra_test() {
+        TestBase::run_mongo_review_test("mongo_to_mongo/review/extra_test").await;
+    }
 }
@@ -0,0 +1,6 @@
+use test_db_1
+
+db.tb_1.insertOne({ "name": "a", "age": "1" , "_id": "65733a82fb2ce9836745de4a" });
+db.tb_1.insertOne({ "name": "b", "age": "2" , "_id": "65733a82fb2ce9836745de4b" });
+
+db.tb_2.insertOne({ "name": "f", "age": "6" , "_id": "65733a82fb2ce9836745de4f" });
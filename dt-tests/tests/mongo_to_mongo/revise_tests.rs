@@ -16,4 +16,10 @@ mod test {
     async fn revise_route_test() {
         TestBase::run_mongo_revise_test("mongo_to_mongo/revise/route_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn revise_extra_test() {
+        TestBase::run_mongo_revise_test("mongo_to_mongo/revise/extra_test").await;
+    }
 }
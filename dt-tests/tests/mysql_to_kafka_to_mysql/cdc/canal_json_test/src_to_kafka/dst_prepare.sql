@@ -0,0 +1 @@
+create topic test_canal
\ No newline at end of file
@@ -0,0 +1 @@
+create topic test_debezium
\ No newline at end of file
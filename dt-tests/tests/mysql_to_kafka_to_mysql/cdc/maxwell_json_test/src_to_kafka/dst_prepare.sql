@@ -0,0 +1 @@
+create topic test_maxwell
\ No newline at end of file
@@ -0,0 +1,2 @@
+create topic test_partition_1 partitions 3
+create topic test_partition_2 partitions 2
\ No newline at end of file
@@ -0,0 +1 @@
+create topic test_sasl partitions 2
\ No newline at end of file
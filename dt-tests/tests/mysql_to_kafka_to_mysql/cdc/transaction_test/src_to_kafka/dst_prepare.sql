@@ -0,0 +1,2 @@
+create topic test_txn partitions 2
+create topic test_offsets
\ No newline at end of file
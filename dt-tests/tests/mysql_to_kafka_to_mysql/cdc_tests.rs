@@ -11,4 +11,77 @@ mod test {
         TestBase::run_rdb_kafka_rdb_cdc_test("mysql_to_kafka_to_mysql/cdc/basic_test", 5000, 10000)
             .await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_multi_partitions_test() {
+        TestBase::run_rdb_kafka_rdb_cdc_test(
+            "mysql_to_kafka_to_mysql/cdc/multi_partitions_test",
+            5000,
+            10000,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_partition_by_key_test() {
+        TestBase::run_rdb_kafka_rdb_cdc_test(
+            "mysql_to_kafka_to_mysql/cdc/partition_by_key_test",
+            5000,
+            10000,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_sasl_test() {
+        TestBase::run_rdb_kafka_rdb_cdc_test("mysql_to_kafka_to_mysql/cdc/sasl_test", 5000, 10000)
+            .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_transaction_test() {
+        TestBase::run_rdb_kafka_rdb_cdc_test(
+            "mysql_to_kafka_to_mysql/cdc/transaction_test",
+            5000,
+            10000,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_debezium_json_test() {
+        TestBase::run_rdb_kafka_rdb_cdc_test(
+            "mysql_to_kafka_to_mysql/cdc/debezium_json_test",
+            5000,
+            10000,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_canal_json_test() {
+        TestBase::run_rdb_kafka_rdb_cdc_test(
+            "mysql_to_kafka_to_mysql/cdc/canal_json_test",
+            5000,
+            10000,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_maxwell_json_test() {
+        TestBase::run_rdb_kafka_rdb_cdc_test(
+            "mysql_to_kafka_to_mysql/cdc/maxwell_json_test",
+            5000,
+            10000,
+        )
+        .await;
+    }
 }
@@ -0,0 +1,15 @@
+INSERT INTO test_db_1.one_pk_no_uk VALUES (1,2,3,4,5,123456.1234,1234.12,12345.123,1893,'2022-01-02 03:04:05.123456','03:04:05.123456','2022-01-02',2022,'2022-01-02 03:04:05.123456','ab','cd','ef','gh','ij','kl','mn','op',x'0123456789ABCDEF',x'0123456789ABCDEF',x'0123456789ABCDEF',x'0123456789ABCDEF','x-small','c','{"k": [1, "v"]}');
+INSERT INTO test_db_1.one_pk_no_uk VALUES (2,20,30,40,50,654321.4321,4321.21,54321.321,3045,'2021-02-01 04:05:06.654321','04:05:06.654321','2012-02-01',2021,'2021-02-01 04:05:06.654321','1','2','3','4','5','6','7','8',x'ABCDEF0123456789',x'ABCDEF0123456789',x'ABCDEF0123456789',x'ABCDEF0123456789','small','b', NULL);
+INSERT INTO test_db_1.one_pk_no_uk VALUES (3, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL);
+
+INSERT INTO test_db_1.multi_pk VALUES (1, 'a', 'abc');
+INSERT INTO test_db_1.multi_pk VALUES (1, 'b', 'abc');
+INSERT INTO test_db_1.multi_pk VALUES (2, 'a', NULL);
+
+UPDATE test_db_1.one_pk_no_uk SET f_1=200, f_5=1.5, f_9='2023-01-02 03:04:05.000000', f_28='{"a": {"b": 1}}' WHERE f_0=1;
+UPDATE test_db_1.one_pk_no_uk SET f_0=4 WHERE f_0=2;
+UPDATE test_db_1.multi_pk SET f_2='def' WHERE f_0=1;
+UPDATE test_db_1.multi_pk SET f_1='c' WHERE f_0=2;
+
+DELETE FROM test_db_1.one_pk_no_uk WHERE f_0=3;
+DELETE FROM test_db_1.multi_pk WHERE f_0=1 AND f_1='a';
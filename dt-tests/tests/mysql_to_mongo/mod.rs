@@ -0,0 +1,2 @@
+mod cdc_tests;
+mod snapshot_tests;
@@ -10,4 +10,10 @@ mod test {
     async fn cdc_basic_test() {
         TestBase::run_rdb_redis_cdc_test("mysql_to_redis/cdc/basic_test", 3000, 1000).await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_stream_test() {
+        TestBase::run_rdb_redis_stream_cdc_test("mysql_to_redis/cdc/stream_test", 3000, 3000).await;
+    }
 }
@@ -0,0 +1,11 @@
+INSERT INTO test_db_1.tb_1 VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, NULL);
+INSERT INTO test_db_1.tb_2 VALUES (1, 1, 'a'), (2, 2, 'b'), (3, 3, NULL);
+
+UPDATE test_db_1.tb_1 SET value = 'aa' WHERE id = 1;
+UPDATE test_db_1.tb_1 SET value = NULL WHERE id = 2;
+-- update the primary key
+UPDATE test_db_1.tb_1 SET id = 5 WHERE id = 3;
+UPDATE test_db_1.tb_2 SET f_1 = 10, f_2 = 'bb' WHERE id = 2;
+
+DELETE FROM test_db_1.tb_1 WHERE id = 4;
+DELETE FROM test_db_1.tb_2 WHERE id = 1;
@@ -0,0 +1,13 @@
+#[cfg(test)]
+mod test {
+
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_basic_test() {
+        TestBase::run_rdb_sqs_cdc_test("mysql_to_sqs/cdc/basic_test", 3000, 3000).await;
+    }
+}
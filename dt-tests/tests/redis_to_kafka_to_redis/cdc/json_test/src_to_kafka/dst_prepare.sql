@@ -0,0 +1 @@
+create topic test_json
\ No newline at end of file
@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod test {
+
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_basic_test() {
+        TestBase::run_redis_kafka_redis_cdc_test(
+            "redis_to_kafka_to_redis/cdc/basic_test",
+            5000,
+            10000,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_json_test() {
+        TestBase::run_redis_kafka_redis_cdc_test(
+            "redis_to_kafka_to_redis/cdc/json_test",
+            5000,
+            10000,
+        )
+        .await;
+    }
+}
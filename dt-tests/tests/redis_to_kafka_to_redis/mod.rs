@@ -0,0 +1,2 @@
+pub mod cdc_tests;
+pub mod snapshot_tests;
@@ -0,0 +1 @@
+create topic test
\ No newline at end of file
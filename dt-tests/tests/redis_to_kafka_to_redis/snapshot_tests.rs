@@ -0,0 +1,18 @@
+#[cfg(test)]
+mod test {
+
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_basic_test() {
+        TestBase::run_redis_kafka_redis_snapshot_test(
+            "redis_to_kafka_to_redis/snapshot/basic_test",
+            5000,
+            5000,
+        )
+        .await;
+    }
+}
@@ -0,0 +1,21 @@
+SET user:1 a
+SET user:2 b
+SET user:tmp:1 c
+SET order:1 d
+SET item:1 e
+HSET user:3 field1 "hello" field2 "world"
+RPUSH user:4 "Hello"
+XADD user:5 1526919030474-55 message "Hello,"
+
+-- multi-key commands with filtered keys
+MSET user:6 f order:2 g user:7 h
+MSET {user}:8 i {user}:tmp:2 j
+
+SELECT 1
+SET user:1 a
+SET order:1 b
+
+SELECT 0
+DEL user:2 order:1
+SET user:tmp:3 k
+XADD user:5 1526919030474-56 message "World,"
@@ -0,0 +1,19 @@
+SET user:1 a
+SET order:1 b
+HSET user:2 field1 "hello" field2 "world"
+RPUSH user:3 "Hello"
+SADD user:4 "Hello"
+ZADD user:5 0 a 1 b 2 c
+XADD user:6 1526919030474-55 message "Hello,"
+SET user:7 c EX 1000
+
+-- multi-key commands
+MSET user:8 d order:2 e
+RENAME user:1 user:9
+DEL user:8 order:1
+
+SELECT 1
+SET user:1 a
+
+SELECT 2
+SET user:1 a
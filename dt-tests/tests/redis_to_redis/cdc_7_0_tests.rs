@@ -34,12 +34,30 @@ mod test {
         TestBase::run_redis_cdc_test("redis_to_redis/cdc/7_0/filter_db_test", 2000, 3000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_filter_keys_test() {
+        TestBase::run_redis_cdc_test("redis_to_redis/cdc/7_0/filter_keys_test", 2000, 3000).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_route_keys_test() {
+        TestBase::run_redis_cdc_test("redis_to_redis/cdc/7_0/route_keys_test", 2000, 3000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_to_cluster_test() {
         TestBase::run_redis_cdc_test("redis_to_redis/cdc/7_0/to_cluster_test", 2000, 3000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_from_cluster_test() {
+        TestBase::run_redis_cdc_test("redis_to_redis/cdc/7_0/from_cluster_test", 3000, 3000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_heartbeat_test() {
@@ -0,0 +1,13 @@
+SET k_same v
+SET k_miss v
+SET k_diff v1
+SET k_type v
+HSET h_diff f1 v1 f2 v2 f3 v3
+SADD s_diff m1 m2 m3
+ZADD z_diff 1 m1 2 m2
+RPUSH l_diff a b c
+XADD x_diff 1526919030474-55 message "Hello,"
+
+SELECT 1
+SET k_same_1 v
+SET k_miss_1 v
@@ -8,7 +8,10 @@ pub mod cdc_cross_version_tests;
 pub mod cdc_rebloom_tests;
 pub mod cdc_redisearch_tests;
 pub mod cdc_rejson_tests;
+pub mod check_7_0_tests;
 pub mod precheck_tests;
+pub mod review_7_0_tests;
+pub mod revise_7_0_tests;
 pub mod snapshot_2_8_tests;
 pub mod snapshot_4_0_tests;
 pub mod snapshot_5_0_tests;
@@ -0,0 +1,13 @@
+flushall
+
+SET k_same v
+SET k_diff v2
+HSET k_type f v
+HSET h_diff f1 v1 f2 x
+SADD s_diff m1 m2 m4
+ZADD z_diff 1 m1 3 m2
+RPUSH l_diff a x c d
+XADD x_diff 1526919030474-55 message "Hi,"
+
+SELECT 1
+SET k_same_1 v
@@ -0,0 +1,9 @@
+flushall
+
+SET k_diff v2
+HSET h_big f1 v1 f2 x f3 v3 f4 v4
+SADD s_big m1 m2 m3 m4 m6
+ZADD z_big 1 m1 2 m2 3 m3 4 m4 6 m5
+RPUSH l_big a b c x e f
+RPUSH l_ttl a b c d e
+PEXPIRE l_ttl 1000000
@@ -0,0 +1,18 @@
+#[cfg(test)]
+mod test {
+
+    use crate::test_runner::test_base::TestBase;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn review_basic_test() {
+        TestBase::run_redis_review_test("redis_to_redis/review/7_0/basic_test").await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn review_big_key_test() {
+        TestBase::run_redis_review_test("redis_to_redis/review/7_0/big_key_test").await;
+    }
+}
@@ -0,0 +1,7 @@
+SET k_diff v1
+HSET h_big f1 v1 f2 v2 f3 v3 f4 v4 f5 v5
+SADD s_big m1 m2 m3 m4 m5
+ZADD z_big 1 m1 2 m2 3 m3 4 m4 5 m5
+RPUSH l_big a b c d e
+RPUSH l_ttl a b c d e
+PEXPIRE l_ttl 1000000
@@ -0,0 +1,18 @@
+#[cfg(test)]
+mod test {
+
+    use crate::test_runner::test_base::TestBase;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn revise_basic_test() {
+        TestBase::run_redis_revise_test("redis_to_redis/revise/7_0/basic_test").await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn revise_big_key_test() {
+        TestBase::run_redis_revise_test("redis_to_redis/revise/7_0/big_key_test").await;
+    }
+}
@@ -0,0 +1,15 @@
+-- same data as appendonlydir, flushall in aof is filtered by ignore_cmds
+SET k0 v0
+RPUSH list_key a b c
+HSET hash_key f1 v1 f2 v2
+SET k1 v1
+SADD set_key m1 m2
+
+SELECT 1
+
+SET k2 v2
+ZADD zset_key 1 a 2 b
+
+SELECT 0
+
+DEL k1
@@ -0,0 +1,42 @@
+-------------------- string entries
+-- SET
+SET set_key_1 val_1
+SET set_key_2_中文 val_2_中文
+SET "set_key_3_  😀" "val_2_  😀"
+
+-- MSET, keys with the same hash tag are in the same slot
+MSET {mset}_key_1 val_1 {mset}_key_2_中文 val_2_中文 "{mset}_key_3_  😀" "val_3_  😀"
+
+-------------------- hash entries
+-- HSET
+HSET hset_key_1 field_1 val_1
+HSET hset_key_1 field_2_中文 val_2_中文
+HSET hset_key_1 "field_3_  😀" "val_3_  😀"
+
+-------------------- list entries
+-- LPUSH
+LPUSH list_key_1 val_1
+LPUSH list_key_1 val_2_中文
+LPUSH list_key_1 "val_3_  😀"
+
+-- RPUSH
+RPUSH list_key_1 val_5 val_6
+
+-------------------- sets entries
+-- SADD
+SADD sets_key_1 val_1 val_2_中文 "val_3_  😀" val_5
+
+-- SREM
+SREM sets_key_1 val_5
+
+-------------------- zset entries
+-- ZADD
+ZADD zset_key_1 1 val_1 2 val_2_中文 3 "val_3_  😀"
+ZINCRBY zset_key_1 5 val_1
+
+-------------------- stream entries
+-- XADD
+XADD stream_key_1 * field_1 val_1 field_2_中文 val_2_中文 "field_3_  😀" "val_3_  😀"
+
+-------------------- expire
+SET expire_key_1 val_1 EX 100000
@@ -0,0 +1,6 @@
+flushall
+
+RPUSH 2-1 x y z
+SADD 3-1 x y z
+HSET 4-1 f1 x f8 v8
+ZADD 5-1 100 a 8 h
@@ -0,0 +1,23 @@
+-- string
+SET 1-1 abcdefghijklmnopqrstuvwxyz
+SET 1-2 abcdefghijklmnopqrstuvwxyz EX 100000
+
+-- list
+RPUSH 2-1 a b c d e f g h i j
+RPUSH 2-2 a b c d e f g h i j
+EXPIRE 2-2 100000
+
+-- set
+SADD 3-1 a b c d e f g h i j
+SADD 3-2 1 2 3 4 5 6 7 8 9 10
+EXPIRE 3-2 100000
+
+-- hash
+HSET 4-1 f1 v1 f2 v2 f3 v3 f4 v4 f5 v5 f6 v6 f7 v7
+HSET 4-2 f1 v1 f2 v2 f3 v3 f4 v4 f5 v5 f6 v6 f7 v7
+EXPIRE 4-2 100000
+
+-- zset
+ZADD 5-1 1 a 2 b 3 c 4 d 5 e 6 f 7 g
+ZADD 5-2 1.5 a 2.5 b 3.5 c 4.5 d 5.5 e 6.5 f 7.5 g
+EXPIRE 5-2 100000
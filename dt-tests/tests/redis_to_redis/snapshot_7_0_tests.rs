@@ -16,6 +16,12 @@ mod test {
         TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/multi_dbs_test").await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_aof_file_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/aof_file_test").await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_cmds_test() {
@@ -71,6 +77,19 @@ mod test {
         TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/rewrite_test").await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_rewrite_chunk_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/rewrite_chunk_test").await;
+    }
+
+    // big keys are rewritten in chunks instead of restore
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_restore_big_key_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/restore_big_key_test").await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_rewrite_stream_test() {
@@ -83,6 +102,12 @@ mod test {
         TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/filter_db_test").await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_filter_keys_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/filter_keys_test").await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_statistic_big_key_rdb_test() {
@@ -111,4 +136,10 @@ mod test {
     async fn snapshot_to_cluster_test() {
         TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/to_cluster_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_from_cluster_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/from_cluster_test").await;
+    }
 }
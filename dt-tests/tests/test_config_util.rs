@@ -123,7 +123,8 @@ impl TestConfigUtil {
         match config.extractor {
             ExtractorConfig::MysqlCheck { check_log_dir, .. }
             | ExtractorConfig::PgCheck { check_log_dir, .. }
-            | ExtractorConfig::MongoCheck { check_log_dir, .. } => {
+            | ExtractorConfig::MongoCheck { check_log_dir, .. }
+            | ExtractorConfig::RedisCheck { check_log_dir, .. } => {
                 let extractor_check_log_dir = format!("{}/{}", project_root, check_log_dir);
                 update_configs.push((
                     EXTRACTOR.to_string(),
@@ -137,6 +138,11 @@ impl TestConfigUtil {
                 update_configs.push((EXTRACTOR.to_string(), "file_path".to_string(), file_path));
             }
 
+            ExtractorConfig::RedisAofFile { aof_dir } => {
+                let aof_dir = format!("{}/{}", project_root, aof_dir);
+                update_configs.push((EXTRACTOR.to_string(), "aof_dir".to_string(), aof_dir));
+            }
+
             _ => {}
         }
 
@@ -144,7 +150,8 @@ impl TestConfigUtil {
             // sinker/check_log_dir
             SinkerConfig::MysqlCheck { check_log_dir, .. }
             | SinkerConfig::PgCheck { check_log_dir, .. }
-            | SinkerConfig::MongoCheck { check_log_dir, .. } => {
+            | SinkerConfig::MongoCheck { check_log_dir, .. }
+            | SinkerConfig::RedisCheck { check_log_dir, .. } => {
                 let sinker_check_log_dir = if !check_log_dir.is_empty() {
                     format!("{}/{}", project_root, check_log_dir)
                 } else {
@@ -173,6 +180,11 @@ impl TestConfigUtil {
                 ));
             }
 
+            SinkerConfig::RedisSnapshotFile { file_path, .. } => {
+                let file_path = format!("{}/{}", project_root, file_path);
+                update_configs.push((SINKER.to_string(), "file_path".to_string(), file_path));
+            }
+
             _ => {}
         }
 
@@ -63,7 +63,8 @@ impl CheckUtil {
         let dst_check_log_dir = match base_test_runner.get_config().sinker {
             SinkerConfig::MysqlCheck { check_log_dir, .. }
             | SinkerConfig::PgCheck { check_log_dir, .. }
-            | SinkerConfig::MongoCheck { check_log_dir, .. } => check_log_dir.clone(),
+            | SinkerConfig::MongoCheck { check_log_dir, .. }
+            | SinkerConfig::RedisCheck { check_log_dir, .. } => check_log_dir.clone(),
             _ => String::new(),
         };
         (expect_check_log_dir, dst_check_log_dir)
@@ -1,6 +1,7 @@
 pub mod base_test_runner;
 pub mod check_util;
 pub mod mongo_check_test_runner;
+pub mod mongo_cycle_test_runner;
 pub mod mongo_test_runner;
 pub mod precheck_test_runner;
 pub mod rdb_check_test_runner;
@@ -8,14 +9,19 @@ pub mod rdb_clickhouse_test_runner;
 pub mod rdb_cycle_test_runner;
 pub mod rdb_kafka_rdb_test_runner;
 pub mod rdb_lua_test_runner;
+pub mod rdb_mongo_test_runner;
+pub mod rdb_rabbitmq_test_runner;
 pub mod rdb_redis_test_runner;
 pub mod rdb_sql_test_runner;
+pub mod rdb_sqs_test_runner;
 pub mod rdb_starrocks_test_runner;
 pub mod rdb_struct_test_runner;
 pub mod rdb_test_runner;
 pub mod rdb_util;
+pub mod redis_check_test_runner;
 pub mod redis_cluster_connection;
 pub mod redis_cycle_test_runner;
+pub mod redis_kafka_redis_test_runner;
 pub mod redis_statistic_runner;
 pub mod redis_test_runner;
 pub mod redis_test_util;
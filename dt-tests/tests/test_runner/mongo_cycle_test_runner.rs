@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use dt_common::utils::time_util::TimeUtil;
+use dt_connector::data_marker::DataMarker;
+use mongodb::bson::Bson;
+use tokio::task::JoinHandle;
+
+use crate::test_config_util::TestConfigUtil;
+
+use super::{
+    mongo_test_runner::{MongoTestRunner, DST},
+    rdb_cycle_test_runner::RdbCycleTestRunner,
+};
+
+pub struct MongoCycleTestRunner {
+    base: MongoTestRunner,
+}
+
+impl MongoCycleTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            base: MongoTestRunner::new(relative_test_dir).await?,
+        })
+    }
+
+    pub async fn run_cycle_cdc_test(
+        test_dir: &str,
+        start_millis: u64,
+        parse_millis: u64,
+        tx_check_data: &Vec<(&str, &str, &str, &str)>,
+    ) {
+        // HashMap<(src_node, dst_node, data_origin_node), expect_tx_count>
+        let expect_tx_count_map = RdbCycleTestRunner::build_expect_tx_count_map(tx_check_data);
+
+        let sub_paths = TestConfigUtil::get_absolute_sub_dir(test_dir);
+        let mut handlers: Vec<JoinHandle<()>> = vec![];
+        let mut runner_map: HashMap<String, MongoCycleTestRunner> = HashMap::new();
+
+        // execute all prepare sqls
+        for sub_path in &sub_paths {
+            let runner = Self::new(format!("{}/{}", test_dir, sub_path.1).as_str())
+                .await
+                .unwrap();
+            runner.base.execute_prepare_sqls().await.unwrap();
+            runner_map.insert(sub_path.1.to_owned(), runner);
+        }
+
+        // start all sub tasks
+        for sub_path in &sub_paths {
+            let runner = runner_map.get(sub_path.1.as_str()).unwrap();
+            handlers.push(runner.base.base.spawn_task().await.unwrap());
+            TimeUtil::sleep_millis(start_millis).await;
+        }
+
+        // execute all test sqls
+        for sub_path in &sub_paths {
+            let runner = runner_map.get(sub_path.1.as_str()).unwrap();
+            runner.base.execute_test_sqls().await.unwrap();
+        }
+        TimeUtil::sleep_millis(parse_millis).await;
+
+        // do check
+        for sub_path in &sub_paths {
+            let runner = runner_map.get(sub_path.1.as_str()).unwrap();
+            let data_marker = runner.get_data_marker();
+
+            let tmp_expect_tx_count_map: HashMap<(String, String, String), u8> =
+                expect_tx_count_map
+                    .clone()
+                    .into_iter()
+                    .filter(|i| i.0 .0 == data_marker.src_node && i.0 .1 == data_marker.dst_node)
+                    .collect();
+
+            runner.check_cycle_cdc_data(&tmp_expect_tx_count_map).await;
+        }
+
+        for handler in handlers {
+            handler.abort();
+            while !handler.is_finished() {
+                TimeUtil::sleep_millis(1).await;
+            }
+        }
+    }
+
+    async fn check_cycle_cdc_data(
+        &self,
+        // HashMap<(src_node, dst_node, data_origin_node), expect_tx_count>
+        expect_tx_count_map: &HashMap<(String, String, String), u8>,
+    ) {
+        let src_sqls = MongoTestRunner::slice_sqls_by_db(&self.base.base.src_prepare_sqls);
+        for db in src_sqls.keys() {
+            self.base.compare_db_data(db).await;
+        }
+
+        self.check_data_marker_data(expect_tx_count_map).await
+    }
+
+    async fn check_data_marker_data(
+        &self,
+        // HashMap<(src_node, dst_node, data_origin_node), expect_tx_count>
+        expect_tx_count_map: &HashMap<(String, String, String), u8>,
+    ) {
+        let data_marker = self.get_data_marker();
+        let result = self
+            .base
+            .fetch_docs(&data_marker.marker_schema, &data_marker.marker_tb, DST)
+            .await;
+
+        // { _id: { data_origin_node: "", src_node: "", dst_node: "" }, n: 1 }
+        for doc in result.iter() {
+            let id = doc.get_document("_id").unwrap();
+            let src_node = id.get_str("src_node").unwrap().to_string();
+            let dst_node = id.get_str("dst_node").unwrap().to_string();
+            let data_origin_node = id.get_str("data_origin_node").unwrap().to_string();
+            let tx_count = match doc.get("n") {
+                Some(Bson::Int32(n)) => *n as u8,
+                Some(Bson::Int64(n)) => *n as u8,
+                _ => 0,
+            };
+
+            if src_node != data_marker.src_node || dst_node != data_marker.dst_node {
+                continue;
+            }
+
+            println!(
+                "src_node: {}, dst_node: {}, data_origin_node: {}, tx_count: {}",
+                src_node, dst_node, data_origin_node, tx_count
+            );
+
+            assert_eq!(
+                tx_count,
+                *expect_tx_count_map
+                    .get(&(src_node, dst_node, data_origin_node))
+                    .unwrap()
+            );
+        }
+    }
+
+    fn get_data_marker(&self) -> DataMarker {
+        self.base.base.get_data_marker().unwrap()
+    }
+}
@@ -369,7 +369,7 @@ impl MongoTestRunner {
         Ok(())
     }
 
-    async fn compare_db_data(&self, db: &str) {
+    pub async fn compare_db_data(&self, db: &str) {
         let tbs = self.list_tb(db, SRC).await;
         for tb in tbs.iter() {
             self.compare_tb_data(db, tb).await;
@@ -409,6 +409,15 @@ impl MongoTestRunner {
     }
 
     pub async fn fetch_data(&self, db: &str, tb: &str, from: &str) -> HashMap<MongoKey, Document> {
+        let mut results = HashMap::new();
+        for doc in self.fetch_docs(db, tb, from).await {
+            let id = MongoKey::from_doc(&doc).unwrap();
+            results.insert(id, doc);
+        }
+        results
+    }
+
+    pub async fn fetch_docs(&self, db: &str, tb: &str, from: &str) -> Vec<Document> {
         let client = if from == SRC {
             self.src_mongo_client.as_ref().unwrap()
         } else {
@@ -421,16 +430,14 @@ impl MongoTestRunner {
             .build();
         let mut cursor = collection.find(None, find_options).await.unwrap();
 
-        let mut results = HashMap::new();
+        let mut results = Vec::new();
         while cursor.advance().await.unwrap() {
-            let doc = cursor.deserialize_current().unwrap();
-            let id = MongoKey::from_doc(&doc).unwrap();
-            results.insert(id, doc);
+            results.push(cursor.deserialize_current().unwrap());
         }
         results
     }
 
-    fn slice_sqls_by_db(sqls: &[String]) -> HashMap<String, Vec<String>> {
+    pub fn slice_sqls_by_db(sqls: &[String]) -> HashMap<String, Vec<String>> {
         let mut db = String::new();
         let mut sliced_sqls: HashMap<String, Vec<String>> = HashMap::new();
         for sql in sqls.iter() {
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use crate::test_config_util::TestConfigUtil;
 
@@ -128,15 +128,25 @@ impl RdbKafkaRdbTestRunner {
     }
 
     async fn prepare_kafka(&self) -> anyhow::Result<()> {
-        let mut topics: Vec<&str> = vec![];
-        for sql in self.src_to_kafka_runner.dst_prepare_sqls.iter() {
-            let re = Regex::new(r"create topic ([\w\W]+)").unwrap();
+        Self::prepare_kafka_topics(&self.src_to_kafka_runner).await
+    }
+
+    /// recreate topics in dst_prepare.sql of the src -> kafka task
+    pub async fn prepare_kafka_topics(src_to_kafka_runner: &BaseTestRunner) -> anyhow::Result<()> {
+        // create topic test, or create topic test partitions 3
+        let mut topics: Vec<(&str, i32)> = vec![];
+        for sql in src_to_kafka_runner.dst_prepare_sqls.iter() {
+            let re = Regex::new(r"create topic ([\w\.\-]+)(?: partitions (\d+))?").unwrap();
             let cap = re.captures(sql).unwrap();
-            topics.push(cap.get(1).unwrap().as_str());
+            let partitions = cap.get(2).map(|i| i.as_str().parse().unwrap()).unwrap_or(1);
+            topics.push((cap.get(1).unwrap().as_str(), partitions));
         }
 
-        let config = TaskConfig::new(&self.src_to_kafka_runner.task_config_file).unwrap();
-        if let SinkerConfig::Kafka { url, .. } = config.sinker {
+        let config = TaskConfig::new(&src_to_kafka_runner.task_config_file).unwrap();
+        if let SinkerConfig::Kafka {
+            url, properties, ..
+        } = config.sinker
+        {
             let check_topic_exist = |meta: &Metadata, topic: &str| -> bool {
                 for exist_topic in meta.topics() {
                     if exist_topic.name() == topic && !exist_topic.partitions().is_empty() {
@@ -146,9 +156,9 @@ impl RdbKafkaRdbTestRunner {
                 false
             };
 
-            let admin_client = Self::create_kafka_admin_client(&url);
-            let consumer: BaseConsumer = Self::create_kafka_base_consumer(&url);
-            for topic in topics.iter() {
+            let admin_client = Self::create_kafka_admin_client(&url, &properties);
+            let consumer: BaseConsumer = Self::create_kafka_base_consumer(&url, &properties);
+            for (topic, partitions) in topics.iter() {
                 // delete_topic/create_topic may fail
                 let mut meta = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
                 while check_topic_exist(&meta, topic) {
@@ -158,7 +168,7 @@ impl RdbKafkaRdbTestRunner {
                 }
 
                 while !check_topic_exist(&meta, topic) {
-                    Self::create_topic(&admin_client, topic).await;
+                    Self::create_topic(&admin_client, topic, *partitions).await;
                     meta = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
                     TimeUtil::sleep_millis(100).await;
                     println!("kafka topic: [{}] is NOT ready", topic);
@@ -168,22 +178,35 @@ impl RdbKafkaRdbTestRunner {
         Ok(())
     }
 
-    fn create_kafka_admin_client(url: &str) -> AdminClient<DefaultClientContext> {
+    fn create_kafka_admin_client(
+        url: &str,
+        properties: &HashMap<String, String>,
+    ) -> AdminClient<DefaultClientContext> {
         let mut config = ClientConfig::new();
         config.set("bootstrap.servers", url);
         config.set("session.timeout.ms", "10000");
+        for (key, value) in properties.iter() {
+            config.set(key, value);
+        }
         config.create().unwrap()
     }
 
-    fn create_kafka_base_consumer(url: &str) -> BaseConsumer {
+    fn create_kafka_base_consumer(url: &str, properties: &HashMap<String, String>) -> BaseConsumer {
         let mut config = ClientConfig::new();
         config.set("bootstrap.servers", url);
         config.set("session.timeout.ms", "10000");
+        for (key, value) in properties.iter() {
+            config.set(key, value);
+        }
         config.create().unwrap()
     }
 
-    async fn create_topic(client: &AdminClient<DefaultClientContext>, topic: &str) {
-        let topic = NewTopic::new(topic, 1, TopicReplication::Fixed(1));
+    async fn create_topic(
+        client: &AdminClient<DefaultClientContext>,
+        topic: &str,
+        partitions: i32,
+    ) {
+        let topic = NewTopic::new(topic, partitions, TopicReplication::Fixed(1));
         client
             .create_topics(&[topic], &AdminOptions::new())
             .await
@@ -0,0 +1,142 @@
+use anyhow::bail;
+use dt_common::{
+    config::{config_enums::DbType, sinker_config::SinkerConfig, task_config::TaskConfig},
+    error::Error,
+    meta::{
+        adaptor::mongo_col_value_convertor::MongoColValueConvertor,
+        mongo::mongo_constant::MongoConstants,
+    },
+    utils::time_util::TimeUtil,
+};
+use dt_task::task_util::TaskUtil;
+use mongodb::{
+    bson::{Bson, Document},
+    Client,
+};
+use sqlx::{MySql, Pool};
+
+use super::{base_test_runner::BaseTestRunner, rdb_test_runner::RdbTestRunner, rdb_util::RdbUtil};
+
+pub struct RdbMongoTestRunner {
+    pub base: BaseTestRunner,
+    mysql_conn_pool: Pool<MySql>,
+    mongo_client: Client,
+}
+
+impl RdbMongoTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let base = BaseTestRunner::new(relative_test_dir).await.unwrap();
+        let config = TaskConfig::new(&base.task_config_file).unwrap();
+
+        let mysql_conn_pool =
+            TaskUtil::create_mysql_conn_pool(&config.extractor_basic.url, 1, false).await?;
+        let mongo_client = match config.sinker {
+            SinkerConfig::Mongo { url, app_name, .. } => {
+                TaskUtil::create_mongo_client(&url, &app_name).await?
+            }
+            _ => {
+                bail! {Error::ConfigError("unsupported sinker config".into())};
+            }
+        };
+
+        Ok(Self {
+            base,
+            mysql_conn_pool,
+            mongo_client,
+        })
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.mysql_conn_pool.close().await;
+        Ok(())
+    }
+
+    pub async fn run_snapshot_test(&self) -> anyhow::Result<()> {
+        let db_tbs = self.prepare().await?;
+        RdbUtil::execute_sqls_mysql(&self.mysql_conn_pool, &self.base.src_test_sqls).await?;
+        self.base.start_task().await?;
+        self.compare_data_for_tbs(&db_tbs).await
+    }
+
+    pub async fn run_cdc_test(&self, start_millis: u64, parse_millis: u64) -> anyhow::Result<()> {
+        let db_tbs = self.prepare().await?;
+        let task = self.base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        // src dml
+        let mut src_insert_sqls = Vec::new();
+        let mut src_update_sqls = Vec::new();
+        let mut src_delete_sqls = Vec::new();
+        for sql in self.base.src_test_sqls.iter() {
+            if sql.to_lowercase().starts_with("insert") {
+                src_insert_sqls.push(sql.clone());
+            } else if sql.to_lowercase().starts_with("update") {
+                src_update_sqls.push(sql.clone());
+            } else {
+                src_delete_sqls.push(sql.clone());
+            }
+        }
+
+        for sqls in [src_insert_sqls, src_update_sqls, src_delete_sqls] {
+            RdbUtil::execute_sqls_mysql(&self.mysql_conn_pool, &sqls).await?;
+            TimeUtil::sleep_millis(parse_millis).await;
+            self.compare_data_for_tbs(&db_tbs).await?;
+        }
+
+        self.base.abort_task(&task).await
+    }
+
+    async fn prepare(&self) -> anyhow::Result<Vec<(String, String)>> {
+        RdbUtil::execute_sqls_mysql(&self.mysql_conn_pool, &self.base.src_prepare_sqls).await?;
+        let db_tbs = RdbTestRunner::get_compare_db_tbs_from_sqls(
+            &DbType::Mysql,
+            &self.base.src_prepare_sqls,
+        )?;
+        for (db, _) in db_tbs.iter() {
+            self.mongo_client.database(db).drop(None).await?;
+        }
+        Ok(db_tbs)
+    }
+
+    async fn compare_data_for_tbs(&self, db_tbs: &[(String, String)]) -> anyhow::Result<()> {
+        for db_tb in db_tbs {
+            let tb_meta = RdbUtil::get_tb_meta_mysql(&self.mysql_conn_pool, db_tb).await?;
+            let src_data =
+                RdbUtil::fetch_data_mysql(&self.mysql_conn_pool, None, db_tb, "").await?;
+
+            let collection = self
+                .mongo_client
+                .database(&db_tb.0)
+                .collection::<Document>(&db_tb.1);
+            assert_eq!(
+                collection.count_documents(None, None).await? as usize,
+                src_data.len()
+            );
+
+            for row_data in src_data {
+                let after = row_data.after.unwrap();
+                let id = if tb_meta.basic.id_cols.len() == 1 {
+                    MongoColValueConvertor::to_bson(&after[&tb_meta.basic.id_cols[0]])
+                } else {
+                    let mut id_doc = Document::new();
+                    for col in tb_meta.basic.id_cols.iter() {
+                        id_doc.insert(col, MongoColValueConvertor::to_bson(&after[col]));
+                    }
+                    Bson::Document(id_doc)
+                };
+
+                let mut filter = Document::new();
+                filter.insert(MongoConstants::ID, id);
+                let dst_doc = collection.find_one(filter, None).await?.unwrap();
+                for (col, src_v) in after.iter() {
+                    let expected = MongoColValueConvertor::to_bson(src_v);
+                    if dst_doc.get(col) != Some(&expected) {
+                        println!("compare db: {}, tb: {}, col: {}", db_tb.0, db_tb.1, col);
+                    }
+                    assert_eq!(dst_doc.get(col), Some(&expected));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::bail;
+use dt_common::{
+    config::{config_enums::DbType, sinker_config::SinkerConfig, task_config::TaskConfig},
+    error::Error,
+    meta::{avro::avro_converter::AvroConverter, dt_data::DtData},
+    utils::time_util::TimeUtil,
+};
+use dt_task::task_util::TaskUtil;
+use lapin::{
+    options::{
+        BasicGetOptions, ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions,
+        QueueDeleteOptions,
+    },
+    types::FieldTable,
+    Channel, Connection, ConnectionProperties, ExchangeKind,
+};
+use sqlx::{MySql, Pool};
+
+use super::{base_test_runner::BaseTestRunner, rdb_test_runner::RdbTestRunner, rdb_util::RdbUtil};
+
+const TEST_QUEUE: &str = "ape_dts_test_queue";
+const PERSISTENT_DELIVERY_MODE: u8 = 2;
+
+pub struct RdbRabbitMqTestRunner {
+    pub base: BaseTestRunner,
+    mysql_conn_pool: Pool<MySql>,
+    conn: Connection,
+    channel: Channel,
+    exchange: String,
+    routing_key: String,
+}
+
+impl RdbRabbitMqTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let base = BaseTestRunner::new(relative_test_dir).await.unwrap();
+        let config = TaskConfig::new(&base.task_config_file).unwrap();
+
+        let mysql_conn_pool =
+            TaskUtil::create_mysql_conn_pool(&config.extractor_basic.url, 1, false).await?;
+        let (url, exchange, routing_key) = match config.sinker {
+            SinkerConfig::RabbitMq {
+                url,
+                exchange,
+                routing_key,
+                ..
+            } => (url, exchange, routing_key),
+            _ => {
+                bail! {Error::ConfigError("unsupported sinker config".into())};
+            }
+        };
+        let conn = Connection::connect(&url, ConnectionProperties::default()).await?;
+        let channel = conn.create_channel().await?;
+
+        Ok(Self {
+            base,
+            mysql_conn_pool,
+            conn,
+            channel,
+            exchange,
+            routing_key,
+        })
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.mysql_conn_pool.close().await;
+        self.conn.close(200, "OK").await?;
+        Ok(())
+    }
+
+    /// Consumes all messages published to the exchange by a queue bound with #,
+    /// replays them and compares the results with the source tables
+    pub async fn run_cdc_test(
+        &mut self,
+        start_millis: u64,
+        parse_millis: u64,
+    ) -> anyhow::Result<()> {
+        RdbUtil::execute_sqls_mysql(&self.mysql_conn_pool, &self.base.src_prepare_sqls).await?;
+        self.prepare_queue().await?;
+
+        let task = self.base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        RdbUtil::execute_sqls_mysql(&self.mysql_conn_pool, &self.base.src_test_sqls).await?;
+        TimeUtil::sleep_millis(parse_millis).await;
+
+        let avro_converter = AvroConverter::new(None, false);
+        let mut payloads = Vec::new();
+        while let Some(message) = self
+            .channel
+            .basic_get(TEST_QUEUE, BasicGetOptions { no_ack: true })
+            .await?
+        {
+            let delivery = message.delivery;
+            assert_eq!(
+                delivery.properties.delivery_mode(),
+                &Some(PERSISTENT_DELIVERY_MODE)
+            );
+
+            if let DtData::Dml { row_data } =
+                avro_converter.avro_value_to_dt_data(delivery.data.clone())?
+            {
+                let routing_key = self
+                    .routing_key
+                    .replace("{schema}", &row_data.schema)
+                    .replace("{tb}", &row_data.tb)
+                    .replace("{operation}", &row_data.row_type.to_string());
+                assert_eq!(delivery.routing_key.as_str(), routing_key);
+            }
+            payloads.push(delivery.data);
+        }
+
+        let db_tbs = RdbTestRunner::get_compare_db_tbs_from_sqls(
+            &DbType::Mysql,
+            &self.base.src_prepare_sqls,
+        )?;
+        for db_tb in db_tbs.iter() {
+            assert!(
+                RdbUtil::compare_avro_messages_mysql(
+                    &self.mysql_conn_pool,
+                    db_tb,
+                    payloads.clone()
+                )
+                .await?
+            );
+        }
+
+        self.base.abort_task(&task).await
+    }
+
+    async fn prepare_queue(&self) -> anyhow::Result<()> {
+        let durable_exchange = ExchangeDeclareOptions {
+            durable: true,
+            ..Default::default()
+        };
+        self.channel
+            .exchange_declare(
+                &self.exchange,
+                ExchangeKind::Topic,
+                durable_exchange,
+                FieldTable::default(),
+            )
+            .await?;
+
+        self.channel
+            .queue_delete(TEST_QUEUE, QueueDeleteOptions::default())
+            .await?;
+        let durable_queue = QueueDeclareOptions {
+            durable: true,
+            ..Default::default()
+        };
+        self.channel
+            .queue_declare(TEST_QUEUE, durable_queue, FieldTable::default())
+            .await?;
+        self.channel
+            .queue_bind(
+                TEST_QUEUE,
+                &self.exchange,
+                "#",
+                QueueBindOptions::default(),
+                FieldTable::default(),
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -1,11 +1,15 @@
+use std::str::FromStr;
+
 use anyhow::bail;
 use dt_common::config::config_enums::DbType;
+use dt_common::meta::position::Position;
 use dt_common::utils::redis_util::RedisUtil;
 use dt_common::{
     config::{sinker_config::SinkerConfig, task_config::TaskConfig},
     error::Error,
     utils::time_util::TimeUtil,
 };
+use dt_connector::rdb_router::RdbRouter;
 use dt_task::task_util::TaskUtil;
 use redis::{Connection, Value};
 use sqlx::{MySql, Pool};
@@ -32,7 +36,9 @@ impl RdbRedisTestRunner {
         let mysql_conn_pool =
             Some(TaskUtil::create_mysql_conn_pool(&config.extractor_basic.url, 1, false).await?);
         let redis_conn = match config.sinker {
-            SinkerConfig::Redis { url, .. } => RedisUtil::create_redis_conn(&url).await.unwrap(),
+            SinkerConfig::Redis { url, .. } | SinkerConfig::RedisStream { url, .. } => {
+                RedisUtil::create_redis_conn(&url).await.unwrap()
+            }
             _ => {
                 bail! {Error::ConfigError("unsupported sinker config".into())};
             }
@@ -121,6 +127,76 @@ impl RdbRedisTestRunner {
         self.base.abort_task(&task).await
     }
 
+    /// Replays the change events in streams and compares the results with the source tables
+    pub async fn run_stream_cdc_test(
+        &mut self,
+        start_millis: u64,
+        parse_millis: u64,
+    ) -> anyhow::Result<()> {
+        self.execute_sqls(&self.base.src_prepare_sqls).await?;
+        self.redis_util
+            .execute_cmds(&mut self.redis_conn, &self.base.dst_prepare_sqls.clone());
+
+        let task = self.base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        self.execute_sqls(&self.base.src_test_sqls).await?;
+        TimeUtil::sleep_millis(parse_millis).await;
+
+        let db_tbs = RdbTestRunner::get_compare_db_tbs_from_sqls(
+            &DbType::Mysql,
+            &self.base.src_prepare_sqls,
+        )?;
+        let config = TaskConfig::new(&self.base.task_config_file)?;
+        let router = RdbRouter::from_config(&config.router, &DbType::Mysql)?;
+        for db_tb in db_tbs.iter() {
+            let stream = router.get_topic(&db_tb.0, &db_tb.1).to_string();
+            assert!(self.compare_stream_data(db_tb, &stream).await?);
+        }
+
+        // the checkpoint position is stored
+        if let SinkerConfig::RedisStream { position_key, .. } = &config.sinker {
+            let cmd = format!("GET {}", position_key);
+            match self.redis_util.execute_cmd(&mut self.redis_conn, &cmd) {
+                Value::BulkString(v) => {
+                    let position = Position::from_str(&String::from_utf8(v)?)?;
+                    assert_ne!(position, Position::None);
+                }
+                _ => panic!("position not stored in key: {}", position_key),
+            }
+        }
+
+        self.base.abort_task(&task).await
+    }
+
+    async fn compare_stream_data(
+        &mut self,
+        db_tb: &(String, String),
+        stream: &str,
+    ) -> anyhow::Result<bool> {
+        // entries: [id, [key, <key>, value, <payload>]]
+        let mut payloads = Vec::new();
+        let cmd = format!("XRANGE {} - +", stream);
+        if let Value::Array(entries) = self.redis_util.execute_cmd(&mut self.redis_conn, &cmd) {
+            for entry in entries {
+                let payload = match entry {
+                    Value::Array(mut v) if v.len() == 2 => match v.pop() {
+                        Some(Value::Array(mut fields)) if fields.len() == 4 => fields.pop(),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match payload {
+                    Some(Value::BulkString(payload)) => payloads.push(payload),
+                    _ => bail! {Error::Unexpected(format!("invalid entry in stream: {}", stream))},
+                }
+            }
+        }
+
+        let conn_pool = self.mysql_conn_pool.as_ref().unwrap();
+        RdbUtil::compare_avro_messages_mysql(conn_pool, db_tb, payloads).await
+    }
+
     async fn execute_sqls(&self, sqls: &Vec<String>) -> anyhow::Result<()> {
         if let Some(conn_pool) = &self.mysql_conn_pool {
             RdbUtil::execute_sqls_mysql(conn_pool, sqls).await?;
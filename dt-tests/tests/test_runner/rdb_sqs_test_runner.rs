@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use dt_common::{
+    config::{config_enums::DbType, sinker_config::SinkerConfig, task_config::TaskConfig},
+    error::Error,
+    utils::time_util::TimeUtil,
+};
+use dt_task::task_util::TaskUtil;
+use rusoto_sqs::{
+    CreateQueueRequest, DeleteMessageRequest, PurgeQueueRequest, ReceiveMessageRequest, Sqs,
+    SqsClient,
+};
+use sqlx::{MySql, Pool};
+
+use super::{base_test_runner::BaseTestRunner, rdb_test_runner::RdbTestRunner, rdb_util::RdbUtil};
+
+const MESSAGE_GROUP_ID: &str = "MessageGroupId";
+
+pub struct RdbSqsTestRunner {
+    pub base: BaseTestRunner,
+    mysql_conn_pool: Pool<MySql>,
+    client: SqsClient,
+    queue_url: String,
+}
+
+impl RdbSqsTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let base = BaseTestRunner::new(relative_test_dir).await.unwrap();
+        let config = TaskConfig::new(&base.task_config_file).unwrap();
+
+        let mysql_conn_pool =
+            TaskUtil::create_mysql_conn_pool(&config.extractor_basic.url, 1, false).await?;
+        let (client, queue_url) = match config.sinker {
+            SinkerConfig::Sqs {
+                url,
+                region,
+                endpoint,
+                access_key,
+                secret_key,
+                ..
+            } => (
+                TaskUtil::create_sqs_client(&region, &endpoint, &access_key, &secret_key)?,
+                url,
+            ),
+            _ => {
+                bail! {Error::ConfigError("unsupported sinker config".into())};
+            }
+        };
+
+        Ok(Self {
+            base,
+            mysql_conn_pool,
+            client,
+            queue_url,
+        })
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.mysql_conn_pool.close().await;
+        Ok(())
+    }
+
+    /// Receives all messages sent to the queue, replays them and compares the results
+    /// with the source tables
+    pub async fn run_cdc_test(
+        &mut self,
+        start_millis: u64,
+        parse_millis: u64,
+    ) -> anyhow::Result<()> {
+        RdbUtil::execute_sqls_mysql(&self.mysql_conn_pool, &self.base.src_prepare_sqls).await?;
+        self.prepare_queue().await?;
+
+        let task = self.base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        RdbUtil::execute_sqls_mysql(&self.mysql_conn_pool, &self.base.src_test_sqls).await?;
+        TimeUtil::sleep_millis(parse_millis).await;
+
+        let mut payloads = Vec::new();
+        loop {
+            let request = ReceiveMessageRequest {
+                queue_url: self.queue_url.clone(),
+                max_number_of_messages: Some(10),
+                wait_time_seconds: Some(1),
+                attribute_names: Some(vec![MESSAGE_GROUP_ID.into()]),
+                ..Default::default()
+            };
+            let messages = self
+                .client
+                .receive_message(request)
+                .await?
+                .messages
+                .unwrap_or_default();
+            if messages.is_empty() {
+                break;
+            }
+
+            for message in messages {
+                if self.is_fifo() {
+                    let group_id = message
+                        .attributes
+                        .as_ref()
+                        .and_then(|i| i.get(MESSAGE_GROUP_ID));
+                    assert!(group_id.is_some_and(|i| !i.is_empty()));
+                }
+                payloads.push(STANDARD.decode(message.body.unwrap_or_default())?);
+
+                // messages of a FIFO group are not received until the previous ones are deleted
+                let request = DeleteMessageRequest {
+                    queue_url: self.queue_url.clone(),
+                    receipt_handle: message.receipt_handle.unwrap_or_default(),
+                };
+                self.client.delete_message(request).await?;
+            }
+        }
+
+        let db_tbs = RdbTestRunner::get_compare_db_tbs_from_sqls(
+            &DbType::Mysql,
+            &self.base.src_prepare_sqls,
+        )?;
+        for db_tb in db_tbs.iter() {
+            assert!(
+                RdbUtil::compare_avro_messages_mysql(
+                    &self.mysql_conn_pool,
+                    db_tb,
+                    payloads.clone()
+                )
+                .await?
+            );
+        }
+
+        self.base.abort_task(&task).await
+    }
+
+    async fn prepare_queue(&self) -> anyhow::Result<()> {
+        let queue_name = self.queue_url.rsplit('/').next().unwrap_or_default();
+        let mut attributes = HashMap::new();
+        if self.is_fifo() {
+            attributes.insert("FifoQueue".to_string(), "true".to_string());
+        }
+        let request = CreateQueueRequest {
+            queue_name: queue_name.to_string(),
+            attributes: Some(attributes),
+            ..Default::default()
+        };
+        self.client.create_queue(request).await?;
+
+        let request = PurgeQueueRequest {
+            queue_url: self.queue_url.clone(),
+        };
+        self.client.purge_queue(request).await?;
+        Ok(())
+    }
+
+    fn is_fifo(&self) -> bool {
+        self.queue_url.ends_with(".fifo")
+    }
+}
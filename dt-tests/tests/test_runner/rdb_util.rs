@@ -1,10 +1,14 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use dt_common::config::config_enums::DbType;
 use dt_common::meta::{
+    avro::avro_converter::AvroConverter,
+    col_value::ColValue,
+    dt_data::DtData,
     mysql::{mysql_meta_manager::MysqlMetaManager, mysql_tb_meta::MysqlTbMeta},
     pg::{pg_meta_manager::PgMetaManager, pg_tb_meta::PgTbMeta},
     row_data::RowData,
+    row_type::RowType,
 };
 use dt_connector::rdb_query_builder::RdbQueryBuilder;
 use futures::TryStreamExt;
@@ -78,6 +82,60 @@ impl RdbUtil {
         Ok(result)
     }
 
+    /// Replays the avro messages of rows in order and compares the results with the table in mysql,
+    /// messages of other tables are skipped
+    pub async fn compare_avro_messages_mysql(
+        conn_pool: &Pool<MySql>,
+        db_tb: &(String, String),
+        payloads: Vec<Vec<u8>>,
+    ) -> anyhow::Result<bool> {
+        let tb_meta = Self::get_tb_meta_mysql(conn_pool, db_tb).await?;
+        let key_col = tb_meta.basic.order_col.as_ref().unwrap();
+        let get_key = |col_values: &Option<HashMap<String, ColValue>>| {
+            col_values
+                .as_ref()
+                .and_then(|i| i.get(key_col))
+                .and_then(|v| v.to_option_string())
+                .unwrap()
+        };
+
+        let avro_converter = AvroConverter::new(None, false);
+        let mut replayed_rows = HashMap::new();
+        for payload in payloads {
+            let row_data = match avro_converter.avro_value_to_dt_data(payload)? {
+                DtData::Dml { row_data } => row_data,
+                _ => continue,
+            };
+            if row_data.schema != db_tb.0 || row_data.tb != db_tb.1 {
+                continue;
+            }
+            match row_data.row_type {
+                RowType::Insert => {
+                    replayed_rows.insert(get_key(&row_data.after), row_data.after);
+                }
+                RowType::Update => {
+                    replayed_rows.remove(&get_key(&row_data.before));
+                    replayed_rows.insert(get_key(&row_data.after), row_data.after);
+                }
+                RowType::Delete => {
+                    replayed_rows.remove(&get_key(&row_data.before));
+                }
+            }
+        }
+
+        let src_rows = Self::fetch_data_mysql(conn_pool, None, db_tb, "").await?;
+        assert_eq!(src_rows.len(), replayed_rows.len());
+        for src_row in src_rows {
+            let key = get_key(&src_row.after);
+            let replayed_row = replayed_rows.get(&key).unwrap().as_ref().unwrap();
+            for (col, src_v) in src_row.after.unwrap() {
+                let replayed_v = replayed_row.get(&col).and_then(|v| v.to_option_string());
+                assert_eq!(src_v.to_option_string(), replayed_v);
+            }
+        }
+        Ok(true)
+    }
+
     pub async fn get_tb_meta_mysql(
         conn_pool: &Pool<MySql>,
         db_tb: &(String, String),
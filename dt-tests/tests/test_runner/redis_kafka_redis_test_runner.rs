@@ -0,0 +1,118 @@
+use crate::test_config_util::TestConfigUtil;
+
+use super::base_test_runner::BaseTestRunner;
+use super::rdb_kafka_rdb_test_runner::RdbKafkaRdbTestRunner;
+use super::redis_test_runner::RedisTestRunner;
+use dt_common::utils::time_util::TimeUtil;
+
+/// This is used for test cases: redis(src) -> kafka -> redis(dst).
+/// There are 2 tasks running:
+///     redis(src) -> kafka
+///     kafka -> redis(dst)
+/// And we need another dummy task runner to compare redis(src) and redis(dst)
+///     redis(src) -> redis(dst)
+pub struct RedisKafkaRedisTestRunner {
+    src_to_dst_runner: RedisTestRunner,
+    src_to_kafka_runner: BaseTestRunner,
+    kafka_to_dst_runners: Vec<BaseTestRunner>,
+}
+
+impl RedisKafkaRedisTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let src_to_dst_runner =
+            RedisTestRunner::new_default(&format!("{}/src_to_dst", relative_test_dir)).await?;
+        let src_to_kafka_runner =
+            BaseTestRunner::new(&format!("{}/src_to_kafka", relative_test_dir)).await?;
+
+        let mut kafka_to_dst_runners = Vec::new();
+        let sub_paths =
+            TestConfigUtil::get_absolute_sub_dir(&format!("{}/kafka_to_dst", relative_test_dir));
+        for sub_path in &sub_paths {
+            let runner = BaseTestRunner::new(&format!(
+                "{}/kafka_to_dst/{}",
+                relative_test_dir, sub_path.1
+            ))
+            .await?;
+            kafka_to_dst_runners.push(runner);
+        }
+
+        Ok(Self {
+            src_to_dst_runner,
+            src_to_kafka_runner,
+            kafka_to_dst_runners,
+        })
+    }
+
+    pub async fn run_snapshot_test(
+        &mut self,
+        start_millis: u64,
+        parse_millis: u64,
+    ) -> anyhow::Result<()> {
+        self.src_to_dst_runner.execute_prepare_sqls()?;
+        RdbKafkaRdbTestRunner::prepare_kafka_topics(&self.src_to_kafka_runner).await?;
+
+        // prepare src data
+        self.src_to_dst_runner.execute_test_sqls()?;
+
+        // kafka -> dst
+        let mut kafka_to_dst_tasks = Vec::new();
+        for runner in self.kafka_to_dst_runners.iter() {
+            kafka_to_dst_tasks.push(runner.spawn_task().await?);
+        }
+        TimeUtil::sleep_millis(start_millis).await;
+
+        // src -> kafka
+        self.src_to_kafka_runner.start_task().await?;
+        TimeUtil::sleep_millis(parse_millis).await;
+
+        // compare data
+        self.src_to_dst_runner.compare_all_data()?;
+
+        // stop
+        #[allow(clippy::needless_range_loop)]
+        for i in 0..self.kafka_to_dst_runners.len() {
+            self.kafka_to_dst_runners[i]
+                .abort_task(&kafka_to_dst_tasks[i])
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn run_cdc_test(
+        &mut self,
+        start_millis: u64,
+        parse_millis: u64,
+    ) -> anyhow::Result<()> {
+        self.src_to_dst_runner.execute_prepare_sqls()?;
+        RdbKafkaRdbTestRunner::prepare_kafka_topics(&self.src_to_kafka_runner).await?;
+
+        // kafka -> dst
+        let mut kafka_to_dst_tasks = Vec::new();
+        for runner in self.kafka_to_dst_runners.iter() {
+            kafka_to_dst_tasks.push(runner.spawn_task().await?);
+        }
+
+        // src -> kafka
+        let src_to_kafka_task = self.src_to_kafka_runner.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        // execute test cmds and compare
+        self.src_to_dst_runner.execute_test_sqls()?;
+        TimeUtil::sleep_millis(parse_millis).await;
+        self.src_to_dst_runner.compare_all_data()?;
+
+        // stop
+        #[allow(clippy::needless_range_loop)]
+        for i in 0..self.kafka_to_dst_runners.len() {
+            self.kafka_to_dst_runners[i]
+                .abort_task(&kafka_to_dst_tasks[i])
+                .await?;
+        }
+
+        self.src_to_kafka_runner
+            .abort_task(&src_to_kafka_task)
+            .await?;
+        Ok(())
+    }
+}
@@ -11,15 +11,17 @@ use dt_common::{
     rdb_filter::RdbFilter,
     utils::{redis_util::RedisUtil, sql_util::SqlUtil, time_util::TimeUtil},
 };
+use dt_connector::rdb_router::RdbRouter;
 
-use redis::{Connection, Value};
+use redis::Value;
 
 pub struct RedisTestRunner {
     pub base: BaseTestRunner,
-    src_conn: Connection,
+    src_conn: RedisClusterConnection,
     dst_conn: RedisClusterConnection,
     redis_util: RedisTestUtil,
     filter: RdbFilter,
+    router: RdbRouter,
 }
 
 impl RedisTestRunner {
@@ -35,8 +37,20 @@ impl RedisTestRunner {
 
         let config = TaskConfig::new(&base.task_config_file).unwrap();
         let src_conn = match config.extractor {
-            ExtractorConfig::RedisSnapshot { url, .. } | ExtractorConfig::RedisCdc { url, .. } => {
-                RedisUtil::create_redis_conn(&url).await.unwrap()
+            ExtractorConfig::RedisSnapshot {
+                url, is_cluster, ..
+            }
+            | ExtractorConfig::RedisCdc {
+                url, is_cluster, ..
+            }
+            | ExtractorConfig::RedisCheck {
+                url, is_cluster, ..
+            } => RedisClusterConnection::new(&url, is_cluster).await.unwrap(),
+            // no src server for aof files, the url is where the expected data are built
+            ExtractorConfig::RedisAofFile { .. } => {
+                RedisClusterConnection::new(&config.extractor_basic.url, false)
+                    .await
+                    .unwrap()
             }
             _ => {
                 bail! {Error::ConfigError("unsupported extractor config".into())};
@@ -46,6 +60,9 @@ impl RedisTestRunner {
         let dst_conn = match config.sinker {
             SinkerConfig::Redis {
                 url, is_cluster, ..
+            }
+            | SinkerConfig::RedisCheck {
+                url, is_cluster, ..
             } => RedisClusterConnection::new(&url, is_cluster).await.unwrap(),
             _ => {
                 bail! {Error::ConfigError("unsupported sinker config".into())};
@@ -54,12 +71,14 @@ impl RedisTestRunner {
 
         let redis_util = RedisTestUtil::new(escape_pairs);
         let filter = RdbFilter::from_config(&config.filter, &DbType::Redis)?;
+        let router = RdbRouter::from_config(&config.router, &DbType::Redis)?;
         Ok(Self {
             base,
             src_conn,
             dst_conn,
             redis_util,
             filter,
+            router,
         })
     }
 
@@ -112,26 +131,31 @@ impl RedisTestRunner {
         let key = &heartbeat_db_key[1];
 
         let cmd = format!("SELECT {}", db_id);
-        self.redis_util.execute_cmd(&mut self.src_conn, &cmd);
+        self.redis_util
+            .execute_cmd_in_cluster(&mut self.src_conn, &cmd);
 
         self.execute_prepare_sqls()?;
 
         let cmd = format!("GET {}", self.redis_util.escape_key(key));
-        let result = self.redis_util.execute_cmd(&mut self.src_conn, &cmd);
+        let result = self
+            .redis_util
+            .execute_cmd_in_one_cluster_node(&mut self.src_conn, &cmd);
         assert_eq!(result, Value::Nil);
 
         let task = self.base.spawn_task().await?;
         TimeUtil::sleep_millis(start_millis).await;
         self.base.abort_task(&task).await.unwrap();
 
-        let result = self.redis_util.execute_cmd(&mut self.src_conn, &cmd);
+        let result = self
+            .redis_util
+            .execute_cmd_in_one_cluster_node(&mut self.src_conn, &cmd);
         assert_ne!(result, Value::Nil);
         Ok(())
     }
 
     pub fn execute_prepare_sqls(&mut self) -> anyhow::Result<()> {
         self.redis_util
-            .execute_cmds(&mut self.src_conn, &self.base.src_prepare_sqls.clone());
+            .execute_cmds_in_cluster(&mut self.src_conn, &self.base.src_prepare_sqls.clone());
         self.redis_util
             .execute_cmds_in_cluster(&mut self.dst_conn, &self.base.dst_prepare_sqls.clone());
         Ok(())
@@ -139,16 +163,16 @@ impl RedisTestRunner {
 
     pub fn execute_test_sqls(&mut self) -> anyhow::Result<()> {
         self.redis_util
-            .execute_cmds(&mut self.src_conn, &self.base.src_test_sqls.clone());
+            .execute_cmds_in_cluster(&mut self.src_conn, &self.base.src_test_sqls.clone());
         Ok(())
     }
 
     pub fn compare_all_data(&mut self) -> anyhow::Result<()> {
-        let dbs = if self.dst_conn.is_cluster() {
+        let dbs = if self.src_conn.is_cluster() || self.dst_conn.is_cluster() {
             // a redis cluster strictly supports only database 0
             vec!["0".to_string()]
         } else {
-            self.redis_util.list_dbs(&mut self.src_conn)
+            self.redis_util.list_dbs(self.src_conn.get_default_conn())
         };
         for db in dbs.iter() {
             println!("compare data for db: {}", db);
@@ -159,9 +183,10 @@ impl RedisTestRunner {
 
     fn compare_data(&mut self, db: &str) -> anyhow::Result<()> {
         self.redis_util
-            .execute_cmd(&mut self.src_conn, &format!("SELECT {}", db));
+            .execute_cmd_in_cluster(&mut self.src_conn, &format!("SELECT {}", db));
+        let dst_db = self.router.get_schema_map(db).to_string();
         self.redis_util
-            .execute_cmd_in_cluster(&mut self.dst_conn, &format!("SELECT {}", db));
+            .execute_cmd_in_cluster(&mut self.dst_conn, &format!("SELECT {}", dst_db));
 
         let data_marker_key = if let Some(data_marker) = self.base.get_data_marker() {
             data_marker.marker
@@ -179,8 +204,14 @@ impl RedisTestRunner {
         let mut json_keys = Vec::new();
         let mut bf_bloom_keys = Vec::new();
         let mut cf_bloom_keys = Vec::new();
+        let mut filtered_keys = Vec::new();
+
+        let mut keys = Vec::new();
+        for src_node_conn in self.src_conn.get_node_conns() {
+            keys.extend(self.redis_util.list_keys(src_node_conn, "*"));
+        }
+        keys.sort();
 
-        let keys = self.redis_util.list_keys(&mut self.src_conn, "*");
         for i in keys.iter() {
             let key = i.clone();
 
@@ -188,7 +219,15 @@ impl RedisTestRunner {
                 continue;
             }
 
-            let key_type = self.redis_util.get_key_type(&mut self.src_conn, &key);
+            let src_node_conn = self.src_conn.get_node_conn_by_key(&key);
+            let key_type = self.redis_util.get_key_type(src_node_conn, &key);
+            if self.filter.filter_key(key.as_bytes())
+                || self.filter.filter_key_type(&key_type.to_lowercase())
+            {
+                filtered_keys.push(key);
+                continue;
+            }
+
             match key_type.to_lowercase().as_str() {
                 "string" => string_keys.push(key),
                 "hash" => hash_keys.push(key),
@@ -215,17 +254,45 @@ impl RedisTestRunner {
         self.compare_rejson_entries(db, &json_keys);
         self.compare_bf_bloom_entries(db, &bf_bloom_keys);
         self.compare_cf_bloom_entries(db, &cf_bloom_keys);
+        self.check_filtered_keys(&filtered_keys);
+
+        keys.retain(|key| !filtered_keys.contains(key));
         self.check_expire(&keys);
         Ok(())
     }
 
-    fn check_expire(&mut self, keys: &Vec<String>) {
+    fn check_filtered_keys(&mut self, keys: &Vec<String>) {
         for key in keys {
-            let cmd = format!("PTTL {}", self.redis_util.escape_key(key));
-            let src_result = self.redis_util.execute_cmd(&mut self.src_conn, &cmd);
+            let dst_key = self.get_dst_key(key);
+            let cmd = format!("EXISTS {}", self.redis_util.escape_key(&dst_key));
             let dst_result = self
                 .redis_util
                 .execute_cmd_in_one_cluster_node(&mut self.dst_conn, &cmd);
+            println!("filtered key: {}, dst exists: {:?}", key, dst_result);
+            assert_eq!(dst_result, Value::Int(0));
+        }
+    }
+
+    fn get_dst_key(&self, key: &str) -> String {
+        match self.router.get_key_map(key.as_bytes()) {
+            Some(dst_key) => String::from_utf8(dst_key).unwrap(),
+            None => key.to_string(),
+        }
+    }
+
+    fn check_expire(&mut self, keys: &Vec<String>) {
+        for key in keys {
+            let src_cmd = format!("PTTL {}", self.redis_util.escape_key(key));
+            let dst_cmd = format!(
+                "PTTL {}",
+                self.redis_util.escape_key(&self.get_dst_key(key))
+            );
+            let src_result = self
+                .redis_util
+                .execute_cmd_in_one_cluster_node(&mut self.src_conn, &src_cmd);
+            let dst_result = self
+                .redis_util
+                .execute_cmd_in_one_cluster_node(&mut self.dst_conn, &dst_cmd);
 
             let get_expire = |result: Value| -> i64 {
                 match result {
@@ -253,16 +320,19 @@ impl RedisTestRunner {
 
     fn compare_string_entries(&mut self, db: &str, keys: &Vec<String>) {
         for key in keys {
-            let cmd = format!("GET {}", self.redis_util.escape_key(key));
-            self.compare_cmd_results(&cmd, db, key);
+            let src_cmd = format!("GET {}", self.redis_util.escape_key(key));
+            let dst_cmd = format!("GET {}", self.redis_util.escape_key(&self.get_dst_key(key)));
+            self.compare_cmd_results(&src_cmd, &dst_cmd, db, key);
         }
     }
 
     fn compare_hash_entries(&mut self, db: &str, keys: &Vec<String>) {
         for key in keys {
-            let src_kvs = self.redis_util.get_hash_entry(&mut self.src_conn, key);
-            let dst_node_conn = self.dst_conn.get_node_conn_by_key(key);
-            let dst_kvs = self.redis_util.get_hash_entry(dst_node_conn, key);
+            let src_node_conn = self.src_conn.get_node_conn_by_key(key);
+            let src_kvs = self.redis_util.get_hash_entry(src_node_conn, key);
+            let dst_key = self.get_dst_key(key);
+            let dst_node_conn = self.dst_conn.get_node_conn_by_key(&dst_key);
+            let dst_kvs = self.redis_util.get_hash_entry(dst_node_conn, &dst_key);
             println!(
                 "compare results for hash entries, \r\n src_kvs: {:?} \r\n dst_kvs: {:?}",
                 src_kvs, dst_kvs
@@ -279,61 +349,91 @@ impl RedisTestRunner {
 
     fn compare_list_entries(&mut self, db: &str, keys: &Vec<String>) {
         for key in keys {
-            let cmd = format!("LRANGE {} 0 -1", self.redis_util.escape_key(key));
-            self.compare_cmd_results(&cmd, db, key);
+            let src_cmd = format!("LRANGE {} 0 -1", self.redis_util.escape_key(key));
+            let dst_cmd = format!(
+                "LRANGE {} 0 -1",
+                self.redis_util.escape_key(&self.get_dst_key(key))
+            );
+            self.compare_cmd_results(&src_cmd, &dst_cmd, db, key);
         }
     }
 
     fn compare_set_entries(&mut self, db: &str, keys: &Vec<String>) {
         for key in keys {
-            let cmd = format!("SORT {} ALPHA", self.redis_util.escape_key(key));
-            self.compare_cmd_results(&cmd, db, key);
+            let src_cmd = format!("SORT {} ALPHA", self.redis_util.escape_key(key));
+            let dst_cmd = format!(
+                "SORT {} ALPHA",
+                self.redis_util.escape_key(&self.get_dst_key(key))
+            );
+            self.compare_cmd_results(&src_cmd, &dst_cmd, db, key);
         }
     }
 
     fn compare_zset_entries(&mut self, db: &str, keys: &Vec<String>) {
         for key in keys {
-            let cmd = format!("ZRANGE {} 0 -1 WITHSCORES", self.redis_util.escape_key(key));
-            self.compare_cmd_results(&cmd, db, key);
+            let src_cmd = format!("ZRANGE {} 0 -1 WITHSCORES", self.redis_util.escape_key(key));
+            let dst_cmd = format!(
+                "ZRANGE {} 0 -1 WITHSCORES",
+                self.redis_util.escape_key(&self.get_dst_key(key))
+            );
+            self.compare_cmd_results(&src_cmd, &dst_cmd, db, key);
         }
     }
 
     fn compare_stream_entries(&mut self, db: &str, keys: &Vec<String>) {
         for key in keys {
-            let cmd = format!("XRANGE {} - +", self.redis_util.escape_key(key));
-            self.compare_cmd_results(&cmd, db, key);
+            let src_cmd = format!("XRANGE {} - +", self.redis_util.escape_key(key));
+            let dst_cmd = format!(
+                "XRANGE {} - +",
+                self.redis_util.escape_key(&self.get_dst_key(key))
+            );
+            self.compare_cmd_results(&src_cmd, &dst_cmd, db, key);
         }
     }
 
     fn compare_rejson_entries(&mut self, db: &str, keys: &Vec<String>) {
         for key in keys {
-            let cmd = format!("JSON.GET {}", self.redis_util.escape_key(key));
-            self.compare_cmd_results(&cmd, db, key);
+            let src_cmd = format!("JSON.GET {}", self.redis_util.escape_key(key));
+            let dst_cmd = format!(
+                "JSON.GET {}",
+                self.redis_util.escape_key(&self.get_dst_key(key))
+            );
+            self.compare_cmd_results(&src_cmd, &dst_cmd, db, key);
         }
     }
 
     fn compare_bf_bloom_entries(&mut self, db: &str, keys: &Vec<String>) {
         for key in keys {
-            let cmd = format!("BF.DEBUG {}", self.redis_util.escape_key(key));
-            self.compare_cmd_results(&cmd, db, key);
+            let src_cmd = format!("BF.DEBUG {}", self.redis_util.escape_key(key));
+            let dst_cmd = format!(
+                "BF.DEBUG {}",
+                self.redis_util.escape_key(&self.get_dst_key(key))
+            );
+            self.compare_cmd_results(&src_cmd, &dst_cmd, db, key);
         }
     }
 
     fn compare_cf_bloom_entries(&mut self, db: &str, keys: &Vec<String>) {
         for key in keys {
-            let cmd = format!("CF.DEBUG {}", self.redis_util.escape_key(key));
-            self.compare_cmd_results(&cmd, db, key);
+            let src_cmd = format!("CF.DEBUG {}", self.redis_util.escape_key(key));
+            let dst_cmd = format!(
+                "CF.DEBUG {}",
+                self.redis_util.escape_key(&self.get_dst_key(key))
+            );
+            self.compare_cmd_results(&src_cmd, &dst_cmd, db, key);
         }
     }
 
-    fn compare_cmd_results(&mut self, cmd: &str, db: &str, key: &str) {
-        let src_result = self.redis_util.execute_cmd(&mut self.src_conn, cmd);
+    fn compare_cmd_results(&mut self, src_cmd: &str, dst_cmd: &str, db: &str, key: &str) {
+        let src_result = self
+            .redis_util
+            .execute_cmd_in_one_cluster_node(&mut self.src_conn, src_cmd);
         let dst_result = self
             .redis_util
-            .execute_cmd_in_one_cluster_node(&mut self.dst_conn, cmd);
+            .execute_cmd_in_one_cluster_node(&mut self.dst_conn, dst_cmd);
         println!(
             "compare results for cmd: {}, \r\n src_kvs: {:?} \r\n dst_kvs: {:?}",
-            cmd, src_result, dst_result
+            src_cmd, src_result, dst_result
         );
 
         if self.filter.filter_schema(db) {
@@ -348,9 +448,10 @@ impl RedisTestRunner {
     }
 
     fn print_version_info(&mut self) {
+        let src_node_conn = self.src_conn.get_default_conn();
         println!(
             "src: {}",
-            RedisUtil::get_redis_version(&mut self.src_conn).unwrap()
+            RedisUtil::get_redis_version(src_node_conn).unwrap()
         );
         let dst_node_conn = self.dst_conn.get_default_conn();
         println!(
@@ -10,10 +10,13 @@ use super::{
     mongo_check_test_runner::MongoCheckTestRunner, mongo_test_runner::MongoTestRunner,
     precheck_test_runner::PrecheckTestRunner, rdb_check_test_runner::RdbCheckTestRunner,
     rdb_kafka_rdb_test_runner::RdbKafkaRdbTestRunner, rdb_lua_test_runner::RdbLuaTestRunner,
+    rdb_mongo_test_runner::RdbMongoTestRunner, rdb_rabbitmq_test_runner::RdbRabbitMqTestRunner,
     rdb_redis_test_runner::RdbRedisTestRunner, rdb_sql_test_runner::RdbSqlTestRunner,
-    rdb_starrocks_test_runner::RdbStarRocksTestRunner, rdb_struct_test_runner::RdbStructTestRunner,
-    rdb_test_runner::RdbTestRunner, redis_statistic_runner::RedisStatisticTestRunner,
-    redis_test_runner::RedisTestRunner,
+    rdb_sqs_test_runner::RdbSqsTestRunner, rdb_starrocks_test_runner::RdbStarRocksTestRunner,
+    rdb_struct_test_runner::RdbStructTestRunner, rdb_test_runner::RdbTestRunner,
+    redis_check_test_runner::RedisCheckTestRunner,
+    redis_kafka_redis_test_runner::RedisKafkaRedisTestRunner,
+    redis_statistic_runner::RedisStatisticTestRunner, redis_test_runner::RedisTestRunner,
 };
 
 pub struct TestBase {}
@@ -239,6 +242,21 @@ impl TestBase {
             .unwrap();
     }
 
+    pub async fn run_redis_check_test(test_dir: &str) {
+        let mut runner = RedisCheckTestRunner::new(test_dir).await.unwrap();
+        runner.run_check_test().await.unwrap();
+    }
+
+    pub async fn run_redis_revise_test(test_dir: &str) {
+        let mut runner = RedisCheckTestRunner::new(test_dir).await.unwrap();
+        runner.run_revise_test().await.unwrap();
+    }
+
+    pub async fn run_redis_review_test(test_dir: &str) {
+        let mut runner = RedisCheckTestRunner::new(test_dir).await.unwrap();
+        runner.run_review_test().await.unwrap();
+    }
+
     pub async fn run_redis_statistic_test(test_dir: &str) {
         let mut runner = RedisStatisticTestRunner::new(test_dir).await.unwrap();
         runner.run_statistic_test().await.unwrap();
@@ -295,6 +313,30 @@ impl TestBase {
             .unwrap();
     }
 
+    pub async fn run_redis_kafka_redis_cdc_test(
+        test_dir: &str,
+        start_millis: u64,
+        parse_millis: u64,
+    ) {
+        let mut runner = RedisKafkaRedisTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+    }
+
+    pub async fn run_redis_kafka_redis_snapshot_test(
+        test_dir: &str,
+        start_millis: u64,
+        parse_millis: u64,
+    ) {
+        let mut runner = RedisKafkaRedisTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_snapshot_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+    }
+
     pub async fn run_rdb_redis_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
         let mut runner = RdbRedisTestRunner::new(test_dir).await.unwrap();
         runner
@@ -304,12 +346,58 @@ impl TestBase {
         runner.close().await.unwrap();
     }
 
+    pub async fn run_rdb_rabbitmq_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let mut runner = RdbRabbitMqTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
+
+    pub async fn run_rdb_sqs_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let mut runner = RdbSqsTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
+
+    pub async fn run_rdb_redis_stream_cdc_test(
+        test_dir: &str,
+        start_millis: u64,
+        parse_millis: u64,
+    ) {
+        let mut runner = RdbRedisTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_stream_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
+
     pub async fn run_rdb_redis_snapshot_test(test_dir: &str) {
         let mut runner = RdbRedisTestRunner::new(test_dir).await.unwrap();
         runner.run_snapshot_test().await.unwrap();
         runner.close().await.unwrap();
     }
 
+    pub async fn run_rdb_mongo_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let runner = RdbMongoTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
+
+    pub async fn run_rdb_mongo_snapshot_test(test_dir: &str) {
+        let runner = RdbMongoTestRunner::new(test_dir).await.unwrap();
+        runner.run_snapshot_test().await.unwrap();
+        runner.close().await.unwrap();
+    }
+
     pub async fn run_rdb_starrocks_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
         let runner = RdbStarRocksTestRunner::new(test_dir).await.unwrap();
         runner